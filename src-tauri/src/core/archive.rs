@@ -0,0 +1,1137 @@
+use flate2::read::DeflateDecoder;
+use serde::Serialize;
+use sha1::{Digest, Sha1};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::{Component, Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Files the launcher creates while importing; never reported by the mods watcher.
+const LAUNCHER_TEMP_FILE_PREFIX: &str = ".epherome-";
+const IMPORT_TEMP_CREATE_ATTEMPTS: usize = 128;
+pub const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+const MAX_ZIP_CENTRAL_DIRECTORY_SIZE: u64 = 32 * 1024 * 1024;
+const MAX_ZIP_ENTRIES: u64 = 100_000;
+const MAX_ZIP64_EXTENSIBLE_DATA_SIZE: u64 = 1024 * 1024;
+pub const ZIP_EOCD_MIN_SIZE: u64 = 22;
+const ZIP_EOCD_MAX_SEARCH: u64 = ZIP_EOCD_MIN_SIZE + u16::MAX as u64;
+pub const ZIP_CENTRAL_DIRECTORY_HEADER_SIZE: u64 = 46;
+pub const ZIP_LOCAL_FILE_HEADER_SIZE: u64 = 30;
+const ZIP_TARGET_PATH_BUFFER_SIZE: usize = 512;
+const ZIP_ENCRYPTION_FLAGS: u16 = 0x2041;
+const ZIP64_EXTRA_FIELD_ID: u16 = 0x0001;
+
+static IMPORT_TEMP_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportFailure {
+    pub filename: String,
+    pub reason: String,
+}
+
+/// Files copied into an instance folder, and the ones that were rejected.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportResult {
+    pub imported: Vec<String>,
+    pub failed: Vec<ImportFailure>,
+}
+
+pub fn validate_single_filename(filename: &str) -> Result<(), String> {
+    if filename.is_empty() || filename == "." || filename == ".." {
+        return Err("The filename is invalid.".to_string());
+    }
+
+    if filename.contains('/') || filename.contains('\\') {
+        return Err("The filename must not contain path separators.".to_string());
+    }
+
+    let mut components = Path::new(filename).components();
+    if !matches!(components.next(), Some(Component::Normal(_))) || components.next().is_some() {
+        return Err("The filename must be a single file name.".to_string());
+    }
+
+    Ok(())
+}
+
+pub fn path_exists(path: &Path) -> Result<bool, String> {
+    match fs::symlink_metadata(path) {
+        Ok(_) => Ok(true),
+        Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(false),
+        Err(error) => Err(format!(
+            "Failed to check destination '{}': {}",
+            path.display(),
+            error
+        )),
+    }
+}
+
+#[cfg(any(target_os = "linux", target_os = "android", target_vendor = "apple"))]
+pub fn rename_without_overwrite(source: &Path, destination: &Path) -> Result<(), io::Error> {
+    rustix::fs::renameat_with(
+        rustix::fs::CWD,
+        source,
+        rustix::fs::CWD,
+        destination,
+        rustix::fs::RenameFlags::NOREPLACE,
+    )
+    .map_err(io::Error::from)
+}
+
+#[cfg(target_os = "windows")]
+pub fn rename_without_overwrite(source: &Path, destination: &Path) -> Result<(), io::Error> {
+    // Windows rename semantics reject an existing destination.
+    fs::rename(source, destination)
+}
+
+#[cfg(not(any(
+    target_os = "linux",
+    target_os = "android",
+    target_vendor = "apple",
+    target_os = "windows"
+)))]
+pub fn rename_without_overwrite(source: &Path, destination: &Path) -> Result<(), io::Error> {
+    match fs::symlink_metadata(destination) {
+        Ok(_) => {
+            return Err(io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                "Destination already exists.",
+            ));
+        }
+        Err(error) if error.kind() == io::ErrorKind::NotFound => {}
+        Err(error) => return Err(error),
+    }
+
+    fs::rename(source, destination)
+}
+
+pub fn create_import_temp_file(directory: &Path) -> Result<(PathBuf, File), String> {
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos();
+
+    for _ in 0..IMPORT_TEMP_CREATE_ATTEMPTS {
+        let counter = IMPORT_TEMP_COUNTER.fetch_add(1, Ordering::Relaxed);
+        let filename = format!(
+            ".epherome-mod-import-{}-{timestamp}-{counter}.tmp",
+            std::process::id()
+        );
+        let path = directory.join(filename);
+
+        match OpenOptions::new().write(true).create_new(true).open(&path) {
+            Ok(file) => return Ok((path, file)),
+            Err(error) if error.kind() == io::ErrorKind::AlreadyExists => continue,
+            Err(error) => {
+                return Err(format!(
+                    "Failed to create temporary import file in '{}': {}",
+                    directory.display(),
+                    error
+                ));
+            }
+        }
+    }
+
+    Err(format!(
+        "Failed to create a unique temporary import file in '{}'.",
+        directory.display()
+    ))
+}
+
+pub fn remove_file_if_exists(path: &Path) -> Result<(), io::Error> {
+    match fs::remove_file(path) {
+        Ok(()) => Ok(()),
+        Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(()),
+        Err(error) => Err(error),
+    }
+}
+
+pub fn cleanup_import_failure(temp_path: &Path, reason: String) -> String {
+    match remove_file_if_exists(temp_path) {
+        Ok(()) => reason,
+        Err(cleanup_error) => format!(
+            "{reason} Also failed to remove temporary file '{}': {cleanup_error}",
+            temp_path.display()
+        ),
+    }
+}
+
+pub fn publish_import_temp(
+    temp_path: &Path,
+    destination: &Path,
+    filename: &str,
+) -> Result<(), String> {
+    if let Err(error) = rename_without_overwrite(temp_path, destination) {
+        let reason = if error.kind() == io::ErrorKind::AlreadyExists {
+            format!("A file named '{filename}' was installed while the import was in progress.")
+        } else {
+            format!(
+                "Failed to atomically install '{filename}' without overwriting another file: {error}"
+            )
+        };
+        return Err(cleanup_import_failure(temp_path, reason));
+    }
+
+    Ok(())
+}
+
+/// Replace a launcher-owned file so readers never observe a partial write.
+pub fn write_file_atomically(path: &Path, bytes: &[u8]) -> Result<(), String> {
+    let mut temp_name = path.as_os_str().to_owned();
+    temp_name.push(".tmp");
+    let temp_path = PathBuf::from(temp_name);
+
+    let write_result = (|| {
+        let mut file = File::create(&temp_path)?;
+        file.write_all(bytes)?;
+        file.sync_all()?;
+        fs::rename(&temp_path, path)
+    })();
+    write_result.map_err(|error| {
+        let _ = remove_file_if_exists(&temp_path);
+        format!("Failed to write '{}': {error}", path.display())
+    })
+}
+
+pub fn is_launcher_temp_filename(filename: &str) -> bool {
+    filename.starts_with(LAUNCHER_TEMP_FILE_PREFIX)
+}
+
+pub fn hex_digest(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+pub fn sha1_reader_hex(reader: &mut impl Read) -> Result<String, String> {
+    let mut hasher = Sha1::new();
+    let mut buffer = [0_u8; 64 * 1024];
+    loop {
+        let read = reader
+            .read(&mut buffer)
+            .map_err(|error| format!("Failed to hash the JAR: {error}"))?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    Ok(hex_digest(&hasher.finalize()))
+}
+
+fn little_endian_u16(bytes: &[u8], offset: usize) -> Option<u16> {
+    let bytes = bytes.get(offset..offset + 2)?;
+    Some(u16::from_le_bytes([bytes[0], bytes[1]]))
+}
+
+fn little_endian_u32(bytes: &[u8], offset: usize) -> Option<u32> {
+    let bytes = bytes.get(offset..offset + 4)?;
+    Some(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+fn little_endian_u64(bytes: &[u8], offset: usize) -> Option<u64> {
+    let bytes = bytes.get(offset..offset + 8)?;
+    Some(u64::from_le_bytes([
+        bytes[0], bytes[1], bytes[2], bytes[3], bytes[4], bytes[5], bytes[6], bytes[7],
+    ]))
+}
+
+fn read_file_range(
+    file: &mut (impl Read + Seek),
+    offset: u64,
+    length: usize,
+) -> Result<Vec<u8>, String> {
+    file.seek(SeekFrom::Start(offset))
+        .map_err(|error| format!("Failed to seek in the JAR: {error}"))?;
+    let mut bytes = vec![0; length];
+    file.read_exact(&mut bytes)
+        .map_err(|error| format!("Failed to read the JAR directory: {error}"))?;
+    Ok(bytes)
+}
+
+pub struct ZipDirectorySummary {
+    entry_count: u64,
+    declared_size: u64,
+    offset: u64,
+    end_structure_offset: u64,
+}
+
+#[derive(Clone, Copy)]
+pub struct ZipTargetEntry {
+    flags: u16,
+    compression_method: u16,
+    compressed_size: u64,
+    uncompressed_size: u64,
+    local_header_offset: u64,
+}
+
+pub enum ZipTargetRecord {
+    Entry(ZipTargetEntry),
+    Invalid(String),
+}
+
+fn zip_directory_summary(file: &mut (impl Read + Seek)) -> Result<ZipDirectorySummary, String> {
+    let file_size = file
+        .seek(SeekFrom::End(0))
+        .map_err(|error| format!("Failed to inspect the JAR size: {error}"))?;
+    if file_size < ZIP_EOCD_MIN_SIZE {
+        return Err("The JAR is too small to contain a ZIP directory.".to_string());
+    }
+
+    let search_length = file_size.min(ZIP_EOCD_MAX_SEARCH);
+    let tail = read_file_range(
+        file,
+        file_size - search_length,
+        usize::try_from(search_length)
+            .map_err(|_| "The ZIP directory search range is too large.".to_string())?,
+    )?;
+    let end_record_index = (0..=tail.len() - ZIP_EOCD_MIN_SIZE as usize)
+        .rev()
+        .find(|index| {
+            if tail.get(*index..*index + 4) != Some(b"PK\x05\x06") {
+                return false;
+            }
+            let Some(comment_length) = little_endian_u16(&tail, *index + 20) else {
+                return false;
+            };
+            *index + ZIP_EOCD_MIN_SIZE as usize + comment_length as usize == tail.len()
+        })
+        .ok_or_else(|| "The JAR does not contain a valid ZIP end record.".to_string())?;
+
+    let entry_count = little_endian_u16(&tail, end_record_index + 10)
+        .ok_or_else(|| "The ZIP end record is incomplete.".to_string())?;
+    let central_directory_size = little_endian_u32(&tail, end_record_index + 12)
+        .ok_or_else(|| "The ZIP end record is incomplete.".to_string())?;
+    let central_directory_offset = little_endian_u32(&tail, end_record_index + 16)
+        .ok_or_else(|| "The ZIP end record is incomplete.".to_string())?;
+    let end_record_offset = file_size - search_length + end_record_index as u64;
+
+    if entry_count != u16::MAX
+        && central_directory_size != u32::MAX
+        && central_directory_offset != u32::MAX
+    {
+        return Ok(ZipDirectorySummary {
+            entry_count: entry_count as u64,
+            declared_size: central_directory_size as u64,
+            offset: central_directory_offset as u64,
+            end_structure_offset: end_record_offset,
+        });
+    }
+
+    let locator_offset = end_record_offset
+        .checked_sub(20)
+        .ok_or_else(|| "The ZIP64 locator is missing.".to_string())?;
+    let locator = read_file_range(file, locator_offset, 20)?;
+    if locator.get(0..4) != Some(b"PK\x06\x07") {
+        return Err("The ZIP64 locator is missing or invalid.".to_string());
+    }
+    let zip64_end_offset = little_endian_u64(&locator, 8)
+        .ok_or_else(|| "The ZIP64 locator is incomplete.".to_string())?;
+    let zip64_end = read_file_range(file, zip64_end_offset, 56)?;
+    if zip64_end.get(0..4) != Some(b"PK\x06\x06") {
+        return Err("The ZIP64 end record is missing or invalid.".to_string());
+    }
+    let zip64_record_size = little_endian_u64(&zip64_end, 4)
+        .ok_or_else(|| "The ZIP64 end record is incomplete.".to_string())?;
+    if zip64_record_size < 44 {
+        return Err("The ZIP64 end record is too short.".to_string());
+    }
+    if zip64_record_size - 44 > MAX_ZIP64_EXTENSIBLE_DATA_SIZE {
+        return Err(format!(
+            "The ZIP64 extensible data sector is {} bytes; the inspection limit is {MAX_ZIP64_EXTENSIBLE_DATA_SIZE} bytes.",
+            zip64_record_size - 44
+        ));
+    }
+    let zip64_end_record_end = zip64_end_offset
+        .checked_add(12)
+        .and_then(|offset| offset.checked_add(zip64_record_size))
+        .ok_or_else(|| "The ZIP64 end record size overflows the JAR.".to_string())?;
+    if zip64_end_record_end > locator_offset {
+        return Err("The ZIP64 end record overlaps its locator.".to_string());
+    }
+
+    let entry_count = little_endian_u64(&zip64_end, 32)
+        .ok_or_else(|| "The ZIP64 entry count is missing.".to_string())?;
+    let central_directory_size = little_endian_u64(&zip64_end, 40)
+        .ok_or_else(|| "The ZIP64 directory size is missing.".to_string())?;
+    let central_directory_offset = little_endian_u64(&zip64_end, 48)
+        .ok_or_else(|| "The ZIP64 directory offset is missing.".to_string())?;
+    Ok(ZipDirectorySummary {
+        entry_count,
+        declared_size: central_directory_size,
+        offset: central_directory_offset,
+        end_structure_offset: zip64_end_offset,
+    })
+}
+
+fn read_zip64_target_entry(
+    file: &mut (impl Read + Seek),
+    extra_offset: u64,
+    extra_length: u64,
+    compressed_size_32: u32,
+    uncompressed_size_32: u32,
+    local_header_offset_32: u32,
+    disk_start_16: u16,
+) -> Result<(u64, u64, u64), String> {
+    let needs_uncompressed_size = uncompressed_size_32 == u32::MAX;
+    let needs_compressed_size = compressed_size_32 == u32::MAX;
+    let needs_local_header_offset = local_header_offset_32 == u32::MAX;
+    let needs_disk_start = disk_start_16 == u16::MAX;
+    if !needs_uncompressed_size
+        && !needs_compressed_size
+        && !needs_local_header_offset
+        && !needs_disk_start
+    {
+        if disk_start_16 != 0 {
+            return Err("Multi-disk ZIP entries are not supported.".to_string());
+        }
+        return Ok((
+            compressed_size_32 as u64,
+            uncompressed_size_32 as u64,
+            local_header_offset_32 as u64,
+        ));
+    }
+
+    let extra_end = extra_offset
+        .checked_add(extra_length)
+        .ok_or_else(|| "The ZIP extra field range overflows the JAR.".to_string())?;
+    let mut position = extra_offset;
+    let mut extra_header = [0_u8; 4];
+    while position < extra_end {
+        let header_end = position
+            .checked_add(extra_header.len() as u64)
+            .ok_or_else(|| "A ZIP extra field header offset overflows the JAR.".to_string())?;
+        if header_end > extra_end {
+            return Err("A ZIP extra field header is truncated.".to_string());
+        }
+
+        file.seek(SeekFrom::Start(position))
+            .map_err(|error| format!("Failed to seek to a ZIP extra field: {error}"))?;
+        file.read_exact(&mut extra_header)
+            .map_err(|error| format!("Failed to read a ZIP extra field header: {error}"))?;
+        let field_id = little_endian_u16(&extra_header, 0)
+            .ok_or_else(|| "A ZIP extra field ID is missing.".to_string())?;
+        let field_size = little_endian_u16(&extra_header, 2)
+            .ok_or_else(|| "A ZIP extra field size is missing.".to_string())?
+            as u64;
+        let data_offset = header_end;
+        let field_end = data_offset
+            .checked_add(field_size)
+            .ok_or_else(|| "A ZIP extra field size overflows the JAR.".to_string())?;
+        if field_end > extra_end {
+            return Err("A ZIP extra field is truncated.".to_string());
+        }
+
+        if field_id == ZIP64_EXTRA_FIELD_ID {
+            let required_size = usize::from(needs_disk_start) * 4
+                + (usize::from(needs_uncompressed_size)
+                    + usize::from(needs_compressed_size)
+                    + usize::from(needs_local_header_offset))
+                    * 8;
+            if field_size
+                < u64::try_from(required_size)
+                    .map_err(|_| "The ZIP64 extra field size overflows.".to_string())?
+            {
+                return Err("The ZIP64 extra field is missing required values.".to_string());
+            }
+
+            let mut values = [0_u8; 28];
+            file.seek(SeekFrom::Start(data_offset))
+                .map_err(|error| format!("Failed to seek to the ZIP64 extra field: {error}"))?;
+            file.read_exact(&mut values[..required_size])
+                .map_err(|error| format!("Failed to read the ZIP64 extra field: {error}"))?;
+
+            let mut cursor = 0;
+            let uncompressed_size = if needs_uncompressed_size {
+                let value = little_endian_u64(&values, cursor)
+                    .ok_or_else(|| "The ZIP64 uncompressed size is missing.".to_string())?;
+                cursor += 8;
+                value
+            } else {
+                uncompressed_size_32 as u64
+            };
+            let compressed_size = if needs_compressed_size {
+                let value = little_endian_u64(&values, cursor)
+                    .ok_or_else(|| "The ZIP64 compressed size is missing.".to_string())?;
+                cursor += 8;
+                value
+            } else {
+                compressed_size_32 as u64
+            };
+            let local_header_offset = if needs_local_header_offset {
+                let value = little_endian_u64(&values, cursor)
+                    .ok_or_else(|| "The ZIP64 local header offset is missing.".to_string())?;
+                cursor += 8;
+                value
+            } else {
+                local_header_offset_32 as u64
+            };
+            let disk_start = if needs_disk_start {
+                little_endian_u32(&values, cursor)
+                    .ok_or_else(|| "The ZIP64 disk start is missing.".to_string())?
+            } else {
+                disk_start_16 as u32
+            };
+            if disk_start != 0 {
+                return Err("Multi-disk ZIP entries are not supported.".to_string());
+            }
+
+            return Ok((compressed_size, uncompressed_size, local_header_offset));
+        }
+
+        position = field_end;
+    }
+
+    Err("The central directory entry is missing its ZIP64 extra field.".to_string())
+}
+
+/// Walk the central directory once, recording only the requested paths.
+pub fn collect_central_directory_entries(
+    file: &mut (impl Read + Seek),
+    summary: &ZipDirectorySummary,
+    paths: &[&str],
+) -> Result<Vec<Option<ZipTargetRecord>>, String> {
+    walk_central_directory(file, summary, paths, None)
+}
+
+/// Every entry name in the central directory, in directory order.
+pub fn collect_central_directory_names(
+    file: &mut (impl Read + Seek),
+    summary: &ZipDirectorySummary,
+) -> Result<Vec<ZipEntryName>, String> {
+    let mut names = Vec::new();
+    walk_central_directory(file, summary, &[], Some(&mut names))?;
+    Ok(names)
+}
+
+/// Record the requested paths and, when `names` is given, every entry name on the way.
+fn walk_central_directory(
+    file: &mut (impl Read + Seek),
+    summary: &ZipDirectorySummary,
+    paths: &[&str],
+    mut names: Option<&mut Vec<ZipEntryName>>,
+) -> Result<Vec<Option<ZipTargetRecord>>, String> {
+    if summary.offset > summary.end_structure_offset {
+        return Err("The ZIP directory starts after its end structure.".to_string());
+    }
+
+    let declared_end = summary
+        .offset
+        .checked_add(summary.declared_size)
+        .ok_or_else(|| "The declared ZIP directory range overflows the JAR.".to_string())?;
+    if declared_end > summary.end_structure_offset {
+        return Err("The declared ZIP directory overlaps its end structure.".to_string());
+    }
+
+    let mut position = summary.offset;
+    let mut actual_size = 0_u64;
+    let mut header = [0_u8; ZIP_CENTRAL_DIRECTORY_HEADER_SIZE as usize];
+    let mut targets: Vec<Option<ZipTargetRecord>> = paths.iter().map(|_| None).collect();
+    let mut filename = vec![
+        0_u8;
+        if names.is_some() {
+            ZIP_TARGET_PATH_BUFFER_SIZE
+        } else {
+            paths
+                .iter()
+                .map(|path| path.len())
+                .max()
+                .unwrap_or(0)
+                .min(ZIP_TARGET_PATH_BUFFER_SIZE)
+        }
+    ];
+
+    for index in 0..summary.entry_count {
+        let header_end = position
+            .checked_add(ZIP_CENTRAL_DIRECTORY_HEADER_SIZE)
+            .ok_or_else(|| "A ZIP directory header offset overflows the JAR.".to_string())?;
+        if header_end > summary.end_structure_offset {
+            return Err(format!(
+                "ZIP directory entry {} crosses into the ZIP end structure.",
+                index + 1
+            ));
+        }
+
+        file.seek(SeekFrom::Start(position)).map_err(|error| {
+            format!(
+                "Failed to seek to ZIP directory entry {}: {error}",
+                index + 1
+            )
+        })?;
+        file.read_exact(&mut header).map_err(|error| {
+            format!("Failed to read ZIP directory entry {}: {error}", index + 1)
+        })?;
+        if header.get(0..4) != Some(b"PK\x01\x02") {
+            return Err(format!(
+                "ZIP directory entry {} has an invalid central directory signature.",
+                index + 1
+            ));
+        }
+
+        let filename_length = little_endian_u16(&header, 28)
+            .ok_or_else(|| "The ZIP directory filename length is missing.".to_string())?
+            as u64;
+        let extra_length = little_endian_u16(&header, 30)
+            .ok_or_else(|| "The ZIP directory extra length is missing.".to_string())?
+            as u64;
+        let comment_length = little_endian_u16(&header, 32)
+            .ok_or_else(|| "The ZIP directory comment length is missing.".to_string())?
+            as u64;
+        let entry_size = ZIP_CENTRAL_DIRECTORY_HEADER_SIZE
+            .checked_add(filename_length)
+            .and_then(|size| size.checked_add(extra_length))
+            .and_then(|size| size.checked_add(comment_length))
+            .ok_or_else(|| "A ZIP directory entry size overflows the JAR.".to_string())?;
+        actual_size = actual_size
+            .checked_add(entry_size)
+            .ok_or_else(|| "The actual ZIP directory size overflows the JAR.".to_string())?;
+        if actual_size > MAX_ZIP_CENTRAL_DIRECTORY_SIZE {
+            return Err(format!(
+                "The actual JAR ZIP directory exceeds the {MAX_ZIP_CENTRAL_DIRECTORY_SIZE}-byte inspection limit."
+            ));
+        }
+
+        let entry_end = position
+            .checked_add(entry_size)
+            .ok_or_else(|| "A ZIP directory entry offset overflows the JAR.".to_string())?;
+        if entry_end > summary.end_structure_offset {
+            return Err(format!(
+                "ZIP directory entry {} crosses into the ZIP end structure.",
+                index + 1
+            ));
+        }
+
+        let filename_length_usize = usize::try_from(filename_length)
+            .map_err(|_| "A ZIP directory filename length is too large.".to_string())?;
+        let fits_buffer = filename_length_usize <= ZIP_TARGET_PATH_BUFFER_SIZE;
+        let could_be_target = fits_buffer
+            && paths
+                .iter()
+                .any(|target| target.len() == filename_length_usize);
+        if let Some(names) = names.as_deref_mut().filter(|_| !fits_buffer) {
+            names.push(Err(format!(
+                "ZIP entry {} has a {filename_length}-byte name; the limit is {ZIP_TARGET_PATH_BUFFER_SIZE} bytes.",
+                index + 1
+            )));
+        } else if could_be_target || names.is_some() {
+            file.seek(SeekFrom::Start(header_end))
+                .map_err(|error| format!("Failed to seek to a ZIP filename: {error}"))?;
+            file.read_exact(&mut filename[..filename_length_usize])
+                .map_err(|error| format!("Failed to read a ZIP filename: {error}"))?;
+
+            if let Some(names) = names.as_deref_mut() {
+                let name = &filename[..filename_length_usize];
+                names.push(String::from_utf8(name.to_vec()).map_err(|_| {
+                    format!(
+                        "ZIP entry '{}' has a name that is not valid UTF-8.",
+                        String::from_utf8_lossy(name)
+                    )
+                }));
+            }
+            if let Some(target_index) = paths
+                .iter()
+                .position(|target| target.as_bytes() == &filename[..filename_length_usize])
+            {
+                if targets[target_index].is_some() {
+                    targets[target_index] = Some(ZipTargetRecord::Invalid(format!(
+                        "The ZIP contains ambiguous duplicate '{}' entries.",
+                        paths[target_index]
+                    )));
+                } else {
+                    let flags = little_endian_u16(&header, 8)
+                        .ok_or_else(|| "The ZIP entry flags are missing.".to_string())?;
+                    let compression_method = little_endian_u16(&header, 10)
+                        .ok_or_else(|| "The ZIP compression method is missing.".to_string())?;
+                    let compressed_size = little_endian_u32(&header, 20)
+                        .ok_or_else(|| "The ZIP compressed size is missing.".to_string())?;
+                    let uncompressed_size = little_endian_u32(&header, 24)
+                        .ok_or_else(|| "The ZIP uncompressed size is missing.".to_string())?;
+                    let disk_start = little_endian_u16(&header, 34)
+                        .ok_or_else(|| "The ZIP disk start is missing.".to_string())?;
+                    let local_header_offset = little_endian_u32(&header, 42)
+                        .ok_or_else(|| "The ZIP local header offset is missing.".to_string())?;
+                    let extra_offset = header_end
+                        .checked_add(filename_length)
+                        .ok_or_else(|| "The ZIP extra field offset overflows.".to_string())?;
+                    targets[target_index] = Some(
+                        match read_zip64_target_entry(
+                            file,
+                            extra_offset,
+                            extra_length,
+                            compressed_size,
+                            uncompressed_size,
+                            local_header_offset,
+                            disk_start,
+                        ) {
+                            Ok((compressed_size, uncompressed_size, local_header_offset)) => {
+                                ZipTargetRecord::Entry(ZipTargetEntry {
+                                    flags,
+                                    compression_method,
+                                    compressed_size,
+                                    uncompressed_size,
+                                    local_header_offset,
+                                })
+                            }
+                            Err(error) => ZipTargetRecord::Invalid(error),
+                        },
+                    );
+                }
+            }
+        }
+
+        position = entry_end;
+    }
+
+    if actual_size > summary.declared_size {
+        return Err(format!(
+            "The actual ZIP directory entries occupy {actual_size} bytes, exceeding the declared size of {} bytes.",
+            summary.declared_size
+        ));
+    }
+
+    Ok(targets)
+}
+
+/// Locate the central directory, refusing archives beyond the inspection limits.
+pub fn checked_zip_directory_summary(
+    file: &mut (impl Read + Seek),
+) -> Result<ZipDirectorySummary, String> {
+    let summary = zip_directory_summary(file)?;
+    if summary.entry_count > MAX_ZIP_ENTRIES {
+        return Err(format!(
+            "The JAR contains {} ZIP entries; the inspection limit is {MAX_ZIP_ENTRIES}.",
+            summary.entry_count
+        ));
+    }
+    if summary.declared_size > MAX_ZIP_CENTRAL_DIRECTORY_SIZE {
+        return Err(format!(
+            "The declared JAR ZIP directory is {} bytes; the inspection limit is {MAX_ZIP_CENTRAL_DIRECTORY_SIZE} bytes.",
+            summary.declared_size
+        ));
+    }
+    Ok(summary)
+}
+
+/// A central directory entry name, or why it cannot be used as a path.
+pub type ZipEntryName = Result<String, String>;
+
+/// Contents of requested archive entries, in request order.
+pub type ZipEntryContents = Vec<Option<Result<Vec<u8>, String>>>;
+
+/// Read the named entries with the same hardened inspection used for mod metadata.
+///
+/// Missing entries are `None`; an entry that cannot be read safely carries its own error.
+pub fn read_zip_entries(
+    file: &mut (impl Read + Seek),
+    paths: &[&str],
+    size_limit: u64,
+) -> Result<ZipEntryContents, String> {
+    let directory = checked_zip_directory_summary(file)?;
+    let records = collect_central_directory_entries(file, &directory, paths)?;
+    Ok(paths
+        .iter()
+        .zip(records)
+        .map(|(path, record)| {
+            record.map(|record| {
+                read_archive_entry(file, &directory, path, &record, size_limit, size_limit)
+            })
+        })
+        .collect())
+}
+
+/// Whether each of `paths` has a central directory record, without reading any entry.
+pub fn zip_contains_entries(
+    file: &mut (impl Read + Seek),
+    paths: &[&str],
+) -> Result<Vec<bool>, String> {
+    let directory = checked_zip_directory_summary(file)?;
+    Ok(collect_central_directory_entries(file, &directory, paths)?
+        .iter()
+        .map(Option::is_some)
+        .collect())
+}
+
+/// Walk the whole central directory so structural problems surface before extraction.
+pub fn preflight_zip_archive(file: &mut (impl Read + Seek)) -> Result<(), String> {
+    let directory = checked_zip_directory_summary(file)?;
+    collect_central_directory_entries(file, &directory, &[]).map(|_| ())
+}
+
+fn read_metadata_stream(
+    reader: impl Read,
+    expected_size: u64,
+    size_limit: u64,
+) -> Result<Vec<u8>, String> {
+    let capacity = usize::try_from(expected_size)
+        .map_err(|_| "The metadata output size is too large.".to_string())?;
+    let mut bytes = Vec::with_capacity(capacity);
+    reader
+        .take(size_limit + 1)
+        .read_to_end(&mut bytes)
+        .map_err(|error| format!("Failed to read the metadata stream: {error}"))?;
+    if bytes.len() as u64 > size_limit {
+        return Err(format!(
+            "The decompressed entry exceeds the {size_limit}-byte inspection limit."
+        ));
+    }
+    if bytes.len() as u64 != expected_size {
+        return Err(format!(
+            "The metadata stream contains {} bytes, but the central directory declares {expected_size} bytes.",
+            bytes.len()
+        ));
+    }
+    Ok(bytes)
+}
+
+/// Read one recorded entry, refusing anything above `size_limit` or `compressed_size_limit` bytes.
+pub fn read_archive_entry(
+    file: &mut (impl Read + Seek),
+    directory: &ZipDirectorySummary,
+    source: &str,
+    record: &ZipTargetRecord,
+    size_limit: u64,
+    compressed_size_limit: u64,
+) -> Result<Vec<u8>, String> {
+    let entry = match record {
+        ZipTargetRecord::Entry(entry) => entry,
+        ZipTargetRecord::Invalid(error) => return Err(error.clone()),
+    };
+    if entry.uncompressed_size > size_limit {
+        return Err(format!(
+            "The entry is too large to inspect ({} bytes; limit is {size_limit} bytes).",
+            entry.uncompressed_size
+        ));
+    }
+    if entry.compressed_size > compressed_size_limit {
+        return Err(format!(
+            "The compressed entry is too large to inspect ({} bytes; limit is {compressed_size_limit} bytes).",
+            entry.compressed_size
+        ));
+    }
+    if entry.flags & ZIP_ENCRYPTION_FLAGS != 0 {
+        return Err("Encrypted ZIP metadata entries are not supported.".to_string());
+    }
+    if !matches!(entry.compression_method, 0 | 8) {
+        return Err(format!(
+            "ZIP compression method {} is not supported for metadata inspection.",
+            entry.compression_method
+        ));
+    }
+
+    let local_header_end = entry
+        .local_header_offset
+        .checked_add(ZIP_LOCAL_FILE_HEADER_SIZE)
+        .ok_or_else(|| "The ZIP local header offset overflows the JAR.".to_string())?;
+    if local_header_end > directory.offset {
+        return Err("The ZIP local header crosses into the central directory.".to_string());
+    }
+    file.seek(SeekFrom::Start(entry.local_header_offset))
+        .map_err(|error| format!("Failed to seek to the ZIP local header: {error}"))?;
+    let mut local_header = [0_u8; ZIP_LOCAL_FILE_HEADER_SIZE as usize];
+    file.read_exact(&mut local_header)
+        .map_err(|error| format!("Failed to read the ZIP local header: {error}"))?;
+    if local_header.get(0..4) != Some(b"PK\x03\x04") {
+        return Err("The ZIP local file header signature is invalid.".to_string());
+    }
+    let local_flags = little_endian_u16(&local_header, 6)
+        .ok_or_else(|| "The ZIP local entry flags are missing.".to_string())?;
+    if local_flags & ZIP_ENCRYPTION_FLAGS != 0 {
+        return Err("Encrypted ZIP metadata entries are not supported.".to_string());
+    }
+    let local_compression_method = little_endian_u16(&local_header, 8)
+        .ok_or_else(|| "The ZIP local compression method is missing.".to_string())?;
+    if local_compression_method != entry.compression_method {
+        return Err(
+            "The ZIP local and central directory compression methods do not match.".to_string(),
+        );
+    }
+    let local_filename_length = little_endian_u16(&local_header, 26)
+        .ok_or_else(|| "The ZIP local filename length is missing.".to_string())?
+        as u64;
+    let local_extra_length = little_endian_u16(&local_header, 28)
+        .ok_or_else(|| "The ZIP local extra length is missing.".to_string())?
+        as u64;
+    if local_filename_length != source.len() as u64 {
+        return Err("The ZIP local and central directory filenames do not match.".to_string());
+    }
+
+    let data_offset = local_header_end
+        .checked_add(local_filename_length)
+        .and_then(|offset| offset.checked_add(local_extra_length))
+        .ok_or_else(|| "The ZIP metadata data offset overflows the JAR.".to_string())?;
+    let data_end = data_offset
+        .checked_add(entry.compressed_size)
+        .ok_or_else(|| "The ZIP metadata data range overflows the JAR.".to_string())?;
+    if data_offset > directory.offset || data_end > directory.offset {
+        return Err("The ZIP metadata data crosses into the central directory.".to_string());
+    }
+
+    let mut local_filename = vec![0_u8; source.len()];
+    file.read_exact(&mut local_filename)
+        .map_err(|error| format!("Failed to read the ZIP local filename: {error}"))?;
+    if local_filename != source.as_bytes() {
+        return Err("The ZIP local and central directory filenames do not match.".to_string());
+    }
+
+    file.seek(SeekFrom::Start(data_offset))
+        .map_err(|error| format!("Failed to seek to ZIP metadata data: {error}"))?;
+    let bytes = match entry.compression_method {
+        0 => {
+            if entry.compressed_size != entry.uncompressed_size {
+                return Err(
+                    "A stored ZIP metadata entry has inconsistent compressed and uncompressed sizes."
+                        .to_string(),
+                );
+            }
+            let reader = (&mut *file).take(entry.compressed_size);
+            read_metadata_stream(reader, entry.uncompressed_size, size_limit)?
+        }
+        8 => {
+            let reader = (&mut *file).take(entry.compressed_size);
+            read_metadata_stream(
+                DeflateDecoder::new(reader),
+                entry.uncompressed_size,
+                size_limit,
+            )?
+        }
+        _ => unreachable!(),
+    };
+
+    if bytes.len() as u64 > size_limit {
+        return Err(format!(
+            "The decompressed entry exceeds the {size_limit}-byte inspection limit."
+        ));
+    }
+
+    Ok(bytes)
+}
+
+pub fn validate_archive_path(path: &str) -> Result<(), String> {
+    if path.len() > ZIP_TARGET_PATH_BUFFER_SIZE {
+        return Err(format!(
+            "The path is longer than the {ZIP_TARGET_PATH_BUFFER_SIZE}-byte inspection limit."
+        ));
+    }
+    if path.starts_with('/')
+        || path.contains('\\')
+        || path
+            .split('/')
+            .any(|component| component.is_empty() || component == "." || component == "..")
+    {
+        return Err("The path is not a relative archive path.".to_string());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolves_zip64_candidate_sizes_and_local_header_offset() {
+        let filename = format!(
+            "epherome-zip64-extra-{}-{}.bin",
+            std::process::id(),
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_nanos()
+        );
+        let path = std::env::temp_dir().join(filename);
+        let mut extra = Vec::new();
+        extra.extend_from_slice(&ZIP64_EXTRA_FIELD_ID.to_le_bytes());
+        extra.extend_from_slice(&24_u16.to_le_bytes());
+        extra.extend_from_slice(&12_u64.to_le_bytes());
+        extra.extend_from_slice(&10_u64.to_le_bytes());
+        extra.extend_from_slice(&7_u64.to_le_bytes());
+        fs::write(&path, &extra).expect("Temporary ZIP64 extra field should be written");
+
+        let mut file = File::open(&path).expect("Temporary ZIP64 extra field should open");
+        let resolved = read_zip64_target_entry(
+            &mut file,
+            0,
+            extra.len() as u64,
+            u32::MAX,
+            u32::MAX,
+            u32::MAX,
+            0,
+        )
+        .expect("ZIP64 values should resolve");
+        let _ = fs::remove_file(path);
+
+        assert_eq!(resolved, (10, 12, 7));
+    }
+
+    #[test]
+    fn zip64_offset_sentinel_uses_the_zip64_limits() {
+        let filename = format!(
+            "epherome-zip64-preflight-{}-{}.jar",
+            std::process::id(),
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_nanos()
+        );
+        let path = std::env::temp_dir().join(filename);
+        let mut bytes = vec![0; 98];
+
+        bytes[0..4].copy_from_slice(b"PK\x06\x06");
+        bytes[4..12].copy_from_slice(&44_u64.to_le_bytes());
+        bytes[32..40].copy_from_slice(&(MAX_ZIP_ENTRIES + 1).to_le_bytes());
+        bytes[40..48].copy_from_slice(&(MAX_ZIP_CENTRAL_DIRECTORY_SIZE + 1).to_le_bytes());
+
+        bytes[56..60].copy_from_slice(b"PK\x06\x07");
+        bytes[64..72].copy_from_slice(&0_u64.to_le_bytes());
+
+        bytes[76..80].copy_from_slice(b"PK\x05\x06");
+        bytes[84..86].copy_from_slice(&1_u16.to_le_bytes());
+        bytes[86..88].copy_from_slice(&1_u16.to_le_bytes());
+        bytes[88..92].copy_from_slice(&1_u32.to_le_bytes());
+        bytes[92..96].copy_from_slice(&u32::MAX.to_le_bytes());
+
+        fs::write(&path, bytes).expect("Temporary ZIP64 fixture should be written");
+        let mut file = File::open(&path).expect("Temporary ZIP64 fixture should open");
+        let result = preflight_zip_archive(&mut file);
+        let _ = fs::remove_file(path);
+
+        assert!(result.is_err());
+        assert!(result
+            .expect_err("ZIP64 fixture should exceed the limits")
+            .contains("ZIP entries"));
+    }
+
+    #[test]
+    fn actual_central_directory_size_cannot_hide_behind_a_small_declared_size() {
+        let filename = format!(
+            "epherome-actual-central-directory-limit-{}-{}.jar",
+            std::process::id(),
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_nanos()
+        );
+        let path = std::env::temp_dir().join(filename);
+        let mut file = File::create(&path).expect("Temporary ZIP fixture should be created");
+        let filename_length = u16::MAX;
+        let entry_size = ZIP_CENTRAL_DIRECTORY_HEADER_SIZE + filename_length as u64;
+        let entry_count = MAX_ZIP_CENTRAL_DIRECTORY_SIZE / entry_size + 1;
+        let entry_count_u16 =
+            u16::try_from(entry_count).expect("Fixture entry count should fit a classic ZIP");
+        let mut header = [0_u8; ZIP_CENTRAL_DIRECTORY_HEADER_SIZE as usize];
+        header[0..4].copy_from_slice(b"PK\x01\x02");
+        header[28..30].copy_from_slice(&filename_length.to_le_bytes());
+
+        let mut position = 0_u64;
+        for _ in 0..entry_count {
+            file.seek(SeekFrom::Start(position))
+                .expect("Fixture central directory seek should succeed");
+            file.write_all(&header)
+                .expect("Fixture central directory header should be written");
+            position = position
+                .checked_add(entry_size)
+                .expect("Fixture central directory size should not overflow");
+        }
+
+        let mut end_record = [0_u8; ZIP_EOCD_MIN_SIZE as usize];
+        end_record[0..4].copy_from_slice(b"PK\x05\x06");
+        end_record[8..10].copy_from_slice(&entry_count_u16.to_le_bytes());
+        end_record[10..12].copy_from_slice(&entry_count_u16.to_le_bytes());
+        end_record[12..16]
+            .copy_from_slice(&(ZIP_CENTRAL_DIRECTORY_HEADER_SIZE as u32).to_le_bytes());
+        end_record[16..20].copy_from_slice(&0_u32.to_le_bytes());
+        file.seek(SeekFrom::Start(position))
+            .expect("Fixture end record seek should succeed");
+        file.write_all(&end_record)
+            .expect("Fixture end record should be written");
+        drop(file);
+
+        let mut file = File::open(&path).expect("Temporary ZIP fixture should open");
+        let result = preflight_zip_archive(&mut file);
+        let _ = fs::remove_file(path);
+
+        let error = result.expect_err("The actual central directory should exceed the limit");
+        assert!(error.contains("actual JAR ZIP directory"));
+        assert!(error.contains("inspection limit"));
+    }
+
+    #[test]
+    fn central_directory_entry_cannot_cross_the_classic_end_record() {
+        let filename = format!(
+            "epherome-central-directory-boundary-{}-{}.jar",
+            std::process::id(),
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_nanos()
+        );
+        let path = std::env::temp_dir().join(filename);
+        let mut bytes =
+            vec![0_u8; (ZIP_CENTRAL_DIRECTORY_HEADER_SIZE + ZIP_EOCD_MIN_SIZE) as usize];
+        bytes[0..4].copy_from_slice(b"PK\x01\x02");
+        bytes[28..30].copy_from_slice(&1_u16.to_le_bytes());
+        let end_record_offset = ZIP_CENTRAL_DIRECTORY_HEADER_SIZE as usize;
+        bytes[end_record_offset..end_record_offset + 4].copy_from_slice(b"PK\x05\x06");
+        bytes[end_record_offset + 8..end_record_offset + 10].copy_from_slice(&1_u16.to_le_bytes());
+        bytes[end_record_offset + 10..end_record_offset + 12].copy_from_slice(&1_u16.to_le_bytes());
+        bytes[end_record_offset + 12..end_record_offset + 16]
+            .copy_from_slice(&(ZIP_CENTRAL_DIRECTORY_HEADER_SIZE as u32).to_le_bytes());
+        bytes[end_record_offset + 16..end_record_offset + 20].copy_from_slice(&0_u32.to_le_bytes());
+        fs::write(&path, bytes).expect("Temporary ZIP fixture should be written");
+
+        let mut file = File::open(&path).expect("Temporary ZIP fixture should open");
+        let result = preflight_zip_archive(&mut file);
+        let _ = fs::remove_file(path);
+
+        assert!(result
+            .expect_err("The central directory entry should cross the classic end record")
+            .contains("crosses into the ZIP end structure"));
+    }
+
+    #[test]
+    fn zip64_central_directory_uses_its_own_offset_and_end_record_boundary() {
+        let filename = format!(
+            "epherome-zip64-central-directory-boundary-{}-{}.jar",
+            std::process::id(),
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_nanos()
+        );
+        let path = std::env::temp_dir().join(filename);
+        let zip64_end_offset = ZIP_CENTRAL_DIRECTORY_HEADER_SIZE as usize;
+        let locator_offset = zip64_end_offset + 56;
+        let end_record_offset = locator_offset + 20;
+        let mut bytes = vec![0_u8; end_record_offset + ZIP_EOCD_MIN_SIZE as usize];
+
+        bytes[0..4].copy_from_slice(b"PK\x01\x02");
+        bytes[28..30].copy_from_slice(&1_u16.to_le_bytes());
+
+        bytes[zip64_end_offset..zip64_end_offset + 4].copy_from_slice(b"PK\x06\x06");
+        bytes[zip64_end_offset + 4..zip64_end_offset + 12].copy_from_slice(&44_u64.to_le_bytes());
+        bytes[zip64_end_offset + 24..zip64_end_offset + 32].copy_from_slice(&1_u64.to_le_bytes());
+        bytes[zip64_end_offset + 32..zip64_end_offset + 40].copy_from_slice(&1_u64.to_le_bytes());
+        bytes[zip64_end_offset + 40..zip64_end_offset + 48]
+            .copy_from_slice(&ZIP_CENTRAL_DIRECTORY_HEADER_SIZE.to_le_bytes());
+        bytes[zip64_end_offset + 48..zip64_end_offset + 56].copy_from_slice(&0_u64.to_le_bytes());
+
+        bytes[locator_offset..locator_offset + 4].copy_from_slice(b"PK\x06\x07");
+        bytes[locator_offset + 8..locator_offset + 16]
+            .copy_from_slice(&(zip64_end_offset as u64).to_le_bytes());
+
+        bytes[end_record_offset..end_record_offset + 4].copy_from_slice(b"PK\x05\x06");
+        bytes[end_record_offset + 8..end_record_offset + 10].copy_from_slice(&1_u16.to_le_bytes());
+        bytes[end_record_offset + 10..end_record_offset + 12].copy_from_slice(&1_u16.to_le_bytes());
+        bytes[end_record_offset + 12..end_record_offset + 16]
+            .copy_from_slice(&(ZIP_CENTRAL_DIRECTORY_HEADER_SIZE as u32).to_le_bytes());
+        bytes[end_record_offset + 16..end_record_offset + 20]
+            .copy_from_slice(&u32::MAX.to_le_bytes());
+        fs::write(&path, bytes).expect("Temporary ZIP64 fixture should be written");
+
+        let mut file = File::open(&path).expect("Temporary ZIP64 fixture should open");
+        let result = preflight_zip_archive(&mut file);
+        let _ = fs::remove_file(path);
+
+        assert!(result
+            .expect_err("The central directory entry should cross the ZIP64 end record")
+            .contains("crosses into the ZIP end structure"));
+    }
+}
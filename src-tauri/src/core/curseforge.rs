@@ -0,0 +1,313 @@
+use crate::core::mods::{
+    scan_local_mods_blocking, LocalModCurseForgeInfo, LocalModFile, MODS_OPERATION_LOCK,
+};
+use crate::utils::http::get_http_client;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::{json, Value as JsonValue};
+use std::collections::{BTreeSet, HashMap};
+
+const DEFAULT_CURSEFORGE_API_BASE_URL: &str = "https://api.curseforge.com";
+const CURSEFORGE_MOD_PAGE_URL: &str = "https://www.curseforge.com/minecraft/mc-mods";
+const CURSEFORGE_SHA1_ALGORITHM: u32 = 1;
+const CURSEFORGE_USER_AGENT: &str = concat!("Epherome/", env!("CARGO_PKG_VERSION"));
+
+#[derive(Debug, Deserialize)]
+struct CurseForgeResponse<T> {
+    data: T,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CurseForgeFileHash {
+    pub value: String,
+    pub algo: u32,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CurseForgeFile {
+    pub id: u64,
+    pub mod_id: u64,
+    #[serde(default)]
+    pub file_name: String,
+    /// `None` when the author disabled third-party distribution.
+    #[serde(default)]
+    pub download_url: Option<String>,
+    #[serde(default)]
+    pub file_length: Option<u64>,
+    #[serde(default)]
+    pub hashes: Vec<CurseForgeFileHash>,
+    #[serde(default)]
+    file_fingerprint: u32,
+}
+
+impl CurseForgeFile {
+    pub fn sha1(&self) -> Option<String> {
+        self.hashes
+            .iter()
+            .find(|hash| hash.algo == CURSEFORGE_SHA1_ALGORITHM)
+            .map(|hash| hash.value.trim().to_ascii_lowercase())
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct CurseForgeFingerprintMatch {
+    file: CurseForgeFile,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CurseForgeFingerprintMatches {
+    #[serde(default)]
+    exact_matches: Vec<CurseForgeFingerprintMatch>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CurseForgeModLinks {
+    website_url: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CurseForgeMod {
+    pub id: u64,
+    pub name: String,
+    slug: String,
+    /// Project category: 6 for mods, 12 for resource packs, 6552 for shader packs.
+    #[serde(default)]
+    pub class_id: Option<u64>,
+    #[serde(default)]
+    links: CurseForgeModLinks,
+}
+
+impl CurseForgeMod {
+    pub fn page_url(&self) -> String {
+        self.links
+            .website_url
+            .clone()
+            .filter(|url| !url.is_empty())
+            .unwrap_or_else(|| format!("{CURSEFORGE_MOD_PAGE_URL}/{}", self.slug))
+    }
+}
+
+/// A minimal CurseForge Core API client; the base URL can point at a proxy or a test server.
+pub struct CurseForgeClient {
+    base_url: String,
+    api_key: String,
+}
+
+impl CurseForgeClient {
+    pub fn new(base_url: Option<String>, api_key: String) -> Result<Self, String> {
+        if api_key.trim().is_empty() {
+            return Err("A CurseForge API key is required.".to_string());
+        }
+        let base_url = base_url
+            .filter(|url| !url.trim().is_empty())
+            .unwrap_or_else(|| DEFAULT_CURSEFORGE_API_BASE_URL.to_string());
+        Ok(Self {
+            base_url: base_url.trim().trim_end_matches('/').to_string(),
+            api_key: api_key.trim().to_string(),
+        })
+    }
+
+    async fn post<T: DeserializeOwned>(&self, path: &str, body: JsonValue) -> Result<T, String> {
+        let response = get_http_client()
+            .post(format!("{}{path}", self.base_url))
+            .header(reqwest::header::USER_AGENT, CURSEFORGE_USER_AGENT)
+            .header("x-api-key", &self.api_key)
+            .json(&body)
+            .send()
+            .await
+            .map_err(|error| format!("CurseForge request to {path} failed: {error}"))?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(format!("CurseForge returned HTTP {status} for {path}."));
+        }
+        response
+            .json::<CurseForgeResponse<T>>()
+            .await
+            .map(|response| response.data)
+            .map_err(|error| format!("CurseForge response for {path} is invalid: {error}"))
+    }
+
+    async fn fingerprint_matches(
+        &self,
+        fingerprints: &[u32],
+    ) -> Result<Vec<CurseForgeFingerprintMatch>, String> {
+        if fingerprints.is_empty() {
+            return Ok(Vec::new());
+        }
+        let matches: CurseForgeFingerprintMatches = self
+            .post("/v1/fingerprints", json!({ "fingerprints": fingerprints }))
+            .await?;
+        Ok(matches.exact_matches)
+    }
+
+    pub async fn files(&self, ids: &[u64]) -> Result<Vec<CurseForgeFile>, String> {
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        self.post("/v1/mods/files", json!({ "fileIds": ids })).await
+    }
+
+    pub async fn mods(&self, ids: &[u64]) -> Result<Vec<CurseForgeMod>, String> {
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        self.post("/v1/mods", json!({ "modIds": ids })).await
+    }
+}
+
+/// Attach matched CurseForge files to scanned mods by fingerprint.
+fn attach_curseforge_matches(
+    files: &mut [LocalModFile],
+    matches: &[CurseForgeFingerprintMatch],
+    mods: &[CurseForgeMod],
+) {
+    let files_by_fingerprint: HashMap<u32, &CurseForgeFile> = matches
+        .iter()
+        .map(|matched| (matched.file.file_fingerprint, &matched.file))
+        .collect();
+    let mods_by_id: HashMap<u64, &CurseForgeMod> =
+        mods.iter().map(|project| (project.id, project)).collect();
+
+    for file in files {
+        let Some(matched) = file
+            .curseforge_fingerprint
+            .and_then(|fingerprint| files_by_fingerprint.get(&fingerprint))
+        else {
+            continue;
+        };
+        let Some(project) = mods_by_id.get(&matched.mod_id) else {
+            continue;
+        };
+        file.curseforge = Some(LocalModCurseForgeInfo {
+            project_id: project.id,
+            file_id: matched.id,
+            project_name: project.name.clone(),
+            page_url: project.page_url(),
+        });
+    }
+}
+
+async fn identify_local_mods(
+    client: &CurseForgeClient,
+    mut files: Vec<LocalModFile>,
+) -> Result<Vec<LocalModFile>, String> {
+    let fingerprints: Vec<u32> = files
+        .iter()
+        .filter_map(|file| file.curseforge_fingerprint)
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect();
+    let matches = client.fingerprint_matches(&fingerprints).await?;
+    let mod_ids: Vec<u64> = matches
+        .iter()
+        .map(|matched| matched.file.mod_id)
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect();
+    let mods = client.mods(&mod_ids).await?;
+    attach_curseforge_matches(&mut files, &matches, &mods);
+    Ok(files)
+}
+
+/// Match installed mods against CurseForge by fingerprint.
+#[tauri::command]
+pub async fn identify_local_mods_on_curseforge(
+    game_directory: String,
+    api_key: String,
+    api_base_url: Option<String>,
+) -> Result<Vec<LocalModFile>, String> {
+    let client = CurseForgeClient::new(api_base_url, api_key)?;
+    let operation_guard = MODS_OPERATION_LOCK.lock().await;
+    let files = tokio::task::spawn_blocking(move || {
+        let _operation_guard = operation_guard;
+        scan_local_mods_blocking(&game_directory)
+    })
+    .await
+    .map_err(|error| format!("Mod scan task failed: {error}"))??;
+
+    identify_local_mods(&client, files).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::mods::LocalModMetadata;
+
+    fn local_mod(filename: &str, curseforge_fingerprint: Option<u32>) -> LocalModFile {
+        LocalModFile {
+            filename: filename.to_string(),
+            enabled: true,
+            size: 0,
+            metadata: LocalModMetadata::default(),
+            curseforge_fingerprint,
+            modrinth: None,
+            curseforge: None,
+            compatibility: None,
+            side_warnings: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn attaches_fingerprint_matches_with_project_pages() {
+        let matches: CurseForgeResponse<CurseForgeFingerprintMatches> =
+            serde_json::from_value(json!({
+                "data": {
+                    "exactMatches": [
+                        {"id": 10, "file": {"id": 100, "modId": 10, "fileFingerprint": 1961219979}},
+                        {"id": 20, "file": {"id": 200, "modId": 20, "fileFingerprint": 42}}
+                    ],
+                    "unmatchedFingerprints": [7]
+                }
+            }))
+            .expect("Fingerprint response should parse");
+        let mods: CurseForgeResponse<Vec<CurseForgeMod>> = serde_json::from_value(json!({
+            "data": [
+                {"id": 10, "name": "Hello", "slug": "hello", "links": {"websiteUrl": "https://cf.example/hello"}},
+                {"id": 20, "name": "Answer", "slug": "answer", "links": {"websiteUrl": ""}}
+            ]
+        }))
+        .expect("Mods response should parse");
+
+        let mut files = vec![
+            local_mod("hello.jar", Some(1961219979)),
+            local_mod("answer.jar", Some(42)),
+            local_mod("unknown.jar", Some(7)),
+            local_mod("unreadable.jar", None),
+        ];
+        attach_curseforge_matches(&mut files, &matches.data.exact_matches, &mods.data);
+
+        let hello = files[0]
+            .curseforge
+            .as_ref()
+            .expect("hello.jar should match");
+        assert_eq!((hello.project_id, hello.file_id), (10, 100));
+        assert_eq!(hello.project_name, "Hello");
+        assert_eq!(hello.page_url, "https://cf.example/hello");
+        let answer = files[1]
+            .curseforge
+            .as_ref()
+            .expect("answer.jar should match");
+        assert_eq!(
+            answer.page_url,
+            "https://www.curseforge.com/minecraft/mc-mods/answer"
+        );
+        assert!(files[2].curseforge.is_none());
+        assert!(files[3].curseforge.is_none());
+    }
+
+    #[test]
+    fn requires_an_api_key_and_normalizes_the_base_url() {
+        assert!(CurseForgeClient::new(None, " ".to_string()).is_err());
+        let client =
+            CurseForgeClient::new(Some("http://127.0.0.1:8080/".to_string()), "key".into())
+                .expect("Client should be created");
+        assert_eq!(client.base_url, "http://127.0.0.1:8080");
+        let client =
+            CurseForgeClient::new(None, "key".to_string()).expect("Client should be created");
+        assert_eq!(client.base_url, DEFAULT_CURSEFORGE_API_BASE_URL);
+    }
+}
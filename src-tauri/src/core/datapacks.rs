@@ -0,0 +1,474 @@
+use crate::core::archive::{
+    is_launcher_temp_filename, validate_single_filename, write_file_atomically, ImportResult,
+};
+use crate::core::mods::game_directory_path;
+use crate::core::nbt::{read_gzip_nbt, write_gzip_nbt, NbtString, NbtTag};
+use crate::core::packs::{
+    file_pack_id, import_pack_archives, inspect_data_pack, is_pack_archive_filename, LocalPack,
+};
+use serde::Serialize;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use tokio::sync::Mutex;
+
+const SAVES_DIRECTORY_NAME: &str = "saves";
+const DATAPACKS_DIRECTORY_NAME: &str = "datapacks";
+const LEVEL_DATA_FILENAME: &str = "level.dat";
+const LEVEL_DATA_BACKUP_FILENAME: &str = "level.dat_old";
+const MAX_LEVEL_DATA_FILE_SIZE: u64 = 16 * 1024 * 1024;
+const MAX_LEVEL_DATA_NBT_SIZE: u64 = 64 * 1024 * 1024;
+
+static DATAPACKS_OPERATION_LOCK: Mutex<()> = Mutex::const_new(());
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalWorld {
+    /// Folder name under `saves/`, which addresses the world in other commands.
+    pub directory: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Whether `level.dat` records datapack state; datapacks can only be toggled when it does.
+    pub tracks_datapacks: bool,
+    pub datapacks: Vec<LocalPack>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub diagnostics: Vec<String>,
+}
+
+/// The `Data.DataPacks` lists of `level.dat`.
+struct LevelDatapacks {
+    enabled: Vec<String>,
+    disabled: Vec<String>,
+}
+
+impl LevelDatapacks {
+    fn read(root: &NbtTag) -> Option<Self> {
+        let packs = root.get("Data")?.get("DataPacks")?;
+        let list = |key| {
+            packs
+                .get(key)
+                .and_then(NbtTag::as_string_list)
+                .unwrap_or_default()
+        };
+        Some(Self {
+            enabled: list("Enabled"),
+            disabled: list("Disabled"),
+        })
+    }
+
+    /// Minecraft enables packs it has not seen before the next time the world loads.
+    fn is_enabled(&self, filename: &str) -> bool {
+        !self.disabled.contains(&file_pack_id(filename))
+    }
+}
+
+fn world_path(game_directory: &str, world: &str) -> Result<PathBuf, String> {
+    validate_single_filename(world)?;
+    let path = game_directory_path(game_directory)?
+        .join(SAVES_DIRECTORY_NAME)
+        .join(world);
+    match fs::symlink_metadata(&path) {
+        Ok(metadata) if metadata.is_dir() => Ok(path),
+        Ok(_) => Err(format!("World '{world}' is not a directory.")),
+        Err(error) if error.kind() == io::ErrorKind::NotFound => {
+            Err(format!("World '{world}' does not exist."))
+        }
+        Err(error) => Err(format!("Failed to access world '{world}': {error}")),
+    }
+}
+
+/// A world's `level.dat`, as read from disk and decoded.
+struct LevelData {
+    bytes: Vec<u8>,
+    name: NbtString,
+    root: NbtTag,
+}
+
+/// `None` when the world has no `level.dat`.
+fn read_level_data(world_path: &Path) -> Result<Option<LevelData>, String> {
+    let path = world_path.join(LEVEL_DATA_FILENAME);
+    let metadata = match fs::symlink_metadata(&path) {
+        Ok(metadata) => metadata,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(error) => return Err(format!("Failed to access {LEVEL_DATA_FILENAME}: {error}")),
+    };
+    if !metadata.is_file() {
+        return Err(format!("{LEVEL_DATA_FILENAME} is not a regular file."));
+    }
+    if metadata.len() > MAX_LEVEL_DATA_FILE_SIZE {
+        return Err(format!(
+            "{LEVEL_DATA_FILENAME} is too large ({} bytes).",
+            metadata.len()
+        ));
+    }
+    let bytes = fs::read(&path)
+        .map_err(|error| format!("Failed to read {LEVEL_DATA_FILENAME}: {error}"))?;
+    let (name, root) = read_gzip_nbt(&bytes, MAX_LEVEL_DATA_NBT_SIZE)
+        .map_err(|error| format!("{LEVEL_DATA_FILENAME}: {error}"))?;
+    Ok(Some(LevelData { bytes, name, root }))
+}
+
+/// An installed datapack's path and whether it is a folder.
+fn installed_datapack(world_path: &Path, filename: &str) -> Result<(PathBuf, bool), String> {
+    validate_single_filename(filename)?;
+    let path = world_path.join(DATAPACKS_DIRECTORY_NAME).join(filename);
+    let metadata = fs::symlink_metadata(&path)
+        .map_err(|error| format!("Failed to access datapack '{filename}': {error}"))?;
+    if metadata.is_dir() {
+        Ok((path, true))
+    } else if metadata.is_file() && is_pack_archive_filename(filename) {
+        Ok((path, false))
+    } else {
+        Err(format!("'{filename}' is not a datapack."))
+    }
+}
+
+fn scan_world(path: &Path, directory: String) -> LocalWorld {
+    let mut world = LocalWorld {
+        directory,
+        name: None,
+        tracks_datapacks: false,
+        datapacks: Vec::new(),
+        diagnostics: Vec::new(),
+    };
+    let level = match read_level_data(path) {
+        Ok(Some(LevelData { root, .. })) => {
+            world.name = root
+                .get("Data")
+                .and_then(|data| data.get("LevelName"))
+                .and_then(NbtTag::as_string);
+            LevelDatapacks::read(&root)
+        }
+        Ok(None) => {
+            world
+                .diagnostics
+                .push(format!("{LEVEL_DATA_FILENAME} is missing."));
+            None
+        }
+        Err(error) => {
+            world.diagnostics.push(error);
+            None
+        }
+    };
+    world.tracks_datapacks = level.is_some();
+
+    let datapacks_directory = path.join(DATAPACKS_DIRECTORY_NAME);
+    let entries = match fs::read_dir(&datapacks_directory) {
+        Ok(entries) => entries,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => return world,
+        Err(error) => {
+            world
+                .diagnostics
+                .push(format!("Failed to read datapacks: {error}"));
+            return world;
+        }
+    };
+    for entry in entries.flatten() {
+        let Ok(filename) = entry.file_name().into_string() else {
+            continue;
+        };
+        if is_launcher_temp_filename(&filename) {
+            continue;
+        }
+        let Ok(metadata) = fs::symlink_metadata(entry.path()) else {
+            continue;
+        };
+        let folder = metadata.is_dir();
+        let archive = metadata.is_file() && is_pack_archive_filename(&filename);
+        if !folder && !archive {
+            continue;
+        }
+        let enabled = level
+            .as_ref()
+            .is_none_or(|level| level.is_enabled(&filename));
+        let mut pack = inspect_data_pack(&entry.path(), filename, folder);
+        pack.enabled = enabled;
+        world.datapacks.push(pack);
+    }
+    world
+        .datapacks
+        .sort_by_key(|pack| pack.filename.to_lowercase());
+    world
+}
+
+fn scan_local_worlds_blocking(game_directory: &str) -> Result<Vec<LocalWorld>, String> {
+    let saves = game_directory_path(game_directory)?.join(SAVES_DIRECTORY_NAME);
+    let entries = match fs::read_dir(&saves) {
+        Ok(entries) => entries,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(error) => return Err(format!("Failed to read '{}': {error}", saves.display())),
+    };
+
+    let mut worlds = Vec::new();
+    for entry in entries {
+        let entry = entry.map_err(|error| {
+            format!("Failed to read an entry in '{}': {error}", saves.display())
+        })?;
+        let Ok(directory) = entry.file_name().into_string() else {
+            continue;
+        };
+        if !fs::symlink_metadata(entry.path()).is_ok_and(|metadata| metadata.is_dir()) {
+            continue;
+        }
+        worlds.push(scan_world(&entry.path(), directory));
+    }
+    worlds.sort_by_key(|world| world.directory.to_lowercase());
+    Ok(worlds)
+}
+
+fn set_string_list(packs: &mut NbtTag, key: &str, items: &[String]) {
+    let list = NbtTag::string_list(items);
+    if let Some(tag) = packs.get_mut(key) {
+        *tag = list;
+    } else if let NbtTag::Compound(entries) = packs {
+        entries.push((NbtString::new(key), list));
+    }
+}
+
+fn set_world_datapack_enabled_blocking(
+    game_directory: &str,
+    world: &str,
+    filename: &str,
+    enabled: bool,
+) -> Result<LocalPack, String> {
+    let world_path = world_path(game_directory, world)?;
+    let (path, directory) = installed_datapack(&world_path, filename)?;
+    let LevelData {
+        bytes: original,
+        name,
+        mut root,
+    } = read_level_data(&world_path)?.ok_or_else(|| {
+        format!("World '{world}' has no {LEVEL_DATA_FILENAME}; open it in Minecraft first.")
+    })?;
+    let mut level = LevelDatapacks::read(&root).ok_or_else(|| {
+        format!(
+            "World '{world}' does not record datapacks; open it in Minecraft 1.13 or newer first."
+        )
+    })?;
+
+    let id = file_pack_id(filename);
+    level.enabled.retain(|pack| *pack != id);
+    level.disabled.retain(|pack| *pack != id);
+    if enabled {
+        level.enabled.push(id);
+    } else {
+        level.disabled.push(id);
+    }
+    let packs = root
+        .get_mut("Data")
+        .and_then(|data| data.get_mut("DataPacks"))
+        .ok_or_else(|| format!("World '{world}' does not record datapacks."))?;
+    set_string_list(packs, "Enabled", &level.enabled);
+    set_string_list(packs, "Disabled", &level.disabled);
+
+    // Keep the previous state where Minecraft keeps its own backup.
+    let encoded = write_gzip_nbt(&name, &root)?;
+    write_file_atomically(&world_path.join(LEVEL_DATA_BACKUP_FILENAME), &original)?;
+    write_file_atomically(&world_path.join(LEVEL_DATA_FILENAME), &encoded)?;
+
+    let mut pack = inspect_data_pack(&path, filename.to_string(), directory);
+    pack.enabled = enabled;
+    Ok(pack)
+}
+
+fn import_world_datapacks_blocking(
+    game_directory: &str,
+    world: &str,
+    source_paths: Vec<String>,
+) -> Result<ImportResult, String> {
+    let world_path = world_path(game_directory, world)?;
+    import_pack_archives(
+        &world_path.join(DATAPACKS_DIRECTORY_NAME),
+        true,
+        source_paths,
+    )
+}
+
+#[tauri::command]
+pub async fn scan_local_worlds(game_directory: String) -> Result<Vec<LocalWorld>, String> {
+    let operation_guard = DATAPACKS_OPERATION_LOCK.lock().await;
+    tokio::task::spawn_blocking(move || {
+        let _operation_guard = operation_guard;
+        scan_local_worlds_blocking(&game_directory)
+    })
+    .await
+    .map_err(|error| format!("World scan task failed: {error}"))?
+}
+
+#[tauri::command]
+pub async fn import_world_datapacks(
+    game_directory: String,
+    world: String,
+    source_paths: Vec<String>,
+) -> Result<ImportResult, String> {
+    let operation_guard = DATAPACKS_OPERATION_LOCK.lock().await;
+    tokio::task::spawn_blocking(move || {
+        let _operation_guard = operation_guard;
+        import_world_datapacks_blocking(&game_directory, &world, source_paths)
+    })
+    .await
+    .map_err(|error| format!("Datapack import task failed: {error}"))?
+}
+
+/// Record a datapack as enabled or disabled in `level.dat`.
+///
+/// The game rewrites `level.dat` while a world is open, so this is meant for closed worlds.
+#[tauri::command]
+pub async fn set_world_datapack_enabled(
+    game_directory: String,
+    world: String,
+    filename: String,
+    enabled: bool,
+) -> Result<LocalPack, String> {
+    let operation_guard = DATAPACKS_OPERATION_LOCK.lock().await;
+    tokio::task::spawn_blocking(move || {
+        let _operation_guard = operation_guard;
+        set_world_datapack_enabled_blocking(&game_directory, &world, &filename, enabled)
+    })
+    .await
+    .map_err(|error| format!("Datapack state task failed: {error}"))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::test_fixtures::{temp_directory, write_zip};
+
+    fn level_data(enabled: &[&str], disabled: &[&str]) -> Vec<u8> {
+        let strings = |items: &[&str]| {
+            items
+                .iter()
+                .map(|item| item.to_string())
+                .collect::<Vec<_>>()
+        };
+        let root = NbtTag::Compound(vec![(
+            NbtString::new("Data"),
+            NbtTag::Compound(vec![
+                (
+                    NbtString::new("LevelName"),
+                    NbtTag::String(NbtString::new("My World")),
+                ),
+                (NbtString::new("SpawnX"), NbtTag::Int(-12)),
+                (
+                    NbtString::new("DataPacks"),
+                    NbtTag::Compound(vec![
+                        (
+                            NbtString::new("Enabled"),
+                            NbtTag::string_list(&strings(enabled)),
+                        ),
+                        (
+                            NbtString::new("Disabled"),
+                            NbtTag::string_list(&strings(disabled)),
+                        ),
+                    ]),
+                ),
+            ]),
+        )]);
+        write_gzip_nbt(&NbtString::new(""), &root).expect("Level data should encode")
+    }
+
+    const METADATA: &[u8] = br#"{"pack": {"pack_format": 48, "description": "Test"}}"#;
+
+    #[test]
+    fn reflects_and_updates_datapack_state_in_level_dat() {
+        let game_directory = temp_directory("datapacks-state");
+        let game_directory_text = game_directory.display().to_string();
+        let world = game_directory.join("saves/New World");
+        let datapacks = world.join("datapacks");
+        fs::create_dir_all(datapacks.join("folder")).expect("Datapacks should be created");
+        fs::create_dir_all(game_directory.join("saves/Empty")).expect("World should be created");
+        fs::write(datapacks.join("folder/pack.mcmeta"), METADATA)
+            .expect("Folder metadata should be written");
+        write_zip(&datapacks.join("listed.zip"), &[("pack.mcmeta", METADATA)]);
+        write_zip(&datapacks.join("new.zip"), &[("pack.mcmeta", METADATA)]);
+        let original = level_data(&["vanilla", "file/listed.zip"], &["file/folder"]);
+        fs::write(world.join("level.dat"), &original).expect("Level data should be written");
+
+        let before = scan_local_worlds_blocking(&game_directory_text);
+        let disabled = set_world_datapack_enabled_blocking(
+            &game_directory_text,
+            "New World",
+            "listed.zip",
+            false,
+        );
+        let enabled =
+            set_world_datapack_enabled_blocking(&game_directory_text, "New World", "folder", true);
+        let missing =
+            set_world_datapack_enabled_blocking(&game_directory_text, "Empty", "folder", true);
+        let level = read_level_data(&world);
+        let backup = fs::read(world.join("level.dat_old"));
+        let _ = fs::remove_dir_all(&game_directory);
+
+        let before = before.expect("Worlds should be scanned");
+        assert_eq!(before.len(), 2);
+        assert_eq!(before[0].directory, "Empty");
+        assert!(!before[0].tracks_datapacks);
+        let world = &before[1];
+        assert_eq!(world.name.as_deref(), Some("My World"));
+        assert!(world.tracks_datapacks);
+        let states: Vec<(&str, bool, Option<i64>)> = world
+            .datapacks
+            .iter()
+            .map(|pack| (pack.filename.as_str(), pack.enabled, pack.pack_format))
+            .collect();
+        assert_eq!(
+            states,
+            [
+                ("folder", false, Some(48)),
+                ("listed.zip", true, Some(48)),
+                ("new.zip", true, Some(48)),
+            ]
+        );
+
+        assert!(!disabled.expect("Datapack should be disabled").enabled);
+        assert!(enabled.expect("Datapack should be enabled").enabled);
+        assert!(missing.is_err());
+        let LevelData { root, .. } = level
+            .expect("Level data should be readable")
+            .expect("Level data should exist");
+        let packs = LevelDatapacks::read(&root).expect("Datapack lists should exist");
+        assert_eq!(packs.enabled, ["vanilla", "file/folder"]);
+        assert_eq!(packs.disabled, ["file/listed.zip"]);
+        assert_eq!(
+            root.get("Data").and_then(|data| data.get("SpawnX")),
+            Some(&NbtTag::Int(-12))
+        );
+        assert!(!backup.expect("Backup should be written").is_empty());
+    }
+
+    #[test]
+    fn imports_only_datapacks_with_valid_metadata() {
+        let game_directory = temp_directory("datapacks-import");
+        let game_directory_text = game_directory.display().to_string();
+        fs::create_dir_all(game_directory.join("saves/World")).expect("World should be created");
+        let downloads = game_directory.join("downloads");
+        fs::create_dir_all(&downloads).expect("Downloads should be created");
+        write_zip(&downloads.join("good.zip"), &[("pack.mcmeta", METADATA)]);
+        write_zip(&downloads.join("broken.zip"), &[("pack.mcmeta", b"{")]);
+        write_zip(&downloads.join("bare.zip"), &[("data/x.json", b"{}")]);
+        let source = |name: &str| downloads.join(name).display().to_string();
+
+        let result = import_world_datapacks_blocking(
+            &game_directory_text,
+            "World",
+            vec![source("good.zip"), source("broken.zip"), source("bare.zip")],
+        );
+        let missing_world =
+            import_world_datapacks_blocking(&game_directory_text, "Nope", vec![source("good.zip")]);
+        let installed = game_directory
+            .join("saves/World/datapacks/good.zip")
+            .is_file();
+        let _ = fs::remove_dir_all(&game_directory);
+
+        let result = result.expect("Datapacks should be imported");
+        assert_eq!(result.imported, ["good.zip"]);
+        let failed: Vec<&str> = result
+            .failed
+            .iter()
+            .map(|failure| failure.filename.as_str())
+            .collect();
+        assert_eq!(failed, ["broken.zip", "bare.zip"]);
+        assert!(installed);
+        assert!(missing_world.is_err());
+    }
+}
@@ -0,0 +1,328 @@
+use serde::Serialize;
+use serde_json::Value as JsonValue;
+use std::fs;
+use std::path::Path;
+
+const MAX_INHERITANCE_DEPTH: usize = 8;
+const MAX_VERSION_JSON_SIZE: u64 = 16 * 1024 * 1024;
+
+/// Game and mod loader versions of an installed version, as far as its version JSON tells.
+#[derive(Debug, Default, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstanceVersionInfo {
+    pub version_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub game_version: Option<String>,
+    /// One of "Fabric", "Quilt", "Forge" or "NeoForge"; `None` for vanilla.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub loader: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub loader_version: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub main_class: Option<String>,
+}
+
+fn validate_version_id(version_id: &str) -> Result<(), String> {
+    if version_id.trim().is_empty()
+        || version_id == "."
+        || version_id == ".."
+        || version_id.contains('/')
+        || version_id.contains('\\')
+    {
+        return Err(format!("Version ID '{version_id}' is invalid."));
+    }
+    Ok(())
+}
+
+fn read_version_json(game_directory: &Path, version_id: &str) -> Result<JsonValue, String> {
+    validate_version_id(version_id)?;
+    let path = game_directory
+        .join("versions")
+        .join(version_id)
+        .join(format!("{version_id}.json"));
+    let metadata = fs::metadata(&path).map_err(|error| {
+        format!(
+            "Failed to access version JSON '{}': {}",
+            path.display(),
+            error
+        )
+    })?;
+    if metadata.len() > MAX_VERSION_JSON_SIZE {
+        return Err(format!(
+            "Version JSON '{}' exceeds the {MAX_VERSION_JSON_SIZE}-byte limit.",
+            path.display()
+        ));
+    }
+    let text = fs::read_to_string(&path).map_err(|error| {
+        format!(
+            "Failed to read version JSON '{}': {}",
+            path.display(),
+            error
+        )
+    })?;
+    serde_json::from_str(&text)
+        .map_err(|error| format!("Version JSON '{}' is invalid: {}", path.display(), error))
+}
+
+/// Read a version JSON and every `inheritsFrom` ancestor, child first.
+fn read_version_chain(game_directory: &Path, version_id: &str) -> Result<Vec<JsonValue>, String> {
+    let mut chain = vec![read_version_json(game_directory, version_id)?];
+    let mut seen = vec![version_id.to_string()];
+
+    while let Some(parent) = chain
+        .last()
+        .and_then(|document| document.get("inheritsFrom"))
+        .and_then(JsonValue::as_str)
+        .map(str::to_string)
+    {
+        if seen.contains(&parent) {
+            return Err(format!("Version '{parent}' inherits from itself."));
+        }
+        if chain.len() > MAX_INHERITANCE_DEPTH {
+            return Err(format!(
+                "Version '{version_id}' inherits through more than {MAX_INHERITANCE_DEPTH} versions."
+            ));
+        }
+        chain.push(read_version_json(game_directory, &parent)?);
+        seen.push(parent);
+    }
+
+    Ok(chain)
+}
+
+fn library_coordinates(library: &JsonValue) -> Option<(&str, &str, &str)> {
+    let mut parts = library.get("name")?.as_str()?.split(':');
+    Some((parts.next()?, parts.next()?, parts.next()?))
+}
+
+/// Forge versions are published as `<minecraft>-<forge>` and, for old builds, `<minecraft>-<forge>-<minecraft>`.
+fn strip_minecraft_prefix(version: &str) -> String {
+    let mut parts = version.split('-');
+    match (parts.next(), parts.next()) {
+        (Some(first), Some(second)) if first.starts_with("1.") => second.to_string(),
+        _ => version.to_string(),
+    }
+}
+
+fn game_arguments(document: &JsonValue) -> Vec<&str> {
+    let mut arguments: Vec<&str> = document
+        .get("arguments")
+        .and_then(|arguments| arguments.get("game"))
+        .and_then(JsonValue::as_array)
+        .map(|values| values.iter().filter_map(JsonValue::as_str).collect())
+        .unwrap_or_default();
+    if let Some(legacy) = document
+        .get("minecraftArguments")
+        .and_then(JsonValue::as_str)
+    {
+        arguments.extend(legacy.split_whitespace());
+    }
+    arguments
+}
+
+fn argument_value<'a>(arguments: &[&'a str], name: &str) -> Option<&'a str> {
+    arguments
+        .windows(2)
+        .find(|pair| pair[0] == name)
+        .map(|pair| pair[1])
+}
+
+/// Work out the game version and mod loader from a version JSON chain, child first.
+fn detect_version_info(version_id: &str, chain: &[JsonValue]) -> InstanceVersionInfo {
+    let mut info = InstanceVersionInfo {
+        version_id: version_id.to_string(),
+        main_class: chain
+            .iter()
+            .find_map(|document| document.get("mainClass").and_then(JsonValue::as_str))
+            .map(str::to_string),
+        ..InstanceVersionInfo::default()
+    };
+    let mut intermediary_version = None;
+
+    for (group, artifact, version) in chain
+        .iter()
+        .filter_map(|document| document.get("libraries").and_then(JsonValue::as_array))
+        .flatten()
+        .filter_map(library_coordinates)
+    {
+        let detected = match (group, artifact) {
+            ("net.fabricmc", "fabric-loader") => Some(("Fabric", version.to_string())),
+            ("org.quiltmc", "quilt-loader") => Some(("Quilt", version.to_string())),
+            ("net.neoforged", "neoforge") => Some(("NeoForge", version.to_string())),
+            ("net.neoforged", "forge") => Some(("NeoForge", strip_minecraft_prefix(version))),
+            ("net.minecraftforge", "forge" | "fmlloader") => {
+                Some(("Forge", strip_minecraft_prefix(version)))
+            }
+            ("net.fabricmc", "intermediary") => {
+                intermediary_version.get_or_insert_with(|| version.to_string());
+                None
+            }
+            _ => None,
+        };
+        // Quilt installs also pull in Fabric artifacts, so Quilt wins over Fabric.
+        if let Some((loader, version)) = detected {
+            if info.loader.is_none()
+                || (loader == "Quilt" && info.loader.as_deref() == Some("Fabric"))
+            {
+                info.loader = Some(loader.to_string());
+                info.loader_version = Some(version);
+            }
+        }
+    }
+
+    let arguments: Vec<&str> = chain.iter().flat_map(game_arguments).collect();
+    if let Some(version) = argument_value(&arguments, "--fml.neoForgeVersion") {
+        info.loader = Some("NeoForge".to_string());
+        info.loader_version = Some(version.to_string());
+    } else if let Some(version) = argument_value(&arguments, "--fml.forgeVersion") {
+        if info.loader.as_deref() != Some("NeoForge") {
+            info.loader = Some("Forge".to_string());
+            info.loader_version = Some(version.to_string());
+        }
+    }
+
+    if info.loader.is_none() {
+        info.loader = match info.main_class.as_deref() {
+            Some(main_class) if main_class.starts_with("net.fabricmc.") => Some("Fabric"),
+            Some(main_class) if main_class.starts_with("org.quiltmc.") => Some("Quilt"),
+            Some("cpw.mods.bootstraplauncher.BootstrapLauncher")
+            | Some("cpw.mods.modlauncher.Launcher")
+            | Some("net.minecraftforge.bootstrap.ForgeBootstrap") => Some("Forge"),
+            Some("net.minecraft.launchwrapper.Launch")
+                if arguments
+                    .iter()
+                    .any(|argument| argument.contains("FMLTweaker")) =>
+            {
+                Some("Forge")
+            }
+            _ => None,
+        }
+        .map(str::to_string);
+    }
+
+    info.game_version = argument_value(&arguments, "--fml.mcVersion")
+        .map(str::to_string)
+        .or(intermediary_version)
+        .or_else(|| {
+            chain
+                .iter()
+                .find_map(|document| document.get("clientVersion").and_then(JsonValue::as_str))
+                .map(str::to_string)
+        })
+        .or_else(|| {
+            chain
+                .last()
+                .and_then(|document| document.get("id"))
+                .and_then(JsonValue::as_str)
+                .map(str::to_string)
+        });
+
+    info
+}
+
+pub fn read_instance_version_info(
+    game_directory: &Path,
+    version_id: &str,
+) -> Result<InstanceVersionInfo, String> {
+    let chain = read_version_chain(game_directory, version_id)?;
+    Ok(detect_version_info(version_id, &chain))
+}
+
+#[tauri::command]
+pub async fn get_instance_version_info(
+    game_directory: String,
+    version_id: String,
+) -> Result<InstanceVersionInfo, String> {
+    tokio::task::spawn_blocking(move || {
+        read_instance_version_info(Path::new(&game_directory), &version_id)
+    })
+    .await
+    .map_err(|error| format!("Version inspection task failed: {error}"))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn detects_fabric_through_inherits_from() {
+        let chain = [
+            json!({
+                "id": "fabric-loader-0.16.5-1.21.1",
+                "inheritsFrom": "1.21.1",
+                "mainClass": "net.fabricmc.loader.impl.launch.knot.KnotClient",
+                "libraries": [
+                    {"name": "net.fabricmc:intermediary:1.21.1"},
+                    {"name": "net.fabricmc:fabric-loader:0.16.5"}
+                ]
+            }),
+            json!({"id": "1.21.1", "mainClass": "net.minecraft.client.main.Main"}),
+        ];
+        let info = detect_version_info("fabric-loader-0.16.5-1.21.1", &chain);
+        assert_eq!(info.loader.as_deref(), Some("Fabric"));
+        assert_eq!(info.loader_version.as_deref(), Some("0.16.5"));
+        assert_eq!(info.game_version.as_deref(), Some("1.21.1"));
+    }
+
+    #[test]
+    fn quilt_takes_precedence_over_its_fabric_artifacts() {
+        let chain = [json!({
+            "id": "quilt",
+            "libraries": [
+                {"name": "net.fabricmc:fabric-loader:0.15.0"},
+                {"name": "org.quiltmc:quilt-loader:0.26.4"},
+                {"name": "net.fabricmc:intermediary:1.20.1"}
+            ]
+        })];
+        let info = detect_version_info("quilt", &chain);
+        assert_eq!(info.loader.as_deref(), Some("Quilt"));
+        assert_eq!(info.loader_version.as_deref(), Some("0.26.4"));
+        assert_eq!(info.game_version.as_deref(), Some("1.20.1"));
+    }
+
+    #[test]
+    fn detects_forge_and_neoforge_from_arguments_and_libraries() {
+        let forge = [json!({
+            "id": "1.20.1-forge-47.2.0",
+            "mainClass": "cpw.mods.bootstraplauncher.BootstrapLauncher",
+            "arguments": {"game": ["--launchTarget", "forgeclient", "--fml.forgeVersion", "47.2.0", "--fml.mcVersion", "1.20.1"]},
+            "libraries": [{"name": "net.minecraftforge:fmlloader:1.20.1-47.2.0"}]
+        })];
+        let info = detect_version_info("1.20.1-forge-47.2.0", &forge);
+        assert_eq!(info.loader.as_deref(), Some("Forge"));
+        assert_eq!(info.loader_version.as_deref(), Some("47.2.0"));
+        assert_eq!(info.game_version.as_deref(), Some("1.20.1"));
+
+        let neoforge = [json!({
+            "id": "neoforge-21.1.77",
+            "arguments": {"game": ["--fml.neoForgeVersion", "21.1.77", "--fml.fmlVersion", "4.0.31", "--fml.mcVersion", "1.21.1"]},
+            "libraries": [{"name": "net.neoforged.fancymodloader:loader:4.0.31"}]
+        })];
+        let info = detect_version_info("neoforge-21.1.77", &neoforge);
+        assert_eq!(info.loader.as_deref(), Some("NeoForge"));
+        assert_eq!(info.loader_version.as_deref(), Some("21.1.77"));
+    }
+
+    #[test]
+    fn detects_legacy_forge_from_the_tweaker() {
+        let chain = [json!({
+            "id": "1.12.2-forge",
+            "mainClass": "net.minecraft.launchwrapper.Launch",
+            "minecraftArguments": "--username ${auth_player_name} --tweakClass net.minecraftforge.fml.common.launcher.FMLTweaker",
+            "libraries": [{"name": "net.minecraftforge:forge:1.12.2-14.23.5.2859"}],
+            "clientVersion": "1.12.2"
+        })];
+        let info = detect_version_info("1.12.2-forge", &chain);
+        assert_eq!(info.loader.as_deref(), Some("Forge"));
+        assert_eq!(info.loader_version.as_deref(), Some("14.23.5.2859"));
+        assert_eq!(info.game_version.as_deref(), Some("1.12.2"));
+    }
+
+    #[test]
+    fn vanilla_has_no_loader() {
+        let info = detect_version_info("1.21.1", &[json!({"id": "1.21.1"})]);
+        assert!(info.loader.is_none());
+        assert_eq!(info.game_version.as_deref(), Some("1.21.1"));
+    }
+}
@@ -1,6 +1,8 @@
 use regex::Regex;
 use serde::Serialize;
 use std::collections::HashSet;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
 #[cfg(target_os = "windows")]
 use std::os::windows::process::CommandExt;
 use std::path::{Path, PathBuf};
@@ -68,6 +70,8 @@ pub struct DetectedJava {
     pub pathname: String,
     pub version: String,
     pub vendor: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub architecture: Option<JavaArchitecture>,
 }
 
 /// Parse result from `java -version` output.
@@ -450,14 +454,783 @@ pub async fn detect_java_runtimes() -> Result<Vec<DetectedJava>, String> {
             continue;
         }
         let path_str = candidate.to_string_lossy().to_string();
-        if let Some(info) = try_get_java_info(&path_str) {
+        let architecture = read_java_architecture(candidate).ok();
+        let info = if architecture
+            .as_ref()
+            .is_some_and(|architecture| !architecture.runnable)
+        {
+            // Spawning a foreign binary only fails with "exec format error".
+            read_release_info(candidate)
+        } else {
+            try_get_java_info(&path_str)
+        };
+        if let Some(info) = info {
             results.push(DetectedJava {
                 pathname: path_str,
                 version: info.version,
                 vendor: info.vendor,
+                architecture,
             });
         }
     }
 
     Ok(results)
 }
+
+/// Option prefixes whose validity is decided by the JVM at startup.
+const CHECKED_JVM_ARGUMENT_PREFIX: &str = "-X";
+
+/// Lines the JVM prints after any startup failure; they never name the offending option.
+const GENERIC_JVM_ERROR_LINES: [&str; 3] = [
+    "Error occurred during initialization of VM",
+    "Error: Could not create the Java Virtual Machine.",
+    "Error: A fatal exception has occurred. Program will exit.",
+];
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UnsupportedJvmArgument {
+    pub argument: String,
+    pub reason: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JvmArgumentCheck {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub java_version: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub major_version: Option<u32>,
+    pub supported: Vec<String>,
+    pub unsupported: Vec<UnsupportedJvmArgument>,
+    /// Arguments that are not VM options (system properties, agents, ...) and were not run.
+    pub unchecked: Vec<String>,
+    pub warnings: Vec<String>,
+    /// Set when the arguments fail together although each one starts the JVM on its own.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub conflict: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JvmArgumentPreset {
+    pub id: &'static str,
+    pub name: &'static str,
+    pub description: &'static str,
+    pub min_major_version: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_major_version: Option<u32>,
+    /// Vendors (as reported by `detect_vendor`) whose builds lack the options.
+    #[serde(skip_serializing_if = "<[_]>::is_empty")]
+    pub excluded_vendors: &'static [&'static str],
+    pub arguments: Vec<&'static str>,
+}
+
+/// Outcome of starting the JVM with a set of options and `-version` as the no-op main.
+struct JvmProbe {
+    success: bool,
+    output: String,
+}
+
+/// Parse a Java version string into its major version number.
+fn java_major_version(version: &str) -> Option<u32> {
+    // "1.8.0_351" -> 8, "17.0.2" -> 17, "21" -> 21, "22-ea" -> 22
+    let mut parts = version.split(['.', '_', '-', '+']);
+    let first = parts.next()?.parse::<u32>().ok()?;
+    if first == 1 {
+        parts.next()?.parse::<u32>().ok()
+    } else {
+        Some(first)
+    }
+}
+
+fn probe_jvm_arguments(java_path: &str, arguments: &[&str]) -> Result<JvmProbe, String> {
+    let mut command = java_command(java_path);
+    command.args(arguments);
+    if arguments.contains(&"-XX:+AlwaysPreTouch") {
+        // Pre-touching a large initial heap only slows the probe down.
+        command.arg("-XX:-AlwaysPreTouch");
+    }
+    let output = command
+        .arg("-XX:-IgnoreUnrecognizedVMOptions")
+        .arg("-version")
+        .output()
+        .map_err(|e| format!("Failed to execute java: {}", e))?;
+
+    Ok(JvmProbe {
+        success: output.status.success(),
+        output: format!(
+            "{}{}",
+            String::from_utf8_lossy(&output.stdout),
+            String::from_utf8_lossy(&output.stderr)
+        ),
+    })
+}
+
+/// Pick the line of a failed JVM start that explains the failure.
+fn jvm_failure_reason(output: &str) -> String {
+    output
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty() && !GENERIC_JVM_ERROR_LINES.contains(line))
+        .unwrap_or("The JVM failed to start with no output")
+        .to_string()
+}
+
+/// Collect `... VM warning: ...` lines from a successful JVM start.
+fn jvm_warnings(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .map(str::trim)
+        .filter(|line| line.to_lowercase().contains("warning:"))
+        .map(str::to_string)
+        .collect()
+}
+
+fn is_unlock_argument(argument: &str) -> bool {
+    argument.starts_with("-XX:+Unlock") && argument.ends_with("VMOptions")
+}
+
+fn check_jvm_arguments_blocking(
+    java_path: &str,
+    arguments: Vec<String>,
+) -> Result<JvmArgumentCheck, String> {
+    let info = try_get_java_info(java_path);
+    let major_version = info
+        .as_ref()
+        .and_then(|info| java_major_version(&info.version));
+
+    let (checked, unchecked): (Vec<String>, Vec<String>) = arguments
+        .into_iter()
+        .map(|argument| argument.trim().to_string())
+        .filter(|argument| !argument.is_empty())
+        .partition(|argument| argument.starts_with(CHECKED_JVM_ARGUMENT_PREFIX));
+    let checked_refs: Vec<&str> = checked.iter().map(String::as_str).collect();
+
+    let mut result = JvmArgumentCheck {
+        java_version: info.map(|info| info.version),
+        major_version,
+        supported: Vec::new(),
+        unsupported: Vec::new(),
+        unchecked,
+        warnings: Vec::new(),
+        conflict: None,
+    };
+
+    let combined = probe_jvm_arguments(java_path, &checked_refs)?;
+    if combined.success {
+        result.supported = checked;
+        result.warnings = jvm_warnings(&combined.output);
+        return Ok(result);
+    }
+
+    // The JVM stops at the first bad option, so narrow it down one option at a time.
+    // Unlock options stay in front of every probe because experimental and diagnostic
+    // options are rejected without them.
+    let unlock_arguments: Vec<&str> = checked_refs
+        .iter()
+        .copied()
+        .filter(|argument| is_unlock_argument(argument))
+        .collect();
+    for argument in &checked {
+        let mut probe_arguments = unlock_arguments.clone();
+        if !is_unlock_argument(argument) {
+            probe_arguments.push(argument);
+        }
+        let probe = probe_jvm_arguments(java_path, &probe_arguments)?;
+        if probe.success {
+            result.supported.push(argument.clone());
+            for warning in jvm_warnings(&probe.output) {
+                if !result.warnings.contains(&warning) {
+                    result.warnings.push(warning);
+                }
+            }
+        } else {
+            result.unsupported.push(UnsupportedJvmArgument {
+                argument: argument.clone(),
+                reason: jvm_failure_reason(&probe.output),
+            });
+        }
+    }
+
+    if result.unsupported.is_empty() {
+        result.conflict = Some(jvm_failure_reason(&combined.output));
+    }
+
+    Ok(result)
+}
+
+/// Built-in JVM tuning presets that are known to start on the listed Java versions.
+fn jvm_argument_presets() -> Vec<JvmArgumentPreset> {
+    vec![
+        JvmArgumentPreset {
+            id: "aikar",
+            name: "Aikar's Flags",
+            description: "G1 tuning for large heaps, widely used for modded Minecraft.",
+            min_major_version: 8,
+            max_major_version: None,
+            excluded_vendors: &[],
+            arguments: vec![
+                "-XX:+UseG1GC",
+                "-XX:+ParallelRefProcEnabled",
+                "-XX:MaxGCPauseMillis=200",
+                "-XX:+UnlockExperimentalVMOptions",
+                "-XX:+DisableExplicitGC",
+                "-XX:+AlwaysPreTouch",
+                "-XX:G1NewSizePercent=30",
+                "-XX:G1MaxNewSizePercent=40",
+                "-XX:G1HeapRegionSize=8M",
+                "-XX:G1ReservePercent=20",
+                "-XX:G1HeapWastePercent=5",
+                "-XX:G1MixedGCCountTarget=4",
+                "-XX:InitiatingHeapOccupancyPercent=15",
+                "-XX:G1MixedGCLiveThresholdPercent=90",
+                "-XX:SurvivorRatio=32",
+                "-XX:+PerfDisableSharedMem",
+                "-XX:MaxTenuringThreshold=1",
+            ],
+        },
+        JvmArgumentPreset {
+            id: "zgc",
+            name: "ZGC",
+            description: "Low-pause collector, production-ready since Java 15.",
+            min_major_version: 17,
+            max_major_version: None,
+            excluded_vendors: &[],
+            arguments: vec!["-XX:+UseZGC"],
+        },
+        JvmArgumentPreset {
+            id: "zgc-generational",
+            name: "Generational ZGC",
+            description: "Generational mode of ZGC; it became the only mode in Java 23.",
+            min_major_version: 21,
+            max_major_version: Some(22),
+            excluded_vendors: &[],
+            arguments: vec!["-XX:+UseZGC", "-XX:+ZGenerational"],
+        },
+        JvmArgumentPreset {
+            id: "shenandoah",
+            name: "Shenandoah",
+            description: "Low-pause collector; missing from Oracle JDK and Oracle GraalVM builds.",
+            min_major_version: 17,
+            max_major_version: None,
+            excluded_vendors: &["Oracle Java", "GraalVM"],
+            arguments: vec!["-XX:+UseShenandoahGC"],
+        },
+    ]
+}
+
+#[tauri::command]
+pub async fn check_jvm_arguments(
+    java_path: String,
+    arguments: Vec<String>,
+) -> Result<JvmArgumentCheck, String> {
+    tokio::task::spawn_blocking(move || check_jvm_arguments_blocking(&java_path, arguments))
+        .await
+        .map_err(|error| format!("JVM argument check task failed: {error}"))?
+}
+
+/// List presets for a Java version; vendor-specific presets need a known `vendor`.
+#[tauri::command]
+pub async fn get_jvm_argument_presets(
+    major_version: u32,
+    vendor: Option<String>,
+) -> Result<Vec<JvmArgumentPreset>, String> {
+    Ok(jvm_argument_presets()
+        .into_iter()
+        .filter(|preset| {
+            major_version >= preset.min_major_version
+                && preset
+                    .max_major_version
+                    .is_none_or(|max_major_version| major_version <= max_major_version)
+                && (preset.excluded_vendors.is_empty()
+                    || vendor.as_deref().is_some_and(|vendor| {
+                        vendor != "Java" && !preset.excluded_vendors.contains(&vendor)
+                    }))
+        })
+        .collect())
+}
+
+const MAX_PE_HEADER_OFFSET: u32 = 64 * 1024;
+const MAX_MACHO_FAT_SLICES: u32 = 32;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecutableArchitecture {
+    pub architecture: String,
+    pub bitness: u32,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JavaArchitecture {
+    pub format: String,
+    /// One entry per slice; only Mach-O universal binaries have more than one.
+    pub slices: Vec<ExecutableArchitecture>,
+    pub host_architecture: String,
+    pub matches_host: bool,
+    /// Whether the host can run it, natively or through translation such as Rosetta 2.
+    pub runnable: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub warning: Option<String>,
+}
+
+fn host_bitness() -> u32 {
+    if cfg!(target_pointer_width = "64") {
+        64
+    } else {
+        32
+    }
+}
+
+fn header_u16(bytes: &[u8], offset: usize, big_endian: bool) -> Option<u16> {
+    let bytes: [u8; 2] = bytes.get(offset..offset + 2)?.try_into().ok()?;
+    Some(if big_endian {
+        u16::from_be_bytes(bytes)
+    } else {
+        u16::from_le_bytes(bytes)
+    })
+}
+
+fn header_u32(bytes: &[u8], offset: usize, big_endian: bool) -> Option<u32> {
+    let bytes: [u8; 4] = bytes.get(offset..offset + 4)?.try_into().ok()?;
+    Some(if big_endian {
+        u32::from_be_bytes(bytes)
+    } else {
+        u32::from_le_bytes(bytes)
+    })
+}
+
+fn read_header_range(file: &mut File, offset: u64, length: usize) -> Result<Vec<u8>, String> {
+    file.seek(SeekFrom::Start(offset))
+        .map_err(|e| format!("Failed to seek in the executable: {}", e))?;
+    let mut bytes = Vec::with_capacity(length);
+    file.take(length as u64)
+        .read_to_end(&mut bytes)
+        .map_err(|e| format!("Failed to read the executable header: {}", e))?;
+    Ok(bytes)
+}
+
+/// Architecture names follow `std::env::consts::ARCH` so they compare directly with the host.
+fn elf_architecture(machine: u16, bitness: u32) -> String {
+    match (machine, bitness) {
+        (3, _) => "x86".to_string(),
+        (62, _) => "x86_64".to_string(),
+        (40, _) => "arm".to_string(),
+        (183, _) => "aarch64".to_string(),
+        (8, 64) => "mips64".to_string(),
+        (8, _) => "mips".to_string(),
+        (20, _) => "powerpc".to_string(),
+        (21, _) => "powerpc64".to_string(),
+        (22, 64) => "s390x".to_string(),
+        (43, _) => "sparc64".to_string(),
+        (243, 64) => "riscv64".to_string(),
+        (243, _) => "riscv32".to_string(),
+        (258, _) => "loongarch64".to_string(),
+        (machine, _) => format!("unknown ({machine:#x})"),
+    }
+}
+
+fn pe_architecture(machine: u16) -> String {
+    match machine {
+        0x014c => "x86".to_string(),
+        0x8664 => "x86_64".to_string(),
+        0x01c0 | 0x01c4 => "arm".to_string(),
+        0xaa64 => "aarch64".to_string(),
+        0x5064 => "riscv64".to_string(),
+        machine => format!("unknown ({machine:#x})"),
+    }
+}
+
+fn macho_architecture(cpu_type: u32) -> ExecutableArchitecture {
+    const CPU_ARCH_ABI64: u32 = 0x0100_0000;
+    let bitness = if cpu_type & CPU_ARCH_ABI64 != 0 {
+        64
+    } else {
+        32
+    };
+    let architecture = match cpu_type {
+        7 => "x86".to_string(),
+        0x0100_0007 => "x86_64".to_string(),
+        12 => "arm".to_string(),
+        0x0100_000c => "aarch64".to_string(),
+        18 => "powerpc".to_string(),
+        0x0100_0012 => "powerpc64".to_string(),
+        cpu_type => format!("unknown ({cpu_type:#x})"),
+    };
+    ExecutableArchitecture {
+        architecture,
+        bitness,
+    }
+}
+
+fn parse_elf_header(header: &[u8]) -> Result<ExecutableArchitecture, String> {
+    let bitness = match header.get(4) {
+        Some(1) => 32,
+        Some(2) => 64,
+        _ => return Err("The ELF class is invalid.".to_string()),
+    };
+    let big_endian = match header.get(5) {
+        Some(1) => false,
+        Some(2) => true,
+        _ => return Err("The ELF data encoding is invalid.".to_string()),
+    };
+    let machine = header_u16(header, 18, big_endian)
+        .ok_or_else(|| "The ELF header is truncated.".to_string())?;
+    Ok(ExecutableArchitecture {
+        architecture: elf_architecture(machine, bitness),
+        bitness,
+    })
+}
+
+fn parse_pe_header(file: &mut File, header: &[u8]) -> Result<ExecutableArchitecture, String> {
+    let pe_offset = header_u32(header, 0x3c, false)
+        .ok_or_else(|| "The DOS header is truncated.".to_string())?;
+    if pe_offset > MAX_PE_HEADER_OFFSET {
+        return Err("The PE header offset is out of range.".to_string());
+    }
+    let pe_header = read_header_range(file, pe_offset as u64, 26)?;
+    if pe_header.get(0..4) != Some(b"PE\0\0") {
+        return Err("The PE signature is missing.".to_string());
+    }
+    let machine = header_u16(&pe_header, 4, false)
+        .ok_or_else(|| "The PE file header is truncated.".to_string())?;
+    let bitness = match header_u16(&pe_header, 24, false) {
+        Some(0x10b) => 32,
+        Some(0x20b) => 64,
+        _ => return Err("The PE optional header magic is invalid.".to_string()),
+    };
+    Ok(ExecutableArchitecture {
+        architecture: pe_architecture(machine),
+        bitness,
+    })
+}
+
+fn parse_macho_fat_header(
+    file: &mut File,
+    header: &[u8],
+    is_64: bool,
+) -> Result<Vec<ExecutableArchitecture>, String> {
+    let slice_count = header_u32(header, 4, true)
+        .ok_or_else(|| "The universal binary header is truncated.".to_string())?;
+    if slice_count == 0 || slice_count > MAX_MACHO_FAT_SLICES {
+        return Err(format!(
+            "The universal binary declares {slice_count} slices."
+        ));
+    }
+    let entry_size = if is_64 { 32 } else { 20 };
+    let entries = read_header_range(file, 8, slice_count as usize * entry_size)?;
+    (0..slice_count as usize)
+        .map(|index| {
+            header_u32(&entries, index * entry_size, true)
+                .map(macho_architecture)
+                .ok_or_else(|| "The universal binary slice table is truncated.".to_string())
+        })
+        .collect()
+}
+
+/// Read the executable headers of a `java` binary without running it.
+fn read_java_architecture(path: &Path) -> Result<JavaArchitecture, String> {
+    let mut file = File::open(path).map_err(|e| format!("Failed to open the executable: {}", e))?;
+    let header = read_header_range(&mut file, 0, 64)?;
+
+    let (format, slices) = match header.get(0..4) {
+        Some([0x7f, b'E', b'L', b'F']) => ("ELF", vec![parse_elf_header(&header)?]),
+        Some([b'M', b'Z', _, _]) => ("PE", vec![parse_pe_header(&mut file, &header)?]),
+        Some([0xfe, 0xed, 0xfa, 0xce | 0xcf]) | Some([0xce | 0xcf, 0xfa, 0xed, 0xfe]) => {
+            let big_endian = header[0] == 0xfe;
+            let cpu_type = header_u32(&header, 4, big_endian)
+                .ok_or_else(|| "The Mach-O header is truncated.".to_string())?;
+            ("Mach-O", vec![macho_architecture(cpu_type)])
+        }
+        Some([0xca, 0xfe, 0xba, magic @ (0xbe | 0xbf)]) => (
+            "Mach-O Universal",
+            parse_macho_fat_header(&mut file, &header, *magic == 0xbf)?,
+        ),
+        _ => return Err("The file is not an ELF, PE or Mach-O executable.".to_string()),
+    };
+
+    let host_architecture = env::consts::ARCH.to_string();
+    let matches_host = slices
+        .iter()
+        .any(|slice| slice.architecture == host_architecture && slice.bitness == host_bitness());
+    let warning = if matches_host {
+        None
+    } else {
+        translation_warning(env::consts::OS, &host_architecture, &slices)
+    };
+    Ok(JavaArchitecture {
+        format: format.to_string(),
+        slices,
+        host_architecture,
+        matches_host,
+        runnable: matches_host || warning.is_some(),
+        warning,
+    })
+}
+
+/// Explain how a foreign executable still runs, for hosts that translate it transparently.
+fn translation_warning(
+    host_os: &str,
+    host_architecture: &str,
+    slices: &[ExecutableArchitecture],
+) -> Option<String> {
+    let translated = host_os == "macos"
+        && host_architecture == "aarch64"
+        && slices
+            .iter()
+            .any(|slice| slice.architecture == "x86_64" && slice.bitness == 64);
+    translated.then(|| {
+        "This x86_64 Java runs under Rosetta 2, which is noticeably slower than a native arm64 Java."
+            .to_string()
+    })
+}
+
+/// Read the version and vendor from the JDK `release` file next to `bin/`.
+fn read_release_info(java_path: &Path) -> Option<JavaInfo> {
+    let release = fs::read_to_string(java_path.parent()?.parent()?.join("release")).ok()?;
+    let field = |key: &str| {
+        release.lines().find_map(|line| {
+            let (name, value) = line.split_once('=')?;
+            (name.trim() == key).then(|| value.trim().trim_matches('"').to_string())
+        })
+    };
+    let version = field("JAVA_VERSION").filter(|version| !version.is_empty())?;
+    let vendor = detect_vendor(&field("IMPLEMENTOR").unwrap_or_default());
+    Some(JavaInfo { version, vendor })
+}
+
+#[tauri::command]
+pub async fn get_java_architecture(java_path: String) -> Result<JavaArchitecture, String> {
+    tokio::task::spawn_blocking(move || {
+        read_java_architecture(&resolve_path(Path::new(&java_path)))
+    })
+    .await
+    .map_err(|error| format!("Java architecture task failed: {error}"))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_java_major_versions() {
+        assert_eq!(java_major_version("1.8.0_351"), Some(8));
+        assert_eq!(java_major_version("17.0.2"), Some(17));
+        assert_eq!(java_major_version("21"), Some(21));
+        assert_eq!(java_major_version("22-ea"), Some(22));
+        assert_eq!(java_major_version("unknown"), None);
+    }
+
+    #[test]
+    fn picks_the_specific_jvm_failure_reason() {
+        let output = "Unrecognized VM option 'UseShenandoahGC'\n\
+                      Error: Could not create the Java Virtual Machine.\n\
+                      Error: A fatal exception has occurred. Program will exit.\n";
+        assert_eq!(
+            jvm_failure_reason(output),
+            "Unrecognized VM option 'UseShenandoahGC'"
+        );
+        assert_eq!(
+            jvm_failure_reason("Error: Could not create the Java Virtual Machine.\n"),
+            "The JVM failed to start with no output"
+        );
+    }
+
+    #[test]
+    fn collects_jvm_warnings_only() {
+        let output = "OpenJDK 64-Bit Server VM warning: Ignoring option UseConcMarkSweepGC; support was removed in 14.0\n\
+                      openjdk version \"17.0.2\" 2022-01-18\n";
+        assert_eq!(
+            jvm_warnings(output),
+            ["OpenJDK 64-Bit Server VM warning: Ignoring option UseConcMarkSweepGC; support was removed in 14.0"]
+        );
+    }
+
+    #[tokio::test]
+    async fn filters_presets_by_major_version() {
+        let ids = |major_version| async move {
+            get_jvm_argument_presets(major_version, Some("Eclipse Temurin".to_string()))
+                .await
+                .expect("Presets should be listed")
+                .into_iter()
+                .map(|preset| preset.id)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(ids(8).await, ["aikar"]);
+        assert_eq!(
+            ids(21).await,
+            ["aikar", "zgc", "zgc-generational", "shenandoah"]
+        );
+        assert_eq!(ids(23).await, ["aikar", "zgc", "shenandoah"]);
+    }
+
+    #[tokio::test]
+    async fn hides_shenandoah_unless_the_vendor_ships_it() {
+        let ids = |vendor: Option<&str>| {
+            let vendor = vendor.map(str::to_string);
+            async move {
+                get_jvm_argument_presets(21, vendor)
+                    .await
+                    .expect("Presets should be listed")
+                    .into_iter()
+                    .map(|preset| preset.id)
+                    .collect::<Vec<_>>()
+            }
+        };
+        assert!(ids(Some("OpenJDK")).await.contains(&"shenandoah"));
+        assert!(!ids(Some("Oracle Java")).await.contains(&"shenandoah"));
+        assert!(!ids(Some("Java")).await.contains(&"shenandoah"));
+        assert!(!ids(None).await.contains(&"shenandoah"));
+    }
+
+    fn write_fixture(name: &str, bytes: &[u8]) -> PathBuf {
+        let filename = format!(
+            "epherome-{name}-{}-{}",
+            std::process::id(),
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_nanos()
+        );
+        let path = env::temp_dir().join(filename);
+        fs::write(&path, bytes).expect("Executable fixture should be written");
+        path
+    }
+
+    fn fixture_architecture(name: &str, bytes: &[u8]) -> Result<JavaArchitecture, String> {
+        let path = write_fixture(name, bytes);
+        let result = read_java_architecture(&path);
+        let _ = fs::remove_file(path);
+        result
+    }
+
+    fn slice_summary(architecture: &JavaArchitecture) -> Vec<(&str, u32)> {
+        architecture
+            .slices
+            .iter()
+            .map(|slice| (slice.architecture.as_str(), slice.bitness))
+            .collect()
+    }
+
+    #[test]
+    fn reads_elf_headers_of_both_classes_and_byte_orders() {
+        let mut elf64 = vec![0_u8; 64];
+        elf64[0..4].copy_from_slice(b"\x7fELF");
+        elf64[4] = 2;
+        elf64[5] = 1;
+        elf64[18..20].copy_from_slice(&183_u16.to_le_bytes());
+        let architecture =
+            fixture_architecture("elf-aarch64", &elf64).expect("ELF64 header should parse");
+        assert_eq!(architecture.format, "ELF");
+        assert_eq!(slice_summary(&architecture), [("aarch64", 64)]);
+
+        let mut elf32 = vec![0_u8; 52];
+        elf32[0..4].copy_from_slice(b"\x7fELF");
+        elf32[4] = 1;
+        elf32[5] = 1;
+        elf32[18..20].copy_from_slice(&3_u16.to_le_bytes());
+        let architecture =
+            fixture_architecture("elf-i386", &elf32).expect("ELF32 header should parse");
+        assert_eq!(slice_summary(&architecture), [("x86", 32)]);
+
+        let mut elf_big_endian = vec![0_u8; 64];
+        elf_big_endian[0..4].copy_from_slice(b"\x7fELF");
+        elf_big_endian[4] = 2;
+        elf_big_endian[5] = 2;
+        elf_big_endian[18..20].copy_from_slice(&22_u16.to_be_bytes());
+        let architecture = fixture_architecture("elf-s390x", &elf_big_endian)
+            .expect("Big-endian ELF header should parse");
+        assert_eq!(slice_summary(&architecture), [("s390x", 64)]);
+    }
+
+    #[test]
+    fn reads_pe_headers() {
+        let pe_offset = 0x80_usize;
+        let mut pe64 = vec![0_u8; pe_offset + 26];
+        pe64[0..2].copy_from_slice(b"MZ");
+        pe64[0x3c..0x40].copy_from_slice(&(pe_offset as u32).to_le_bytes());
+        pe64[pe_offset..pe_offset + 4].copy_from_slice(b"PE\0\0");
+        pe64[pe_offset + 4..pe_offset + 6].copy_from_slice(&0x8664_u16.to_le_bytes());
+        pe64[pe_offset + 24..pe_offset + 26].copy_from_slice(&0x20b_u16.to_le_bytes());
+        let architecture = fixture_architecture("pe-x64", &pe64).expect("PE32+ should parse");
+        assert_eq!(architecture.format, "PE");
+        assert_eq!(slice_summary(&architecture), [("x86_64", 64)]);
+
+        let mut pe32 = pe64.clone();
+        pe32[pe_offset + 4..pe_offset + 6].copy_from_slice(&0x014c_u16.to_le_bytes());
+        pe32[pe_offset + 24..pe_offset + 26].copy_from_slice(&0x10b_u16.to_le_bytes());
+        let architecture = fixture_architecture("pe-x86", &pe32).expect("PE32 should parse");
+        assert_eq!(slice_summary(&architecture), [("x86", 32)]);
+
+        let mut missing_signature = pe64;
+        missing_signature[pe_offset..pe_offset + 4].copy_from_slice(b"XX\0\0");
+        assert!(fixture_architecture("pe-invalid", &missing_signature)
+            .expect_err("A missing PE signature should be rejected")
+            .contains("PE signature"));
+    }
+
+    #[test]
+    fn reads_thin_and_universal_macho_headers() {
+        let mut thin = vec![0_u8; 32];
+        thin[0..4].copy_from_slice(&0xfeed_facf_u32.to_le_bytes());
+        thin[4..8].copy_from_slice(&0x0100_000c_u32.to_le_bytes());
+        let architecture =
+            fixture_architecture("macho-arm64", &thin).expect("Mach-O header should parse");
+        assert_eq!(architecture.format, "Mach-O");
+        assert_eq!(slice_summary(&architecture), [("aarch64", 64)]);
+
+        let mut universal = vec![0_u8; 8 + 2 * 20];
+        universal[0..4].copy_from_slice(&0xcafe_babe_u32.to_be_bytes());
+        universal[4..8].copy_from_slice(&2_u32.to_be_bytes());
+        universal[8..12].copy_from_slice(&0x0100_0007_u32.to_be_bytes());
+        universal[28..32].copy_from_slice(&0x0100_000c_u32.to_be_bytes());
+        let architecture = fixture_architecture("macho-universal", &universal)
+            .expect("Universal header should parse");
+        assert_eq!(architecture.format, "Mach-O Universal");
+        assert_eq!(
+            slice_summary(&architecture),
+            [("x86_64", 64), ("aarch64", 64)]
+        );
+        assert_eq!(
+            architecture.matches_host,
+            cfg!(any(target_arch = "x86_64", target_arch = "aarch64"))
+        );
+    }
+
+    #[test]
+    fn flags_foreign_architectures_and_rejects_scripts() {
+        let mut elf = vec![0_u8; 64];
+        elf[0..4].copy_from_slice(b"\x7fELF");
+        elf[4] = 2;
+        elf[5] = 1;
+        let foreign_machine: u16 = if cfg!(target_arch = "x86_64") {
+            183
+        } else {
+            62
+        };
+        elf[18..20].copy_from_slice(&foreign_machine.to_le_bytes());
+        let architecture =
+            fixture_architecture("elf-foreign", &elf).expect("ELF header should parse");
+        assert!(!architecture.matches_host);
+        assert_eq!(architecture.host_architecture, env::consts::ARCH);
+
+        assert!(fixture_architecture("script", b"#!/bin/sh\nexec java \"$@\"\n").is_err());
+    }
+
+    #[test]
+    fn treats_x86_64_under_rosetta_as_runnable_with_a_warning() {
+        let x86_64 = [ExecutableArchitecture {
+            architecture: "x86_64".to_string(),
+            bitness: 64,
+        }];
+        assert!(translation_warning("macos", "aarch64", &x86_64).is_some());
+        assert_eq!(translation_warning("linux", "aarch64", &x86_64), None);
+        assert_eq!(translation_warning("macos", "x86_64", &x86_64), None);
+        let i686 = [ExecutableArchitecture {
+            architecture: "x86".to_string(),
+            bitness: 32,
+        }];
+        assert_eq!(translation_warning("macos", "aarch64", &i686), None);
+    }
+}
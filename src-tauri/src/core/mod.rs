@@ -1,4 +1,15 @@
+pub mod archive;
 pub mod auth;
+pub mod curseforge;
+pub mod datapacks;
+pub mod instances;
 pub mod java;
+pub mod modpacks;
+pub mod modrinth;
 pub mod mods;
+pub mod nbt;
+pub mod packs;
 pub mod runner;
+#[cfg(test)]
+pub(crate) mod test_fixtures;
+pub mod version;
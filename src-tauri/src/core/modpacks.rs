@@ -0,0 +1,1615 @@
+use crate::core::archive::{preflight_zip_archive, read_zip_entries, validate_archive_path};
+use crate::core::curseforge::{CurseForgeClient, CurseForgeFile, CurseForgeMod};
+use crate::core::instances::{read_instance_version_info, InstanceVersionInfo};
+use crate::core::modrinth::modrinth_download_urls;
+use crate::core::mods::{
+    mod_file_hashes, mods_directory, scan_local_mods_blocking, ModFileHasher, ModFileHashes,
+    MODS_OPERATION_LOCK,
+};
+use crate::utils::http::get_http_client;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::fs::{self, File};
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Emitter};
+use tokio::io::AsyncWriteExt;
+
+const MODRINTH_PACK_INDEX_PATH: &str = "modrinth.index.json";
+const CURSEFORGE_PACK_MANIFEST_PATH: &str = "manifest.json";
+const CURSEFORGE_RESOURCE_PACK_CLASS: u64 = 12;
+const CURSEFORGE_SHADER_PACK_CLASS: u64 = 6552;
+const MAX_PACK_INDEX_SIZE: u64 = 16 * 1024 * 1024;
+const MAX_PACK_FILES: usize = 8192;
+const MAX_PACK_FILE_SIZE: u64 = 1024 * 1024 * 1024;
+const MAX_PACK_OVERRIDES_SIZE: u64 = 4 * 1024 * 1024 * 1024;
+/// Applied in order, so client-only overrides win over shared ones.
+const MODRINTH_PACK_OVERRIDE_DIRECTORIES: [&str; 2] = ["overrides/", "client-overrides/"];
+const MODRINTH_PACK_EXPORT_OVERRIDES: &str = "overrides";
+/// Always exported alongside the folders the user picks.
+const DEFAULT_EXPORT_DIRECTORIES: [&str; 1] = ["config"];
+const MODPACK_IMPORT_PROGRESS_EVENT: &str = "modpack-import-progress";
+const MOJANG_VERSION_MANIFEST_URL: &str =
+    "https://piston-meta.mojang.com/mc/game/version_manifest_v2.json";
+const FABRIC_META_URL: &str = "https://meta.fabricmc.net/v2";
+const QUILT_META_URL: &str = "https://meta.quiltmc.org/v3";
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ModrinthPackIndex {
+    format_version: u32,
+    game: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    version_id: Option<String>,
+    name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    summary: Option<String>,
+    #[serde(default)]
+    files: Vec<ModrinthPackFile>,
+    dependencies: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ModrinthPackFile {
+    path: String,
+    hashes: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    env: Option<ModrinthPackEnv>,
+    downloads: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    file_size: Option<u64>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct ModrinthPackEnv {
+    client: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CurseForgePackManifest {
+    minecraft: CurseForgePackMinecraft,
+    #[serde(default)]
+    manifest_type: Option<String>,
+    name: String,
+    #[serde(default)]
+    version: Option<String>,
+    #[serde(default)]
+    files: Vec<CurseForgePackFile>,
+    #[serde(default)]
+    overrides: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CurseForgePackMinecraft {
+    version: String,
+    #[serde(default)]
+    mod_loaders: Vec<CurseForgePackLoader>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CurseForgePackLoader {
+    id: String,
+    #[serde(default)]
+    primary: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct CurseForgePackFile {
+    #[serde(rename = "projectID")]
+    project_id: u64,
+    #[serde(rename = "fileID")]
+    file_id: u64,
+    #[serde(default = "default_required")]
+    required: bool,
+}
+
+fn default_required() -> bool {
+    true
+}
+
+/// A pack file to download, already checked for a safe path and the required hashes.
+#[derive(Debug)]
+struct PackDownload {
+    path: String,
+    urls: Vec<String>,
+    sha1: String,
+    sha512: Option<String>,
+    size: Option<u64>,
+}
+
+/// Game version and mod loader a pack was made for.
+#[derive(Debug, PartialEq, Eq)]
+struct PackTarget {
+    game_version: String,
+    /// Launcher loader name ("Fabric", "Quilt", "Forge" or "NeoForge") and version.
+    loader: Option<(String, String)>,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModpackImportProgress {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub import_id: Option<String>,
+    /// One of "files", "overrides" or "version".
+    pub stage: String,
+    pub completed: usize,
+    pub total: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModpackImport {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pack_version: Option<String>,
+    /// Version to launch the new instance with.
+    pub version_id: String,
+    pub game_version: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub loader: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub loader_version: Option<String>,
+    /// False when the loader needs its own installer, as Forge and NeoForge do.
+    pub loader_installed: bool,
+    /// Pack files skipped because they are unsupported on the client or optional.
+    pub skipped: Vec<String>,
+    /// Files whose authors disabled third-party downloads; they must be fetched by hand.
+    pub manual_downloads: Vec<ModpackManualDownload>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModpackManualDownload {
+    pub project_id: u64,
+    pub file_id: u64,
+    pub project_name: String,
+    pub filename: String,
+    /// Where the file belongs inside the instance, such as `mods/example.jar`.
+    pub path: String,
+    pub page_url: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModpackExportOptions {
+    pub name: String,
+    #[serde(default)]
+    pub pack_version: Option<String>,
+    #[serde(default)]
+    pub summary: Option<String>,
+    /// Instance folders to pack into `overrides/` besides `config`, such as `resourcepacks`.
+    #[serde(default)]
+    pub override_directories: Vec<String>,
+    #[serde(default)]
+    pub exclude_disabled_mods: bool,
+    #[serde(default)]
+    pub api_base_url: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModpackExport {
+    /// Mods listed as Modrinth downloads, by pack path.
+    pub files: Vec<String>,
+    /// Archive paths of everything packed into `overrides/`.
+    pub overrides: Vec<String>,
+}
+
+/// A jar from the instance's mods folder with what the index needs to reference it.
+#[derive(Debug, PartialEq, Eq)]
+struct ExportedMod {
+    filename: String,
+    enabled: bool,
+    size: u64,
+    hashes: ModFileHashes,
+}
+
+/// Downloads, manual downloads and skipped optional files.
+type CurseForgePackPlan = (Vec<PackDownload>, Vec<ModpackManualDownload>, Vec<String>);
+type ImportProgress<'a> = &'a (dyn Fn(&str, usize, usize, Option<&str>) + Sync);
+
+fn validate_pack_path(path: &str) -> Result<(), String> {
+    validate_archive_path(path)
+        .and_then(|()| {
+            if path.split('/').any(|component| component.contains(':')) {
+                return Err("The path is not a relative archive path.".to_string());
+            }
+            Ok(())
+        })
+        .map_err(|error| format!("Pack path '{path}' is unsafe: {error}"))
+}
+
+/// Read and parse a JSON document stored at `entry_path` inside a pack.
+fn read_pack_json<T: DeserializeOwned>(pack_path: &Path, entry_path: &str) -> Result<T, String> {
+    let mut file = File::open(pack_path)
+        .map_err(|error| format!("Failed to open '{}': {error}", pack_path.display()))?;
+    let bytes = read_zip_entries(&mut file, &[entry_path], MAX_PACK_INDEX_SIZE)
+        .map_err(|error| format!("'{}' is not a valid pack: {error}", pack_path.display()))?
+        .pop()
+        .flatten()
+        .ok_or_else(|| format!("The pack does not contain {entry_path}."))?
+        .map_err(|error| format!("Failed to read {entry_path}: {error}"))?;
+    serde_json::from_slice(&bytes).map_err(|error| format!("{entry_path} is invalid: {error}"))
+}
+
+fn modrinth_pack_target(dependencies: &BTreeMap<String, String>) -> Result<PackTarget, String> {
+    let mut game_version = None;
+    let mut loader = None;
+    for (dependency, version) in dependencies {
+        let name = match dependency.as_str() {
+            "minecraft" => {
+                game_version = Some(version.clone());
+                continue;
+            }
+            "fabric-loader" => "Fabric",
+            "quilt-loader" => "Quilt",
+            "forge" => "Forge",
+            "neoforge" => "NeoForge",
+            _ => return Err(format!("The pack depends on unsupported '{dependency}'.")),
+        };
+        if loader.is_some() {
+            return Err("The pack depends on more than one mod loader.".to_string());
+        }
+        loader = Some((name.to_string(), version.clone()));
+    }
+    Ok(PackTarget {
+        game_version: game_version
+            .ok_or_else(|| "The pack does not declare a Minecraft version.".to_string())?,
+        loader,
+    })
+}
+
+/// Pick the files to download for a client and return them with the skipped paths.
+fn plan_modrinth_pack(
+    index: &ModrinthPackIndex,
+) -> Result<(Vec<PackDownload>, Vec<String>), String> {
+    if index.format_version != 1 {
+        return Err(format!(
+            "Pack format version {} is not supported.",
+            index.format_version
+        ));
+    }
+    if index.game != "minecraft" {
+        return Err(format!("The pack is for '{}', not Minecraft.", index.game));
+    }
+    if index.files.len() > MAX_PACK_FILES {
+        return Err(format!("The pack lists more than {MAX_PACK_FILES} files."));
+    }
+
+    let mut downloads = Vec::new();
+    let mut skipped = Vec::new();
+    let mut seen = HashSet::new();
+    for file in &index.files {
+        validate_pack_path(&file.path)?;
+        if !seen.insert(file.path.to_ascii_lowercase()) {
+            return Err(format!("The pack lists '{}' more than once.", file.path));
+        }
+        if file
+            .env
+            .as_ref()
+            .is_some_and(|env| env.client == "unsupported")
+        {
+            skipped.push(file.path.clone());
+            continue;
+        }
+        let hash = |algorithm: &str| {
+            file.hashes
+                .get(algorithm)
+                .map(|hash| hash.trim().to_ascii_lowercase())
+                .ok_or_else(|| format!("Pack file '{}' has no {algorithm} hash.", file.path))
+        };
+        // The format only allows HTTPS; hashes stop tampering but not snooping or blocking.
+        let urls: Vec<String> = file
+            .downloads
+            .iter()
+            .filter(|url| url.starts_with("https://"))
+            .cloned()
+            .collect();
+        if urls.is_empty() {
+            return Err(format!(
+                "Pack file '{}' has no HTTPS download URL.",
+                file.path
+            ));
+        }
+        downloads.push(PackDownload {
+            path: file.path.clone(),
+            urls,
+            sha1: hash("sha1")?,
+            sha512: Some(hash("sha512")?),
+            size: file.file_size,
+        });
+    }
+    Ok((downloads, skipped))
+}
+
+/// Download one URL next to `destination` and move it into place once every check passes.
+async fn download_verified(
+    url: &str,
+    destination: &Path,
+    download: &PackDownload,
+) -> Result<(), String> {
+    let mut temp_name = destination.as_os_str().to_owned();
+    temp_name.push(".epherome-download");
+    let temp_path = PathBuf::from(temp_name);
+    let size_limit = download
+        .size
+        .unwrap_or(MAX_PACK_FILE_SIZE)
+        .min(MAX_PACK_FILE_SIZE);
+
+    let result = async {
+        let mut response = get_http_client()
+            .get(url)
+            .send()
+            .await
+            .map_err(|error| format!("{url}: {error}"))?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(format!("{url} returned HTTP {status}."));
+        }
+        let mut temp_file = tokio::fs::File::create(&temp_path)
+            .await
+            .map_err(|error| format!("Failed to create '{}': {error}", temp_path.display()))?;
+        let mut hasher = ModFileHasher::default();
+        let mut downloaded: u64 = 0;
+        while let Some(chunk) = response
+            .chunk()
+            .await
+            .map_err(|error| format!("{url}: {error}"))?
+        {
+            downloaded += chunk.len() as u64;
+            if downloaded > size_limit {
+                return Err(format!("{url} is larger than {size_limit} bytes."));
+            }
+            hasher.update(&chunk);
+            temp_file
+                .write_all(&chunk)
+                .await
+                .map_err(|error| format!("Failed to write '{}': {error}", temp_path.display()))?;
+        }
+        temp_file
+            .sync_all()
+            .await
+            .map_err(|error| format!("Failed to write '{}': {error}", temp_path.display()))?;
+        drop(temp_file);
+
+        if download.size.is_some_and(|size| size != downloaded) {
+            return Err(format!(
+                "{url} is {downloaded} bytes, but the pack declares {} bytes.",
+                download.size.unwrap_or_default()
+            ));
+        }
+        let hashes = hasher.finish();
+        if hashes.sha1 != download.sha1
+            || download
+                .sha512
+                .as_ref()
+                .is_some_and(|sha512| *sha512 != hashes.sha512)
+        {
+            return Err(format!("{url} does not match the hashes in the pack."));
+        }
+        tokio::fs::rename(&temp_path, destination)
+            .await
+            .map_err(|error| format!("Failed to move '{}' into place: {error}", download.path))
+    }
+    .await;
+
+    if result.is_err() {
+        let _ = tokio::fs::remove_file(&temp_path).await;
+    }
+    result
+}
+
+/// Try each mirror of a pack file in turn.
+async fn download_pack_file(game_directory: &Path, download: &PackDownload) -> Result<(), String> {
+    let destination = game_directory.join(&download.path);
+    if let Some(parent) = destination.parent() {
+        tokio::fs::create_dir_all(parent)
+            .await
+            .map_err(|error| format!("Failed to create '{}': {error}", parent.display()))?;
+    }
+
+    let mut errors = Vec::new();
+    for url in &download.urls {
+        match download_verified(url, &destination, download).await {
+            Ok(()) => return Ok(()),
+            Err(error) => errors.push(error),
+        }
+    }
+    Err(format!(
+        "Failed to download '{}': {}",
+        download.path,
+        errors.join(" ")
+    ))
+}
+
+/// Copy every entry below `prefixes` into the instance, in prefix order.
+fn extract_pack_overrides(
+    pack_path: &Path,
+    game_directory: &Path,
+    prefixes: &[String],
+) -> Result<usize, String> {
+    let mut file = File::open(pack_path)
+        .map_err(|error| format!("Failed to open '{}': {error}", pack_path.display()))?;
+    preflight_zip_archive(&mut file)
+        .map_err(|error| format!("'{}' is not a valid pack: {error}", pack_path.display()))?;
+    let mut archive = zip::ZipArchive::new(file)
+        .map_err(|error| format!("'{}' is not a valid pack: {error}", pack_path.display()))?;
+    let mut remaining = MAX_PACK_OVERRIDES_SIZE;
+    let mut extracted = 0;
+
+    for prefix in prefixes {
+        for index in 0..archive.len() {
+            let mut entry = archive
+                .by_index(index)
+                .map_err(|error| format!("Failed to read pack entry {}: {error}", index + 1))?;
+            let Some(relative) = entry.name().strip_prefix(prefix) else {
+                continue;
+            };
+            let relative = relative.trim_end_matches('/').to_string();
+            if relative.is_empty() {
+                continue;
+            }
+            validate_pack_path(&relative)?;
+
+            let destination = game_directory.join(&relative);
+            if entry.is_dir() {
+                fs::create_dir_all(&destination).map_err(|error| {
+                    format!("Failed to create '{}': {error}", destination.display())
+                })?;
+                continue;
+            }
+            if let Some(parent) = destination.parent() {
+                fs::create_dir_all(parent)
+                    .map_err(|error| format!("Failed to create '{}': {error}", parent.display()))?;
+            }
+            let mut output = File::create(&destination).map_err(|error| {
+                format!("Failed to create '{}': {error}", destination.display())
+            })?;
+            let copied = io::copy(&mut (&mut entry).take(remaining + 1), &mut output)
+                .map_err(|error| format!("Failed to extract '{relative}': {error}"))?;
+            if copied > remaining {
+                return Err(format!(
+                    "The pack overrides exceed the {MAX_PACK_OVERRIDES_SIZE}-byte limit."
+                ));
+            }
+            remaining -= copied;
+            extracted += 1;
+        }
+    }
+    Ok(extracted)
+}
+
+async fn fetch_json(url: &str) -> Result<JsonValue, String> {
+    let response = get_http_client()
+        .get(url)
+        .send()
+        .await
+        .map_err(|error| format!("Request to {url} failed: {error}"))?;
+    let status = response.status();
+    if !status.is_success() {
+        return Err(format!("{url} returned HTTP {status}."));
+    }
+    response
+        .json()
+        .await
+        .map_err(|error| format!("{url} returned invalid JSON: {error}"))
+}
+
+async fn write_version_json(
+    game_directory: &Path,
+    version_id: &str,
+    document: &JsonValue,
+) -> Result<(), String> {
+    if version_id.is_empty() || version_id.contains(['/', '\\']) || version_id.starts_with('.') {
+        return Err(format!("Version ID '{version_id}' is invalid."));
+    }
+    let directory = game_directory.join("versions").join(version_id);
+    tokio::fs::create_dir_all(&directory)
+        .await
+        .map_err(|error| format!("Failed to create '{}': {error}", directory.display()))?;
+    let bytes = serde_json::to_vec_pretty(document)
+        .map_err(|error| format!("Failed to serialize version '{version_id}': {error}"))?;
+    let path = directory.join(format!("{version_id}.json"));
+    tokio::fs::write(&path, bytes)
+        .await
+        .map_err(|error| format!("Failed to write '{}': {error}", path.display()))
+}
+
+/// Write the vanilla and, for Fabric and Quilt, the loader version JSON.
+async fn install_pack_version(
+    game_directory: &Path,
+    target: &PackTarget,
+) -> Result<(String, bool), String> {
+    let manifest = fetch_json(MOJANG_VERSION_MANIFEST_URL).await?;
+    let url = manifest["versions"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .find(|version| version["id"] == target.game_version.as_str())
+        .and_then(|version| version["url"].as_str())
+        .ok_or_else(|| format!("Minecraft {} was not found.", target.game_version))?;
+    let vanilla = fetch_json(url).await?;
+    write_version_json(game_directory, &target.game_version, &vanilla).await?;
+
+    let meta_url = match &target.loader {
+        None => return Ok((target.game_version.clone(), true)),
+        Some((loader, _)) if loader == "Fabric" => FABRIC_META_URL,
+        Some((loader, _)) if loader == "Quilt" => QUILT_META_URL,
+        Some(_) => return Ok((target.game_version.clone(), false)),
+    };
+    let loader_version = target
+        .loader
+        .as_ref()
+        .map(|(_, version)| version.as_str())
+        .unwrap_or_default();
+    let profile = fetch_json(&format!(
+        "{meta_url}/versions/loader/{}/{loader_version}/profile/json",
+        target.game_version
+    ))
+    .await?;
+    let version_id = profile["id"]
+        .as_str()
+        .ok_or_else(|| "The loader profile has no version ID.".to_string())?
+        .to_string();
+    write_version_json(game_directory, &version_id, &profile).await?;
+    Ok((version_id, true))
+}
+
+/// `forge-47.2.0`-style loader IDs from a CurseForge manifest, primary loader first.
+fn curseforge_pack_target(minecraft: &CurseForgePackMinecraft) -> Result<PackTarget, String> {
+    let loader = minecraft
+        .mod_loaders
+        .iter()
+        .find(|loader| loader.primary)
+        .or_else(|| minecraft.mod_loaders.first())
+        .map(|loader| {
+            let (name, version) = loader
+                .id
+                .split_once('-')
+                .ok_or_else(|| format!("Mod loader '{}' is not recognized.", loader.id))?;
+            let name = match name {
+                "fabric" => "Fabric",
+                "quilt" => "Quilt",
+                "forge" => "Forge",
+                "neoforge" => "NeoForge",
+                _ => return Err(format!("Mod loader '{}' is not supported.", loader.id)),
+            };
+            Ok((name.to_string(), version.to_string()))
+        })
+        .transpose()?;
+    Ok(PackTarget {
+        game_version: minecraft.version.clone(),
+        loader,
+    })
+}
+
+/// Sort manifest files into downloads, manual downloads and skipped optional files.
+fn plan_curseforge_pack(
+    manifest: &CurseForgePackManifest,
+    files: &[CurseForgeFile],
+    projects: &[CurseForgeMod],
+) -> Result<CurseForgePackPlan, String> {
+    if manifest
+        .manifest_type
+        .as_deref()
+        .is_some_and(|manifest_type| manifest_type != "minecraftModpack")
+    {
+        return Err("The manifest does not describe a Minecraft modpack.".to_string());
+    }
+    if manifest.files.len() > MAX_PACK_FILES {
+        return Err(format!("The pack lists more than {MAX_PACK_FILES} files."));
+    }
+
+    let mut downloads = Vec::new();
+    let mut manual_downloads = Vec::new();
+    let mut skipped = Vec::new();
+    let mut seen = HashSet::new();
+    for entry in &manifest.files {
+        let file = files
+            .iter()
+            .find(|file| file.id == entry.file_id && file.mod_id == entry.project_id)
+            .ok_or_else(|| {
+                format!(
+                    "CurseForge file {} of project {} was not found.",
+                    entry.file_id, entry.project_id
+                )
+            })?;
+        let project = projects
+            .iter()
+            .find(|project| project.id == entry.project_id);
+        let directory = match project.and_then(|project| project.class_id) {
+            Some(CURSEFORGE_RESOURCE_PACK_CLASS) => "resourcepacks",
+            Some(CURSEFORGE_SHADER_PACK_CLASS) => "shaderpacks",
+            _ => "mods",
+        };
+        let path = format!("{directory}/{}", file.file_name);
+        if file.file_name.contains('/') {
+            return Err(format!("Pack path '{path}' is unsafe."));
+        }
+        validate_pack_path(&path)?;
+        if !entry.required {
+            skipped.push(path);
+            continue;
+        }
+        if !seen.insert(path.to_ascii_lowercase()) {
+            return Err(format!("The pack lists '{path}' more than once."));
+        }
+
+        match file.download_url.as_deref().filter(|url| !url.is_empty()) {
+            Some(url) => downloads.push(PackDownload {
+                urls: vec![url.to_string()],
+                sha1: file
+                    .sha1()
+                    .ok_or_else(|| format!("CurseForge file '{path}' has no SHA-1 hash."))?,
+                sha512: None,
+                size: file.file_length,
+                path,
+            }),
+            None => manual_downloads.push(ModpackManualDownload {
+                project_id: entry.project_id,
+                file_id: entry.file_id,
+                project_name: project
+                    .map(|project| project.name.clone())
+                    .unwrap_or_else(|| entry.project_id.to_string()),
+                filename: file.file_name.clone(),
+                page_url: project
+                    .map(|project| format!("{}/download/{}", project.page_url(), entry.file_id))
+                    .unwrap_or_default(),
+                path,
+            }),
+        }
+    }
+    Ok((downloads, manual_downloads, skipped))
+}
+
+/// Modrinth index dependencies for the instance's game version and loader.
+fn modrinth_pack_dependencies(
+    version: &InstanceVersionInfo,
+) -> Result<BTreeMap<String, String>, String> {
+    let game_version = version.game_version.clone().ok_or_else(|| {
+        format!(
+            "The Minecraft version of '{}' is unknown.",
+            version.version_id
+        )
+    })?;
+    let mut dependencies = BTreeMap::from([("minecraft".to_string(), game_version)]);
+    if let Some(loader) = &version.loader {
+        let dependency = match loader.as_str() {
+            "Fabric" => "fabric-loader",
+            "Quilt" => "quilt-loader",
+            "Forge" => "forge",
+            "NeoForge" => "neoforge",
+            _ => return Err(format!("Mod loader '{loader}' cannot be exported.")),
+        };
+        let loader_version = version.loader_version.clone().ok_or_else(|| {
+            format!(
+                "The {loader} version of '{}' is unknown.",
+                version.version_id
+            )
+        })?;
+        dependencies.insert(dependency.to_string(), loader_version);
+    }
+    Ok(dependencies)
+}
+
+/// Hash the instance's mods, sorted by filename so exports are stable.
+fn collect_export_mods(
+    game_directory: &str,
+    exclude_disabled: bool,
+) -> Result<Vec<ExportedMod>, String> {
+    let mods_directory = mods_directory(game_directory)?;
+    let mut mods = scan_local_mods_blocking(game_directory)?
+        .into_iter()
+        .filter(|file| file.enabled || !exclude_disabled)
+        .map(|file| {
+            Ok(ExportedMod {
+                hashes: mod_file_hashes(&mods_directory.join(&file.filename))?,
+                filename: file.filename,
+                enabled: file.enabled,
+                size: file.size,
+            })
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+    mods.sort_by(|left, right| left.filename.cmp(&right.filename));
+    Ok(mods)
+}
+
+/// Re-hash the mods and fail if they no longer match the snapshot the index was built from.
+fn verify_export_mods(
+    game_directory: &str,
+    exclude_disabled: bool,
+    snapshot: &[ExportedMod],
+) -> Result<(), String> {
+    if collect_export_mods(game_directory, exclude_disabled)? != snapshot {
+        return Err("The mods changed while the pack was being exported; try again.".to_string());
+    }
+    Ok(())
+}
+
+/// Add regular files below `relative` to `overrides`, skipping symlinks.
+fn collect_override_files(
+    game_directory: &Path,
+    relative: &str,
+    overrides: &mut BTreeMap<String, PathBuf>,
+) -> Result<(), String> {
+    let path = game_directory.join(relative);
+    let metadata = match fs::symlink_metadata(&path) {
+        Ok(metadata) => metadata,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(()),
+        Err(error) => return Err(format!("Failed to inspect '{}': {error}", path.display())),
+    };
+    if metadata.is_file() {
+        overrides.insert(format!("{MODRINTH_PACK_EXPORT_OVERRIDES}/{relative}"), path);
+        return Ok(());
+    }
+    if !metadata.is_dir() {
+        return Ok(());
+    }
+    let entries = fs::read_dir(&path)
+        .map_err(|error| format!("Failed to read '{}': {error}", path.display()))?;
+    for entry in entries {
+        let entry =
+            entry.map_err(|error| format!("Failed to read '{}': {error}", path.display()))?;
+        let name = entry.file_name().into_string().map_err(|name| {
+            format!(
+                "'{}' has a name that cannot be exported.",
+                path.join(name).display()
+            )
+        })?;
+        collect_override_files(game_directory, &format!("{relative}/{name}"), overrides)?;
+    }
+    Ok(())
+}
+
+/// Build the index and the override file list; mods Modrinth hosts become downloads.
+fn plan_modrinth_pack_export(
+    game_directory: &Path,
+    version: &InstanceVersionInfo,
+    options: &ModpackExportOptions,
+    mods: &[ExportedMod],
+    download_urls: &[Option<String>],
+) -> Result<(ModrinthPackIndex, BTreeMap<String, PathBuf>), String> {
+    let name = options.name.trim();
+    if name.is_empty() {
+        return Err("The pack needs a name.".to_string());
+    }
+    let dependencies = modrinth_pack_dependencies(version)?;
+
+    let mut files = Vec::new();
+    let mut overrides = BTreeMap::new();
+    for (exported, url) in mods.iter().zip(download_urls) {
+        let path = format!("mods/{}", exported.filename);
+        validate_pack_path(&path)?;
+        match url.as_ref().filter(|_| exported.enabled) {
+            Some(url) => files.push(ModrinthPackFile {
+                path,
+                hashes: BTreeMap::from([
+                    ("sha1".to_string(), exported.hashes.sha1.clone()),
+                    ("sha512".to_string(), exported.hashes.sha512.clone()),
+                ]),
+                env: None,
+                downloads: vec![url.clone()],
+                file_size: Some(exported.size),
+            }),
+            None => {
+                overrides.insert(
+                    format!("{MODRINTH_PACK_EXPORT_OVERRIDES}/{path}"),
+                    game_directory.join(&path),
+                );
+            }
+        }
+    }
+
+    let mut directories = BTreeSet::new();
+    for directory in DEFAULT_EXPORT_DIRECTORIES
+        .iter()
+        .copied()
+        .chain(options.override_directories.iter().map(String::as_str))
+    {
+        let directory = directory.trim().replace('\\', "/");
+        let directory = directory.trim_matches('/');
+        validate_pack_path(directory)?;
+        let first = directory.split('/').next().unwrap_or_default();
+        if first.eq_ignore_ascii_case("mods") || first.eq_ignore_ascii_case(".epherome") {
+            return Err(format!("'{directory}' cannot be exported as an override."));
+        }
+        directories.insert(directory.to_string());
+    }
+    for directory in &directories {
+        collect_override_files(game_directory, directory, &mut overrides)?;
+    }
+
+    Ok((
+        ModrinthPackIndex {
+            format_version: 1,
+            game: "minecraft".to_string(),
+            version_id: Some(
+                options
+                    .pack_version
+                    .clone()
+                    .filter(|version| !version.trim().is_empty())
+                    .unwrap_or_else(|| "1.0.0".to_string()),
+            ),
+            name: name.to_string(),
+            summary: options
+                .summary
+                .clone()
+                .filter(|summary| !summary.trim().is_empty()),
+            files,
+            dependencies,
+        },
+        overrides,
+    ))
+}
+
+/// Write the pack with sorted entries, fixed timestamps and permissions so equal inputs give equal bytes.
+fn write_modrinth_pack(
+    destination: &Path,
+    index: &ModrinthPackIndex,
+    overrides: &BTreeMap<String, PathBuf>,
+) -> Result<(), String> {
+    let mut temp_name = destination.as_os_str().to_owned();
+    temp_name.push(".epherome-export");
+    let temp_path = PathBuf::from(temp_name);
+    let options = zip::write::SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated)
+        .last_modified_time(zip::DateTime::default())
+        .unix_permissions(0o644);
+
+    let write_result = (|| -> Result<(), String> {
+        let file = File::create(&temp_path)
+            .map_err(|error| format!("Failed to create '{}': {error}", temp_path.display()))?;
+        let mut archive = zip::ZipWriter::new(file);
+        let index_bytes = serde_json::to_vec_pretty(index)
+            .map_err(|error| format!("Failed to serialize the pack index: {error}"))?;
+        archive
+            .start_file(MODRINTH_PACK_INDEX_PATH, options)
+            .and_then(|()| archive.write_all(&index_bytes).map_err(Into::into))
+            .map_err(|error| format!("Failed to write {MODRINTH_PACK_INDEX_PATH}: {error}"))?;
+        for (archive_path, source) in overrides {
+            let mut input = File::open(source)
+                .map_err(|error| format!("Failed to open '{}': {error}", source.display()))?;
+            archive
+                .start_file(archive_path.as_str(), options)
+                .map_err(|error| format!("Failed to write '{archive_path}': {error}"))?;
+            io::copy(&mut input, &mut archive)
+                .map_err(|error| format!("Failed to write '{archive_path}': {error}"))?;
+        }
+        let file = archive
+            .finish()
+            .map_err(|error| format!("Failed to finish the pack: {error}"))?;
+        file.sync_all()
+            .map_err(|error| format!("Failed to write '{}': {error}", temp_path.display()))?;
+        fs::rename(&temp_path, destination)
+            .map_err(|error| format!("Failed to move the pack into place: {error}"))
+    })();
+    write_result.inspect_err(|_| {
+        let _ = fs::remove_file(&temp_path);
+    })
+}
+
+/// Use a missing or empty directory for a new instance; returns whether it was created.
+fn prepare_instance_directory(game_directory: &Path) -> Result<bool, String> {
+    match fs::read_dir(game_directory) {
+        Ok(mut entries) => {
+            if entries.next().is_some() {
+                return Err(format!(
+                    "Instance directory '{}' is not empty.",
+                    game_directory.display()
+                ));
+            }
+            Ok(false)
+        }
+        Err(error) if error.kind() == io::ErrorKind::NotFound => {
+            fs::create_dir_all(game_directory).map_err(|error| {
+                format!("Failed to create '{}': {error}", game_directory.display())
+            })?;
+            Ok(true)
+        }
+        Err(error) => Err(format!(
+            "Failed to read '{}': {error}",
+            game_directory.display()
+        )),
+    }
+}
+
+/// Remove everything a failed import left behind.
+fn clean_instance_directory(game_directory: &Path, created: bool, reason: String) -> String {
+    let cleanup = if created {
+        fs::remove_dir_all(game_directory)
+    } else {
+        fs::read_dir(game_directory).and_then(|entries| {
+            for entry in entries {
+                let path = entry?.path();
+                if fs::symlink_metadata(&path)?.is_dir() {
+                    fs::remove_dir_all(&path)?;
+                } else {
+                    fs::remove_file(&path)?;
+                }
+            }
+            Ok(())
+        })
+    };
+    match cleanup {
+        Ok(()) => reason,
+        Err(error) => format!(
+            "{reason} Also failed to clean up '{}': {error}",
+            game_directory.display()
+        ),
+    }
+}
+
+/// Download the planned files, apply overrides and install the version; returns the version ID.
+async fn install_pack_contents(
+    pack_path: &Path,
+    game_directory: &Path,
+    downloads: &[PackDownload],
+    override_prefixes: Vec<String>,
+    target: &PackTarget,
+    on_progress: ImportProgress<'_>,
+) -> Result<(String, bool), String> {
+    on_progress("files", 0, downloads.len(), None);
+    for (completed, download) in downloads.iter().enumerate() {
+        download_pack_file(game_directory, download).await?;
+        on_progress(
+            "files",
+            completed + 1,
+            downloads.len(),
+            Some(&download.path),
+        );
+    }
+
+    on_progress("overrides", 0, 1, None);
+    let (overrides_pack, overrides_directory) =
+        (pack_path.to_path_buf(), game_directory.to_path_buf());
+    tokio::task::spawn_blocking(move || {
+        extract_pack_overrides(&overrides_pack, &overrides_directory, &override_prefixes)
+    })
+    .await
+    .map_err(|error| format!("Override extraction task failed: {error}"))??;
+    on_progress("overrides", 1, 1, None);
+
+    on_progress("version", 0, 1, None);
+    let installed = install_pack_version(game_directory, target).await?;
+    on_progress("version", 1, 1, None);
+    Ok(installed)
+}
+
+async fn read_pack_json_blocking<T: DeserializeOwned + Send + 'static>(
+    pack_path: &Path,
+    entry_path: &'static str,
+) -> Result<T, String> {
+    let pack_path = pack_path.to_path_buf();
+    tokio::task::spawn_blocking(move || read_pack_json(&pack_path, entry_path))
+        .await
+        .map_err(|error| format!("Pack reading task failed: {error}"))?
+}
+
+async fn install_modrinth_pack(
+    pack_path: &Path,
+    game_directory: &Path,
+    on_progress: ImportProgress<'_>,
+) -> Result<ModpackImport, String> {
+    let index: ModrinthPackIndex =
+        read_pack_json_blocking(pack_path, MODRINTH_PACK_INDEX_PATH).await?;
+    let target = modrinth_pack_target(&index.dependencies)?;
+    let (downloads, skipped) = plan_modrinth_pack(&index)?;
+    let (version_id, loader_installed) = install_pack_contents(
+        pack_path,
+        game_directory,
+        &downloads,
+        MODRINTH_PACK_OVERRIDE_DIRECTORIES
+            .map(String::from)
+            .to_vec(),
+        &target,
+        on_progress,
+    )
+    .await?;
+
+    let (loader, loader_version) = target.loader.unzip();
+    Ok(ModpackImport {
+        name: index.name,
+        pack_version: index.version_id,
+        version_id,
+        game_version: target.game_version,
+        loader,
+        loader_version,
+        loader_installed,
+        skipped,
+        manual_downloads: Vec::new(),
+    })
+}
+
+async fn install_curseforge_pack(
+    client: &CurseForgeClient,
+    pack_path: &Path,
+    game_directory: &Path,
+    on_progress: ImportProgress<'_>,
+) -> Result<ModpackImport, String> {
+    let manifest: CurseForgePackManifest =
+        read_pack_json_blocking(pack_path, CURSEFORGE_PACK_MANIFEST_PATH).await?;
+    let target = curseforge_pack_target(&manifest.minecraft)?;
+    let overrides = manifest
+        .overrides
+        .clone()
+        .map(|overrides| overrides.trim().trim_end_matches('/').to_string())
+        .filter(|overrides| !overrides.is_empty())
+        .unwrap_or_else(|| "overrides".to_string());
+    validate_pack_path(&overrides)?;
+
+    let file_ids: Vec<u64> = manifest.files.iter().map(|file| file.file_id).collect();
+    let files = client.files(&file_ids).await?;
+    let project_ids: Vec<u64> = manifest
+        .files
+        .iter()
+        .map(|file| file.project_id)
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect();
+    let projects = client.mods(&project_ids).await?;
+    let (downloads, manual_downloads, skipped) =
+        plan_curseforge_pack(&manifest, &files, &projects)?;
+
+    let (version_id, loader_installed) = install_pack_contents(
+        pack_path,
+        game_directory,
+        &downloads,
+        vec![format!("{overrides}/")],
+        &target,
+        on_progress,
+    )
+    .await?;
+
+    let (loader, loader_version) = target.loader.unzip();
+    Ok(ModpackImport {
+        name: manifest.name,
+        pack_version: manifest.version,
+        version_id,
+        game_version: target.game_version,
+        loader,
+        loader_version,
+        loader_installed,
+        skipped,
+        manual_downloads,
+    })
+}
+
+/// Run an install into a missing or empty directory, removing partial results on failure.
+async fn import_into_instance_directory(
+    game_directory: &Path,
+    install: impl std::future::Future<Output = Result<ModpackImport, String>>,
+) -> Result<ModpackImport, String> {
+    let created = prepare_instance_directory(game_directory)?;
+    install
+        .await
+        .map_err(|reason| clean_instance_directory(game_directory, created, reason))
+}
+
+fn emit_import_progress(
+    app: &AppHandle,
+    import_id: &Option<String>,
+    stage: &str,
+    completed: usize,
+    total: usize,
+    path: Option<&str>,
+) {
+    let _ = app.emit(
+        MODPACK_IMPORT_PROGRESS_EVENT,
+        ModpackImportProgress {
+            import_id: import_id.clone(),
+            stage: stage.to_string(),
+            completed,
+            total,
+            path: path.map(str::to_string),
+        },
+    );
+}
+
+/// Import a `.mrpack` into a new instance directory, removing partial results on failure.
+#[tauri::command]
+pub async fn import_modrinth_pack(
+    app: AppHandle,
+    pack_path: String,
+    game_directory: String,
+    import_id: Option<String>,
+) -> Result<ModpackImport, String> {
+    let on_progress = |stage: &str, completed: usize, total: usize, path: Option<&str>| {
+        emit_import_progress(&app, &import_id, stage, completed, total, path);
+    };
+    let (pack_path, game_directory) = (Path::new(&pack_path), Path::new(&game_directory));
+    import_into_instance_directory(
+        game_directory,
+        install_modrinth_pack(pack_path, game_directory, &on_progress),
+    )
+    .await
+}
+
+/// Import a CurseForge modpack zip into a new instance directory.
+#[tauri::command]
+pub async fn import_curseforge_pack(
+    app: AppHandle,
+    pack_path: String,
+    game_directory: String,
+    api_key: String,
+    api_base_url: Option<String>,
+    import_id: Option<String>,
+) -> Result<ModpackImport, String> {
+    let client = CurseForgeClient::new(api_base_url, api_key)?;
+    let on_progress = |stage: &str, completed: usize, total: usize, path: Option<&str>| {
+        emit_import_progress(&app, &import_id, stage, completed, total, path);
+    };
+    let (pack_path, game_directory) = (Path::new(&pack_path), Path::new(&game_directory));
+    import_into_instance_directory(
+        game_directory,
+        install_curseforge_pack(&client, pack_path, game_directory, &on_progress),
+    )
+    .await
+}
+
+/// Export an instance as a `.mrpack`, listing mods Modrinth hosts as downloads.
+#[tauri::command]
+pub async fn export_modrinth_pack(
+    game_directory: String,
+    version_id: String,
+    destination: String,
+    options: ModpackExportOptions,
+) -> Result<ModpackExport, String> {
+    // Snapshot the mods under the lock, but release it while Modrinth is queried.
+    let operation_guard = MODS_OPERATION_LOCK.lock().await;
+    let scan_directory = game_directory.clone();
+    let exclude_disabled = options.exclude_disabled_mods;
+    let (version, mods) = tokio::task::spawn_blocking(move || {
+        let _operation_guard = operation_guard;
+        let version = read_instance_version_info(Path::new(&scan_directory), &version_id)?;
+        Ok::<_, String>((
+            version,
+            collect_export_mods(&scan_directory, exclude_disabled)?,
+        ))
+    })
+    .await
+    .map_err(|error| format!("Mod hashing task failed: {error}"))??;
+
+    let hashes: Vec<ModFileHashes> = mods
+        .iter()
+        .map(|exported| exported.hashes.clone())
+        .collect();
+    let download_urls = modrinth_download_urls(options.api_base_url.clone(), &hashes).await?;
+
+    let operation_guard = MODS_OPERATION_LOCK.lock().await;
+    tokio::task::spawn_blocking(move || {
+        let _operation_guard = operation_guard;
+        verify_export_mods(&game_directory, exclude_disabled, &mods)?;
+        let (index, overrides) = plan_modrinth_pack_export(
+            Path::new(&game_directory),
+            &version,
+            &options,
+            &mods,
+            &download_urls,
+        )?;
+        write_modrinth_pack(Path::new(&destination), &index, &overrides)?;
+        Ok(ModpackExport {
+            files: index.files.into_iter().map(|file| file.path).collect(),
+            overrides: overrides.into_keys().collect(),
+        })
+    })
+    .await
+    .map_err(|error| format!("Pack export task failed: {error}"))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::test_fixtures::{temp_directory, write_zip};
+    use serde_json::json;
+    use std::net::TcpListener;
+
+    fn pack_file(path: &str, client: &str) -> JsonValue {
+        json!({
+            "path": path,
+            "hashes": {"sha1": "AB", "sha512": "cd"},
+            "env": {"client": client, "server": "required"},
+            "downloads": ["https://cdn.modrinth.com/data/x/file.jar"],
+            "fileSize": 2
+        })
+    }
+
+    fn index(files: Vec<JsonValue>, dependencies: JsonValue) -> ModrinthPackIndex {
+        serde_json::from_value(json!({
+            "formatVersion": 1,
+            "game": "minecraft",
+            "versionId": "1.0.0",
+            "name": "Example Pack",
+            "files": files,
+            "dependencies": dependencies
+        }))
+        .expect("Index should parse")
+    }
+
+    #[test]
+    fn plans_client_files_and_resolves_the_target_loader() {
+        let dependencies = json!({"minecraft": "1.21.1", "fabric-loader": "0.16.5"});
+        let pack = index(
+            vec![
+                pack_file("mods/client.jar", "required"),
+                pack_file("mods/optional.jar", "optional"),
+                pack_file("mods/server.jar", "unsupported"),
+            ],
+            dependencies.clone(),
+        );
+        let (downloads, skipped) = plan_modrinth_pack(&pack).expect("Pack should be planned");
+        let paths: Vec<&str> = downloads.iter().map(|file| file.path.as_str()).collect();
+        assert_eq!(paths, ["mods/client.jar", "mods/optional.jar"]);
+        assert_eq!(
+            (downloads[0].sha1.as_str(), downloads[0].size),
+            ("ab", Some(2))
+        );
+        assert_eq!(skipped, ["mods/server.jar"]);
+        assert_eq!(
+            modrinth_pack_target(&pack.dependencies),
+            Ok(PackTarget {
+                game_version: "1.21.1".to_string(),
+                loader: Some(("Fabric".to_string(), "0.16.5".to_string())),
+            })
+        );
+
+        for unsafe_path in [
+            "../escape.jar",
+            "/etc/passwd",
+            "mods\\..\\x.jar",
+            "C:/x.jar",
+        ] {
+            let pack = index(
+                vec![pack_file(unsafe_path, "required")],
+                dependencies.clone(),
+            );
+            assert!(plan_modrinth_pack(&pack).is_err(), "{unsafe_path}");
+        }
+        let mut plain_http = pack_file("mods/plain.jar", "required");
+        plain_http["downloads"] = json!(["http://cdn.modrinth.com/data/x/file.jar"]);
+        assert!(
+            plan_modrinth_pack(&index(vec![plain_http], dependencies.clone()))
+                .err()
+                .is_some_and(|error| error.contains("HTTPS"))
+        );
+        let both_loaders = json!({"minecraft": "1.20.1", "forge": "47.2.0", "neoforge": "47.1.0"});
+        assert!(modrinth_pack_target(
+            &serde_json::from_value(both_loaders).expect("Dependencies should parse")
+        )
+        .is_err());
+        let no_game = json!({"quilt-loader": "0.26.4"});
+        assert!(modrinth_pack_target(
+            &serde_json::from_value(no_game).expect("Dependencies should parse")
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn extracts_client_overrides_last_and_rejects_traversal() {
+        let root = temp_directory("modpack-overrides");
+        let instance = root.join("instance");
+        fs::create_dir_all(&instance).expect("Instance should be created");
+        let pack = root.join("pack.mrpack");
+        write_zip(
+            &pack,
+            &[
+                ("client-overrides/config/a.txt", b"client"),
+                ("overrides/config/a.txt", b"shared"),
+                ("overrides/options.txt", b"options"),
+                ("modrinth.index.json", b"{}"),
+            ],
+        );
+        let prefixes = MODRINTH_PACK_OVERRIDE_DIRECTORIES.map(String::from);
+        let extracted = extract_pack_overrides(&pack, &instance, &prefixes)
+            .expect("Overrides should be extracted");
+        let config = fs::read_to_string(instance.join("config/a.txt"));
+        let options = fs::read_to_string(instance.join("options.txt"));
+
+        let evil = root.join("evil.mrpack");
+        write_zip(&evil, &[("overrides/../escaped.txt", b"evil")]);
+        let rejected = extract_pack_overrides(&evil, &instance, &prefixes);
+        let escaped = root.join("escaped.txt").exists();
+        let _ = fs::remove_dir_all(&root);
+
+        assert_eq!(extracted, 3);
+        assert_eq!(config.expect("Override should exist"), "client");
+        assert_eq!(options.expect("Override should exist"), "options");
+        assert!(rejected.is_err());
+        assert!(!escaped);
+    }
+
+    #[tokio::test]
+    async fn failed_imports_remove_partial_installs() {
+        // A port that was just released refuses connections, so the download fails fast.
+        let port = TcpListener::bind("127.0.0.1:0")
+            .and_then(|listener| listener.local_addr())
+            .expect("Port should be reserved")
+            .port();
+        let root = temp_directory("modpack-cleanup");
+        fs::create_dir_all(&root).expect("Root should be created");
+        let pack = root.join("pack.mrpack");
+        let mut file = pack_file("mods/a.jar", "required");
+        file["downloads"] = json!([format!("https://127.0.0.1:{port}/a.jar")]);
+        let index = json!({
+            "formatVersion": 1,
+            "game": "minecraft",
+            "name": "Broken",
+            "files": [file],
+            "dependencies": {"minecraft": "1.21.1"}
+        })
+        .to_string();
+        write_zip(
+            &pack,
+            &[
+                ("modrinth.index.json", index.as_bytes()),
+                ("overrides/options.txt", b"options"),
+            ],
+        );
+
+        let new_instance = root.join("new");
+        let existing_instance = root.join("existing");
+        fs::create_dir_all(&existing_instance).expect("Instance should be created");
+        let progress = std::sync::Mutex::new(Vec::new());
+        let on_progress = |stage: &str, completed: usize, total: usize, _: Option<&str>| {
+            progress
+                .lock()
+                .expect("Progress lock should not be poisoned")
+                .push((stage.to_string(), completed, total));
+        };
+        let created = import_into_instance_directory(
+            &new_instance,
+            install_modrinth_pack(&pack, &new_instance, &on_progress),
+        )
+        .await;
+        let reused = import_into_instance_directory(
+            &existing_instance,
+            install_modrinth_pack(&pack, &existing_instance, &on_progress),
+        )
+        .await;
+        let new_exists = new_instance.exists();
+        let existing_entries = fs::read_dir(&existing_instance).map(|entries| entries.count());
+        let _ = fs::remove_dir_all(&root);
+
+        assert!(created
+            .err()
+            .is_some_and(|error| error.contains("mods/a.jar")));
+        assert!(reused.is_err());
+        assert!(!new_exists);
+        assert_eq!(existing_entries.ok(), Some(0));
+        assert_eq!(
+            progress
+                .lock()
+                .expect("Progress lock should not be poisoned")[0],
+            ("files".to_string(), 0, 1)
+        );
+    }
+
+    #[test]
+    fn plans_curseforge_downloads_and_manual_files() {
+        let manifest: CurseForgePackManifest = serde_json::from_value(json!({
+            "minecraft": {
+                "version": "1.20.1",
+                "modLoaders": [
+                    {"id": "fabric-0.14.0", "primary": false},
+                    {"id": "forge-47.2.0", "primary": true}
+                ]
+            },
+            "manifestType": "minecraftModpack",
+            "manifestVersion": 1,
+            "name": "Example",
+            "version": "2.0",
+            "files": [
+                {"projectID": 1, "fileID": 10, "required": true},
+                {"projectID": 2, "fileID": 20, "required": true},
+                {"projectID": 3, "fileID": 30, "required": true},
+                {"projectID": 4, "fileID": 40, "required": false}
+            ],
+            "overrides": "overrides"
+        }))
+        .expect("Manifest should parse");
+        let files: Vec<CurseForgeFile> = serde_json::from_value(json!([
+            {"id": 10, "modId": 1, "fileName": "a.jar", "downloadUrl": "https://edge.forgecdn.net/a.jar",
+             "fileLength": 3, "hashes": [{"value": "ABC", "algo": 1}, {"value": "def", "algo": 2}]},
+            {"id": 20, "modId": 2, "fileName": "b.jar", "downloadUrl": null, "fileLength": 3, "hashes": []},
+            {"id": 30, "modId": 3, "fileName": "shiny.zip", "downloadUrl": "https://edge.forgecdn.net/shiny.zip",
+             "hashes": [{"value": "0123", "algo": 1}]},
+            {"id": 40, "modId": 4, "fileName": "extra.jar", "downloadUrl": "https://edge.forgecdn.net/extra.jar"}
+        ]))
+        .expect("Files should parse");
+        let projects: Vec<CurseForgeMod> = serde_json::from_value(json!([
+            {"id": 1, "name": "A", "slug": "a", "classId": 6},
+            {"id": 2, "name": "B", "slug": "b", "classId": 6, "links": {"websiteUrl": "https://www.curseforge.com/minecraft/mc-mods/b"}},
+            {"id": 3, "name": "Shiny", "slug": "shiny", "classId": 6552}
+        ]))
+        .expect("Projects should parse");
+
+        assert_eq!(
+            curseforge_pack_target(&manifest.minecraft),
+            Ok(PackTarget {
+                game_version: "1.20.1".to_string(),
+                loader: Some(("Forge".to_string(), "47.2.0".to_string())),
+            })
+        );
+        let (downloads, manual, skipped) =
+            plan_curseforge_pack(&manifest, &files, &projects).expect("Pack should be planned");
+        let planned: Vec<(&str, &str)> = downloads
+            .iter()
+            .map(|download| (download.path.as_str(), download.sha1.as_str()))
+            .collect();
+        assert_eq!(
+            planned,
+            [("mods/a.jar", "abc"), ("shaderpacks/shiny.zip", "0123")]
+        );
+        assert_eq!(manual.len(), 1);
+        assert_eq!(manual[0].path, "mods/b.jar");
+        assert_eq!(
+            manual[0].page_url,
+            "https://www.curseforge.com/minecraft/mc-mods/b/download/20"
+        );
+        assert_eq!(skipped, ["mods/extra.jar"]);
+
+        let traversal: Vec<CurseForgeFile> = serde_json::from_value(json!([
+            {"id": 10, "modId": 1, "fileName": "..", "downloadUrl": "https://x/a.jar"}
+        ]))
+        .expect("Files should parse");
+        assert!(plan_curseforge_pack(&manifest, &traversal, &projects).is_err());
+    }
+
+    #[test]
+    fn export_rejects_mods_changed_after_the_snapshot() {
+        let root = temp_directory("modpack-export-snapshot");
+        let mods = root.join("mods");
+        fs::create_dir_all(&mods).expect("Mods directory should be created");
+        fs::write(mods.join("a.jar"), b"before").expect("Mod should be written");
+        let game_directory = root.to_string_lossy().into_owned();
+
+        let snapshot =
+            collect_export_mods(&game_directory, false).expect("Mods should be collected");
+        let unchanged = verify_export_mods(&game_directory, false, &snapshot);
+        fs::write(mods.join("a.jar"), b"after!").expect("Mod should be rewritten");
+        let rewritten = verify_export_mods(&game_directory, false, &snapshot);
+        fs::write(mods.join("a.jar"), b"before").expect("Mod should be restored");
+        fs::write(mods.join("b.jar"), b"added").expect("Mod should be written");
+        let added = verify_export_mods(&game_directory, false, &snapshot);
+        let _ = fs::remove_dir_all(&root);
+
+        assert!(unchanged.is_ok());
+        assert!(rewritten.is_err());
+        assert!(added.is_err());
+    }
+
+    #[test]
+    fn exports_identical_bytes_for_identical_instances() {
+        let root = temp_directory("modpack-export");
+        let instance = root.join("instance");
+        for (path, contents) in [
+            ("mods/a.jar", &b"hosted"[..]),
+            ("mods/b.jar", b"local"),
+            ("mods/c.jar.disabled", b"disabled"),
+            ("config/z.toml", b"z"),
+            ("config/nested/a.json", b"{}"),
+            ("resourcepacks/pack.zip", b"pack"),
+            ("saves/world/level.dat", b"world"),
+        ] {
+            let path = instance.join(path);
+            fs::create_dir_all(path.parent().expect("Path should have a parent"))
+                .expect("Directory should be created");
+            fs::write(path, contents).expect("File should be written");
+        }
+        let exported = |filename: &str, enabled: bool| ExportedMod {
+            filename: filename.to_string(),
+            enabled,
+            size: 6,
+            hashes: ModFileHashes {
+                sha1: format!("{filename}-sha1"),
+                sha512: format!("{filename}-sha512"),
+            },
+        };
+        let mods = [
+            exported("a.jar", true),
+            exported("b.jar", true),
+            exported("c.jar.disabled", false),
+        ];
+        let urls = [
+            Some("https://cdn.modrinth.com/data/a/a.jar".to_string()),
+            None,
+            Some("https://cdn.modrinth.com/data/c/c.jar".to_string()),
+        ];
+        let version = InstanceVersionInfo {
+            version_id: "fabric-loader-0.15.0-1.20.1".to_string(),
+            game_version: Some("1.20.1".to_string()),
+            loader: Some("Fabric".to_string()),
+            loader_version: Some("0.15.0".to_string()),
+            main_class: None,
+        };
+        let options = ModpackExportOptions {
+            name: "Team Pack".to_string(),
+            pack_version: None,
+            summary: None,
+            override_directories: vec!["resourcepacks/".to_string()],
+            exclude_disabled_mods: false,
+            api_base_url: None,
+        };
+
+        let export = |name: &str| {
+            let (index, overrides) =
+                plan_modrinth_pack_export(&instance, &version, &options, &mods, &urls)
+                    .expect("Export should be planned");
+            let destination = root.join(name);
+            write_modrinth_pack(&destination, &index, &overrides).expect("Pack should be written");
+            (
+                index,
+                overrides,
+                fs::read(destination).expect("Pack should exist"),
+            )
+        };
+        let (index, overrides, first) = export("first.mrpack");
+        let (_, _, second) = export("second.mrpack");
+        let mut written =
+            zip::ZipArchive::new(File::open(root.join("first.mrpack")).expect("Pack should open"))
+                .expect("Pack should be readable");
+        let names: Vec<String> = written.file_names().map(str::to_string).collect();
+        let reread: Result<ModrinthPackIndex, String> =
+            read_pack_json(&root.join("first.mrpack"), MODRINTH_PACK_INDEX_PATH);
+        let mut nested = String::new();
+        written
+            .by_name("overrides/config/nested/a.json")
+            .expect("Override should be packed")
+            .read_to_string(&mut nested)
+            .expect("Override should be readable");
+        let rejected = plan_modrinth_pack_export(
+            &instance,
+            &version,
+            &ModpackExportOptions {
+                override_directories: vec!["../outside".to_string()],
+                ..options
+            },
+            &mods,
+            &urls,
+        );
+        let _ = fs::remove_dir_all(&root);
+
+        assert_eq!(first, second);
+        assert_eq!(
+            index
+                .files
+                .iter()
+                .map(|file| file.path.as_str())
+                .collect::<Vec<_>>(),
+            ["mods/a.jar"]
+        );
+        assert_eq!(
+            index.dependencies,
+            BTreeMap::from([
+                ("fabric-loader".to_string(), "0.15.0".to_string()),
+                ("minecraft".to_string(), "1.20.1".to_string()),
+            ])
+        );
+        assert_eq!(
+            overrides.keys().map(String::as_str).collect::<Vec<_>>(),
+            [
+                "overrides/config/nested/a.json",
+                "overrides/config/z.toml",
+                "overrides/mods/b.jar",
+                "overrides/mods/c.jar.disabled",
+                "overrides/resourcepacks/pack.zip",
+            ]
+        );
+        assert_eq!(
+            names.first().map(String::as_str),
+            Some(MODRINTH_PACK_INDEX_PATH)
+        );
+        assert_eq!(names.len(), 6);
+        assert_eq!(nested, "{}");
+        let reread = reread.expect("Index should be readable");
+        assert_eq!(reread.name, "Team Pack");
+        assert_eq!(reread.version_id.as_deref(), Some("1.0.0"));
+        assert!(rejected.is_err());
+    }
+}
@@ -0,0 +1,597 @@
+use crate::core::instances::{read_instance_version_info, InstanceVersionInfo};
+use crate::core::mods::{
+    mod_file_hashes, mods_directory, scan_local_mods_blocking, LocalModFile, LocalModModrinthInfo,
+    ModFileHashes, MODS_OPERATION_LOCK,
+};
+use crate::utils::http::get_http_client;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value as JsonValue};
+use std::collections::{BTreeSet, HashMap};
+use std::path::Path;
+
+const DEFAULT_MODRINTH_API_BASE_URL: &str = "https://api.modrinth.com";
+const MODRINTH_SITE_URL: &str = "https://modrinth.com";
+const MODRINTH_USER_AGENT: &str = concat!("Epherome/", env!("CARGO_PKG_VERSION"));
+
+#[derive(Debug, Clone, Deserialize)]
+struct ModrinthVersionFile {
+    url: String,
+    filename: String,
+    #[serde(default)]
+    hashes: HashMap<String, String>,
+    #[serde(default)]
+    primary: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct ModrinthVersion {
+    id: String,
+    project_id: String,
+    version_number: String,
+    #[serde(default)]
+    files: Vec<ModrinthVersionFile>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct ModrinthProject {
+    id: String,
+    slug: String,
+    title: String,
+    #[serde(default = "default_project_type")]
+    project_type: String,
+}
+
+fn default_project_type() -> String {
+    "mod".to_string()
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModrinthModUpdate {
+    pub filename: String,
+    pub project_id: String,
+    pub current_version_id: String,
+    pub version_id: String,
+    pub version_number: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub download_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub download_filename: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModrinthIdentification {
+    /// Every scanned mod; files Modrinth knows carry their `modrinth` info.
+    pub files: Vec<LocalModFile>,
+    /// Newer versions for the instance's loader and game version, if both are known.
+    pub updates: Vec<ModrinthModUpdate>,
+}
+
+/// Modrinth loader names to filter updates by; Quilt also loads Fabric mods.
+fn modrinth_loaders(loader: &str) -> Vec<String> {
+    match loader {
+        "Quilt" => vec!["quilt".to_string(), "fabric".to_string()],
+        loader => vec![loader.to_lowercase()],
+    }
+}
+
+fn primary_file(version: &ModrinthVersion) -> Option<&ModrinthVersionFile> {
+    version
+        .files
+        .iter()
+        .find(|file| file.primary)
+        .or_else(|| version.files.first())
+}
+
+/// A minimal Modrinth v2 API client; the base URL can point at a mirror or a test server.
+struct ModrinthClient {
+    base_url: String,
+}
+
+impl ModrinthClient {
+    fn new(base_url: Option<String>) -> Self {
+        let base_url = base_url
+            .filter(|url| !url.trim().is_empty())
+            .unwrap_or_else(|| DEFAULT_MODRINTH_API_BASE_URL.to_string());
+        Self {
+            base_url: base_url.trim().trim_end_matches('/').to_string(),
+        }
+    }
+
+    async fn send<T: DeserializeOwned>(
+        &self,
+        request: reqwest::RequestBuilder,
+        path: &str,
+    ) -> Result<T, String> {
+        let response = request
+            .header(reqwest::header::USER_AGENT, MODRINTH_USER_AGENT)
+            .send()
+            .await
+            .map_err(|error| format!("Modrinth request to {path} failed: {error}"))?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(format!("Modrinth returned HTTP {status} for {path}."));
+        }
+        response
+            .json()
+            .await
+            .map_err(|error| format!("Modrinth response for {path} is invalid: {error}"))
+    }
+
+    async fn post<T: DeserializeOwned>(&self, path: &str, body: JsonValue) -> Result<T, String> {
+        let request = get_http_client()
+            .post(format!("{}{path}", self.base_url))
+            .json(&body);
+        self.send(request, path).await
+    }
+
+    /// Versions keyed by the requested hash; unknown hashes are simply absent.
+    async fn versions_from_hashes(
+        &self,
+        hashes: &[String],
+        algorithm: &str,
+    ) -> Result<HashMap<String, ModrinthVersion>, String> {
+        if hashes.is_empty() {
+            return Ok(HashMap::new());
+        }
+        self.post(
+            "/v2/version_files",
+            json!({ "hashes": hashes, "algorithm": algorithm }),
+        )
+        .await
+    }
+
+    /// The version each file belongs to, looked up by SHA-512 with a SHA-1 fallback.
+    async fn versions_for_files(
+        &self,
+        hashes: &[ModFileHashes],
+    ) -> Result<Vec<Option<ModrinthFileMatch>>, String> {
+        // SHA-512 is the preferred lookup; SHA-1 catches files indexed before SHA-512 existed.
+        let sha512_hashes: Vec<String> = hashes.iter().map(|hash| hash.sha512.clone()).collect();
+        let by_sha512 = self.versions_from_hashes(&sha512_hashes, "sha512").await?;
+        let missing_sha1: Vec<String> = hashes
+            .iter()
+            .filter(|hash| !by_sha512.contains_key(&hash.sha512))
+            .map(|hash| hash.sha1.clone())
+            .collect();
+        let by_sha1 = self.versions_from_hashes(&missing_sha1, "sha1").await?;
+        Ok(hashes
+            .iter()
+            .map(|hash| {
+                let matched = |version: &ModrinthVersion, algorithm| ModrinthFileMatch {
+                    version: version.clone(),
+                    algorithm,
+                };
+                by_sha512
+                    .get(&hash.sha512)
+                    .map(|version| matched(version, HashAlgorithm::Sha512))
+                    .or_else(|| {
+                        by_sha1
+                            .get(&hash.sha1)
+                            .map(|version| matched(version, HashAlgorithm::Sha1))
+                    })
+            })
+            .collect())
+    }
+
+    async fn latest_versions_from_hashes(
+        &self,
+        hashes: &[String],
+        algorithm: &str,
+        loaders: &[String],
+        game_versions: &[String],
+    ) -> Result<HashMap<String, ModrinthVersion>, String> {
+        if hashes.is_empty() {
+            return Ok(HashMap::new());
+        }
+        self.post(
+            "/v2/version_files/update",
+            json!({
+                "hashes": hashes,
+                "algorithm": algorithm,
+                "loaders": loaders,
+                "game_versions": game_versions,
+            }),
+        )
+        .await
+    }
+
+    async fn projects(&self, ids: &[String]) -> Result<Vec<ModrinthProject>, String> {
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        let path = "/v2/projects";
+        let mut url = reqwest::Url::parse(&format!("{}{path}", self.base_url))
+            .map_err(|error| format!("Modrinth API URL is invalid: {error}"))?;
+        url.query_pairs_mut()
+            .append_pair("ids", &JsonValue::from(ids).to_string());
+        self.send(get_http_client().get(url), path).await
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+enum HashAlgorithm {
+    Sha1,
+    Sha512,
+}
+
+impl HashAlgorithm {
+    fn name(self) -> &'static str {
+        match self {
+            HashAlgorithm::Sha1 => "sha1",
+            HashAlgorithm::Sha512 => "sha512",
+        }
+    }
+
+    fn hash(self, hashes: &ModFileHashes) -> &str {
+        match self {
+            HashAlgorithm::Sha1 => &hashes.sha1,
+            HashAlgorithm::Sha512 => &hashes.sha512,
+        }
+    }
+}
+
+/// The Modrinth version of a local file and the hash that found it.
+struct ModrinthFileMatch {
+    version: ModrinthVersion,
+    algorithm: HashAlgorithm,
+}
+
+async fn identify_local_mods(
+    client: &ModrinthClient,
+    mut files: Vec<LocalModFile>,
+    hashes: Vec<ModFileHashes>,
+    version: &InstanceVersionInfo,
+) -> Result<ModrinthIdentification, String> {
+    let matched = client.versions_for_files(&hashes).await?;
+    let project_ids: Vec<String> = matched
+        .iter()
+        .flatten()
+        .map(|matched| matched.version.project_id.clone())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect();
+    let projects: HashMap<String, ModrinthProject> = client
+        .projects(&project_ids)
+        .await?
+        .into_iter()
+        .map(|project| (project.id.clone(), project))
+        .collect();
+
+    for (file, matched) in files.iter_mut().zip(&matched) {
+        let Some(ModrinthFileMatch { version, .. }) = matched else {
+            continue;
+        };
+        let Some(project) = projects.get(&version.project_id) else {
+            continue;
+        };
+        file.modrinth = Some(LocalModModrinthInfo {
+            project_id: project.id.clone(),
+            version_id: version.id.clone(),
+            project_title: project.title.clone(),
+            page_url: format!(
+                "{MODRINTH_SITE_URL}/{}/{}",
+                project.project_type, project.slug
+            ),
+        });
+    }
+
+    let mut updates = Vec::new();
+    if let (Some(loader), Some(game_version)) = (&version.loader, &version.game_version) {
+        // Each update lookup must use the algorithm that identified the file, or Modrinth
+        // cannot resolve it.
+        let mut latest = HashMap::new();
+        for algorithm in [HashAlgorithm::Sha512, HashAlgorithm::Sha1] {
+            let identified: Vec<String> = hashes
+                .iter()
+                .zip(&matched)
+                .filter(|(_, matched)| {
+                    matched
+                        .as_ref()
+                        .is_some_and(|matched| matched.algorithm == algorithm)
+                })
+                .map(|(hash, _)| algorithm.hash(hash).to_string())
+                .collect();
+            let found = client
+                .latest_versions_from_hashes(
+                    &identified,
+                    algorithm.name(),
+                    &modrinth_loaders(loader),
+                    std::slice::from_ref(game_version),
+                )
+                .await?;
+            latest.extend(
+                found
+                    .into_iter()
+                    .map(|(hash, version)| ((algorithm, hash), version)),
+            );
+        }
+        for ((file, hash), matched) in files.iter().zip(&hashes).zip(&matched) {
+            let Some(ModrinthFileMatch {
+                version: current,
+                algorithm,
+            }) = matched
+            else {
+                continue;
+            };
+            let Some(latest) = latest.get(&(*algorithm, algorithm.hash(hash).to_string())) else {
+                continue;
+            };
+            if latest.id == current.id || latest.project_id != current.project_id {
+                continue;
+            }
+            let download = primary_file(latest);
+            updates.push(ModrinthModUpdate {
+                filename: file.filename.clone(),
+                project_id: latest.project_id.clone(),
+                current_version_id: current.id.clone(),
+                version_id: latest.id.clone(),
+                version_number: latest.version_number.clone(),
+                download_url: download.map(|file| file.url.clone()),
+                download_filename: download.map(|file| file.filename.clone()),
+            });
+        }
+    }
+
+    Ok(ModrinthIdentification { files, updates })
+}
+
+/// Download URL of each file's exact Modrinth release; `None` for files Modrinth does not host.
+pub async fn modrinth_download_urls(
+    api_base_url: Option<String>,
+    hashes: &[ModFileHashes],
+) -> Result<Vec<Option<String>>, String> {
+    let versions = ModrinthClient::new(api_base_url)
+        .versions_for_files(hashes)
+        .await?;
+    Ok(hashes
+        .iter()
+        .zip(versions)
+        .map(|(hash, matched)| {
+            matched?
+                .version
+                .files
+                .into_iter()
+                .find(|file| {
+                    file.hashes.get("sha512") == Some(&hash.sha512)
+                        || file.hashes.get("sha1") == Some(&hash.sha1)
+                })
+                .map(|file| file.url)
+        })
+        .collect())
+}
+
+/// Scan and hash the instance's mods, returning them with the instance version.
+fn prepare_modrinth_identification(
+    game_directory: &str,
+    version_id: &str,
+) -> Result<(Vec<LocalModFile>, Vec<ModFileHashes>, InstanceVersionInfo), String> {
+    let version = read_instance_version_info(Path::new(game_directory), version_id)?;
+    let mods_directory = mods_directory(game_directory)?;
+    let files = scan_local_mods_blocking(game_directory)?;
+    let hashes = files
+        .iter()
+        .map(|file| mod_file_hashes(&mods_directory.join(&file.filename)))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok((files, hashes, version))
+}
+
+/// Identify installed mods on Modrinth by hash and look for updates matching the instance.
+#[tauri::command]
+pub async fn identify_local_mods_on_modrinth(
+    game_directory: String,
+    version_id: String,
+    api_base_url: Option<String>,
+) -> Result<ModrinthIdentification, String> {
+    let operation_guard = MODS_OPERATION_LOCK.lock().await;
+    let (files, hashes, version) = tokio::task::spawn_blocking(move || {
+        let _operation_guard = operation_guard;
+        prepare_modrinth_identification(&game_directory, &version_id)
+    })
+    .await
+    .map_err(|error| format!("Mod hashing task failed: {error}"))??;
+
+    identify_local_mods(&ModrinthClient::new(api_base_url), files, hashes, &version).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::test_fixtures::temp_game_directory;
+    use std::fs;
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::sync::{Arc, Mutex};
+
+    type MockRoute = Box<dyn Fn(&str, &JsonValue) -> Option<JsonValue> + Send>;
+    type RecordedRequests = Arc<Mutex<Vec<(String, JsonValue)>>>;
+
+    /// Serve JSON from `route(path, body)` on a local port, recording every request.
+    fn mock_server(route: MockRoute) -> (String, RecordedRequests) {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("Mock server should bind");
+        let address = listener
+            .local_addr()
+            .expect("Mock server should have an address");
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let recorded = requests.clone();
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(mut stream) = stream else { continue };
+                let mut reader = BufReader::new(stream.try_clone().expect("Stream should clone"));
+                let mut request_line = String::new();
+                reader.read_line(&mut request_line).ok();
+                let target = request_line
+                    .split_whitespace()
+                    .nth(1)
+                    .unwrap_or_default()
+                    .to_string();
+                let mut content_length = 0;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).ok();
+                    let line = line.trim_end();
+                    if line.is_empty() {
+                        break;
+                    }
+                    if let Some((name, value)) = line.split_once(':') {
+                        if name.eq_ignore_ascii_case("content-length") {
+                            content_length = value.trim().parse().unwrap_or(0);
+                        }
+                    }
+                }
+                let mut body = vec![0; content_length];
+                reader.read_exact(&mut body).ok();
+                let body = serde_json::from_slice(&body).unwrap_or(JsonValue::Null);
+                let path = target.split('?').next().unwrap_or_default();
+                let response = route(path, &body);
+                recorded
+                    .lock()
+                    .expect("Recorded requests should be lockable")
+                    .push((target.clone(), body));
+
+                let (status, payload) = match response {
+                    Some(payload) => ("200 OK", payload.to_string()),
+                    None => ("404 Not Found", "{}".to_string()),
+                };
+                let _ = write!(
+                    stream,
+                    "HTTP/1.1 {status}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{payload}",
+                    payload.len()
+                );
+            }
+        });
+        (format!("http://{address}/"), requests)
+    }
+
+    fn version_json(id: &str, files: JsonValue) -> JsonValue {
+        json!({
+            "id": id,
+            "project_id": "P1",
+            "version_number": id,
+            "files": files,
+        })
+    }
+
+    #[tokio::test]
+    async fn identifies_mods_by_hash_and_reports_updates() {
+        let game_directory = temp_game_directory("modrinth-identify");
+        let version_directory = game_directory.join("versions").join("fabric");
+        fs::create_dir_all(&version_directory).expect("Version directory should be created");
+        fs::write(
+            version_directory.join("fabric.json"),
+            json!({
+                "id": "fabric",
+                "libraries": [
+                    {"name": "net.fabricmc:fabric-loader:0.16.5"},
+                    {"name": "net.fabricmc:intermediary:1.21.1"}
+                ]
+            })
+            .to_string(),
+        )
+        .expect("Version JSON should be written");
+        let mods = game_directory.join("mods");
+        fs::write(mods.join("known.jar"), b"abc").expect("Fixture should be written");
+        fs::write(mods.join("legacy.jar"), b"legacy").expect("Fixture should be written");
+        fs::write(mods.join("unknown.jar"), b"unknown").expect("Fixture should be written");
+
+        let known = mod_file_hashes(&mods.join("known.jar")).expect("Fixture should hash");
+        assert_eq!(known.sha1, "a9993e364706816aba3e25717850c26c9cd0d89d");
+        assert!(known.sha512.starts_with("ddaf35a193617abacc417349ae204131"));
+        let legacy = mod_file_hashes(&mods.join("legacy.jar")).expect("Fixture should hash");
+
+        let (known_sha512, legacy_sha1) = (known.sha512.clone(), legacy.sha1.clone());
+        let (base_url, requests) = mock_server(Box::new(move |path, body| match path {
+            "/v2/version_files" if body["algorithm"] == "sha512" => {
+                Some(json!({ known_sha512.clone(): version_json("v1", json!([])) }))
+            }
+            "/v2/version_files" if body["algorithm"] == "sha1" => {
+                Some(json!({ legacy_sha1.clone(): version_json("v0", json!([])) }))
+            }
+            "/v2/projects" => Some(json!([{
+                "id": "P1",
+                "slug": "example",
+                "title": "Example Mod",
+                "project_type": "mod"
+            }])),
+            // Like Modrinth, only resolve hashes of the requested algorithm.
+            "/v2/version_files/update" if body["algorithm"] == "sha512" => {
+                Some(json!({ known_sha512.clone(): version_json("v1", json!([])) }))
+            }
+            "/v2/version_files/update" if body["algorithm"] == "sha1" => Some(json!({
+                legacy_sha1.clone(): version_json("v2", json!([
+                    {"url": "https://cdn.example/extra.jar", "filename": "extra.jar", "primary": false},
+                    {"url": "https://cdn.example/example-2.jar", "filename": "example-2.jar", "primary": true}
+                ]))
+            })),
+            _ => None,
+        }));
+
+        let game_directory_text = game_directory.display().to_string();
+        let (files, hashes, version) =
+            prepare_modrinth_identification(&game_directory_text, "fabric")
+                .expect("Mods should be hashed");
+        let result = identify_local_mods(
+            &ModrinthClient::new(Some(base_url)),
+            files,
+            hashes,
+            &version,
+        )
+        .await
+        .expect("Identification should succeed");
+        let _ = fs::remove_dir_all(&game_directory);
+
+        let identified: Vec<(&str, Option<&str>)> = result
+            .files
+            .iter()
+            .map(|file| {
+                (
+                    file.filename.as_str(),
+                    file.modrinth.as_ref().map(|info| info.version_id.as_str()),
+                )
+            })
+            .collect();
+        assert_eq!(
+            identified,
+            [
+                ("known.jar", Some("v1")),
+                ("legacy.jar", Some("v0")),
+                ("unknown.jar", None)
+            ]
+        );
+        let info = result.files[0]
+            .modrinth
+            .as_ref()
+            .expect("The known mod should be identified");
+        assert_eq!(info.project_title, "Example Mod");
+        assert_eq!(info.page_url, "https://modrinth.com/mod/example");
+
+        assert_eq!(result.updates.len(), 1);
+        let update = &result.updates[0];
+        assert_eq!(update.filename, "legacy.jar");
+        assert_eq!(update.current_version_id, "v0");
+        assert_eq!(update.version_id, "v2");
+        assert_eq!(
+            update.download_url.as_deref(),
+            Some("https://cdn.example/example-2.jar")
+        );
+
+        let requests = requests
+            .lock()
+            .expect("Recorded requests should be lockable");
+        let update_requests: Vec<&JsonValue> = requests
+            .iter()
+            .filter(|(target, _)| target == "/v2/version_files/update")
+            .map(|(_, body)| body)
+            .collect();
+        assert_eq!(update_requests.len(), 2);
+        assert_eq!(update_requests[0]["loaders"], json!(["fabric"]));
+        assert_eq!(update_requests[0]["game_versions"], json!(["1.21.1"]));
+        assert_eq!(update_requests[1]["algorithm"], "sha1");
+        assert_eq!(update_requests[1]["hashes"], json!([legacy.sha1]));
+        assert!(requests
+            .iter()
+            .any(|(target, _)| target == "/v2/projects?ids=%5B%22P1%22%5D"));
+    }
+}
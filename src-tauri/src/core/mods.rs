@@ -1,16 +1,53 @@
-use flate2::read::DeflateDecoder;
-use serde::Serialize;
+use crate::core::archive::{
+    checked_zip_directory_summary, cleanup_import_failure, collect_central_directory_entries,
+    collect_central_directory_names, create_import_temp_file, hex_digest,
+    is_launcher_temp_filename, path_exists, preflight_zip_archive, publish_import_temp,
+    read_archive_entry, remove_file_if_exists, rename_without_overwrite, sha1_reader_hex,
+    validate_archive_path, validate_single_filename, write_file_atomically, ZipDirectorySummary,
+    ZipTargetRecord, PNG_SIGNATURE,
+};
+use crate::core::instances::{read_instance_version_info, InstanceVersionInfo};
+use crate::core::version::{compare_versions, version_requirement_matches, VersionSyntax};
+use crate::utils::http::get_http_client;
+use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
-use std::fs::{self, File, OpenOptions};
-use std::io::{self, Read, Seek, SeekFrom};
-use std::path::{Component, Path, PathBuf};
-use std::sync::atomic::{AtomicU64, Ordering};
-use std::time::{SystemTime, UNIX_EPOCH};
+use sha1::{Digest, Sha1};
+use sha2::Digest as _;
+use sha2::Sha512;
+use std::cmp;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fs::{self, File};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter};
+use tokio::io::AsyncWriteExt;
 use tokio::sync::Mutex;
 use toml::Value as TomlValue;
 
 const DISABLED_SUFFIX: &str = ".disabled";
-const IMPORT_TEMP_CREATE_ATTEMPTS: usize = 128;
+pub const LAUNCHER_DIRECTORY_NAME: &str = ".epherome";
+const MOD_TRASH_DIRECTORY_NAME: &str = "mod-trash";
+const MOD_TRASH_MANIFEST_FILENAME: &str = "manifest.json";
+const MOD_ICON_CACHE_DIRECTORY_NAME: &str = "mod-icons";
+const MOD_METADATA_CACHE_FILENAME: &str = "mod-metadata-cache.json";
+const MOD_PROFILES_FILENAME: &str = "mod-profiles.json";
+const MAX_MOD_PROFILE_NAME_CHARS: usize = 64;
+const MOD_BISECT_FILENAME: &str = "mod-bisect.json";
+const MOD_TRASH_ID_ATTEMPTS: usize = 128;
+/// Bump whenever `LocalModMetadata` or its parsers change, so stale caches are discarded.
+const MOD_METADATA_CACHE_VERSION: u32 = 4;
+const MURMUR2_MULTIPLIER: u32 = 0x5bd1e995;
+const MAX_MOD_METADATA_CACHE_SIZE: u64 = 64 * 1024 * 1024;
+const MAX_MOD_SCAN_WORKERS: usize = 8;
+const MOD_SCAN_PROGRESS_EVENT: &str = "mod-scan-progress";
+const MOD_DIRECTORY_CHANGE_EVENT: &str = "mod-directory-change";
+const MOD_WATCH_TICK: Duration = Duration::from_millis(100);
+const MOD_WATCH_DEBOUNCE: Duration = Duration::from_millis(500);
+const MOD_WATCH_POLL_INTERVAL: Duration = Duration::from_secs(2);
+const MAX_MOD_DOWNLOAD_SIZE: u64 = 512 * 1024 * 1024;
 const MAX_METADATA_FILE_SIZE: u64 = 1024 * 1024;
 const MAX_METADATA_COMPRESSED_SIZE: u64 = 4 * 1024 * 1024;
 const MAX_METADATA_ENTRIES_PER_DESCRIPTOR: usize = 128;
@@ -21,16 +58,18 @@ const MAX_METADATA_FIELD_BYTES: usize = 4096;
 const MAX_METADATA_PEOPLE_PER_ENTRY: usize = 256;
 const MAX_METADATA_SERIALIZED_BYTES_PER_JAR: usize = 512 * 1024;
 const MAX_METADATA_DIAGNOSTICS: usize = 64;
-const MAX_ZIP_CENTRAL_DIRECTORY_SIZE: u64 = 32 * 1024 * 1024;
-const MAX_ZIP_ENTRIES: u64 = 100_000;
-const MAX_ZIP64_EXTENSIBLE_DATA_SIZE: u64 = 1024 * 1024;
-const ZIP_EOCD_MIN_SIZE: u64 = 22;
-const ZIP_EOCD_MAX_SEARCH: u64 = ZIP_EOCD_MIN_SIZE + u16::MAX as u64;
-const ZIP_CENTRAL_DIRECTORY_HEADER_SIZE: u64 = 46;
-const ZIP_LOCAL_FILE_HEADER_SIZE: u64 = 30;
-const ZIP_TARGET_PATH_BUFFER_SIZE: usize = 64;
-const ZIP_ENCRYPTION_FLAGS: u16 = 0x2041;
-const ZIP64_EXTRA_FIELD_ID: u16 = 0x0001;
+const MAX_NESTED_JAR_DEPTH: usize = 3;
+const MAX_NESTED_JARS_PER_JAR: usize = 128;
+const MAX_NESTED_JAR_SIZE: u64 = 8 * 1024 * 1024;
+const MAX_NESTED_JAR_TOTAL_SIZE: u64 = 24 * 1024 * 1024;
+/// Serialized size charged per nested JAR for its path and field names.
+const NESTED_JAR_SERIALIZED_OVERHEAD: usize = 96;
+const MAX_MOD_ICON_SIZE: u64 = 2 * 1024 * 1024;
+const MAX_MOD_ICON_CACHE_ENTRIES: usize = 1024;
+const MAX_BUNDLED_MOD_SIZE: u64 = 128 * 1024 * 1024;
+const MAX_BUNDLE_IMPORT_ENTRIES: usize = 1024;
+const MAX_BUNDLE_FOLDER_DEPTH: usize = 4;
+const DEFAULT_MOD_ICON_SIZE: u32 = 64;
 const MANIFEST_PATH: &str = "META-INF/MANIFEST.MF";
 const MOD_DESCRIPTOR_PATHS: [&str; 5] = [
     "fabric.mod.json",
@@ -39,36 +78,144 @@ const MOD_DESCRIPTOR_PATHS: [&str; 5] = [
     "META-INF/neoforge.mods.toml",
     "mcmod.info",
 ];
-const ZIP_TARGET_PATHS: [&str; 6] = [
+const JARJAR_METADATA_PATH: &str = "META-INF/jarjar/metadata.json";
+const ZIP_TARGET_PATHS: [&str; 7] = [
     MANIFEST_PATH,
     "fabric.mod.json",
     "quilt.mod.json",
     "META-INF/mods.toml",
     "META-INF/neoforge.mods.toml",
     "mcmod.info",
+    JARJAR_METADATA_PATH,
 ];
 const TRUNCATED_FIELD_SUFFIX: &str = "… [truncated]";
 
-static MODS_OPERATION_LOCK: Mutex<()> = Mutex::const_new(());
-static IMPORT_TEMP_COUNTER: AtomicU64 = AtomicU64::new(0);
+pub static MODS_OPERATION_LOCK: Mutex<()> = Mutex::const_new(());
+static MOD_TRASH_COUNTER: AtomicU64 = AtomicU64::new(0);
+static MOD_WATCH_COUNTER: AtomicU64 = AtomicU64::new(0);
+static MOD_WATCHERS: std::sync::Mutex<Vec<ModDirectoryWatcher>> = std::sync::Mutex::new(Vec::new());
+static MOD_SCAN_CANCELLATIONS: std::sync::Mutex<Vec<(String, Arc<AtomicBool>)>> =
+    std::sync::Mutex::new(Vec::new());
 
-#[derive(Serialize)]
+#[derive(Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct LocalModFile {
     pub filename: String,
     pub enabled: bool,
     pub size: u64,
     pub metadata: LocalModMetadata,
+    /// CurseForge's whitespace-insensitive MurmurHash2 of the file; unset if it could not be read.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub curseforge_fingerprint: Option<u32>,
+    /// Set once the file has been identified on Modrinth by its hash.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub modrinth: Option<LocalModModrinthInfo>,
+    /// Set once the file has been matched on CurseForge by its fingerprint.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub curseforge: Option<LocalModCurseForgeInfo>,
+    /// Set when the scan was given the instance version to check against.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub compatibility: Option<LocalModCompatibility>,
+    /// Set for enabled mods that do nothing on the side the scan was asked to check.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub side_warnings: Vec<LocalModSideWarning>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum InstanceSide {
+    Client,
+    Server,
+}
+
+impl InstanceSide {
+    fn other(self) -> Self {
+        match self {
+            InstanceSide::Client => InstanceSide::Server,
+            InstanceSide::Server => InstanceSide::Client,
+        }
+    }
+
+    /// Read a Fabric/Quilt `environment` or Forge `side`; `None` means both sides.
+    fn from_environment(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "client" => Some(InstanceSide::Client),
+            "server" | "dedicated_server" => Some(InstanceSide::Server),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalModSideWarning {
+    /// "otherSide" when the mod only runs on the other side, or "otherSideDependency"
+    /// when every enabled mod that requires it only requires it there.
+    pub kind: String,
+    /// The side the mod is meant for.
+    pub side: InstanceSide,
+    /// Filenames of the dependents, for "otherSideDependency".
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub required_by: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalModCompatibility {
+    /// "compatible", "incompatible", or "unknown" when the jar has no readable metadata.
+    pub verdict: String,
+    pub issues: Vec<LocalModCompatibilityIssue>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalModCompatibilityIssue {
+    /// "loader", "gameVersion" or "loaderVersion".
+    pub kind: String,
+    /// The mod's declared loader(s) or version requirement.
+    pub required: String,
+    /// The instance's loader or version; `None` for a vanilla instance.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub found: Option<String>,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalModCurseForgeInfo {
+    pub project_id: u64,
+    pub file_id: u64,
+    pub project_name: String,
+    pub page_url: String,
 }
 
-#[derive(Debug, Default, Serialize)]
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalModModrinthInfo {
+    pub project_id: String,
+    pub version_id: String,
+    pub project_title: String,
+    pub page_url: String,
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct LocalModMetadata {
     pub entries: Vec<LocalModMetadataEntry>,
     pub diagnostics: Vec<String>,
+    /// Jar-in-jar mods declared by Fabric/Quilt `jars` or `META-INF/jarjar/metadata.json`.
+    pub nested: Vec<LocalNestedMod>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalNestedMod {
+    /// Path of the nested JAR inside its parent.
+    pub path: String,
+    pub size: u64,
+    pub metadata: LocalModMetadata,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct LocalModMetadataEntry {
     pub source: String,
@@ -91,9 +238,10 @@ pub struct LocalModMetadataEntry {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub environment: Option<String>,
     pub dependencies: Vec<LocalModDependency>,
+    pub provides: Vec<String>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct LocalModDependency {
     pub relation: String,
@@ -108,6 +256,92 @@ pub struct LocalModDependency {
     pub ordering: Option<String>,
 }
 
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalModScanProgress {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scan_id: Option<String>,
+    pub parsed: usize,
+    pub total: usize,
+    pub filename: String,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalModRename {
+    pub from: String,
+    pub file: LocalModFile,
+}
+
+/// One debounced batch of changes in a watched mods directory.
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalModDirectoryChange {
+    pub watch_id: String,
+    /// New files, including files rewritten in place under the same name.
+    pub added: Vec<LocalModFile>,
+    pub removed: Vec<String>,
+    pub renamed: Vec<LocalModRename>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct ModFileStamp {
+    size: u64,
+    modified_ns: Option<u64>,
+    inode: Option<u64>,
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+struct ModDirectoryDiff {
+    added: Vec<String>,
+    removed: Vec<String>,
+    renamed: Vec<(String, String)>,
+}
+
+/// One watcher thread per mods directory, shared by every subscriber of that directory.
+struct ModDirectoryWatcher {
+    watch_id: String,
+    mods_directory: PathBuf,
+    subscribers: usize,
+    stopped: Arc<AtomicBool>,
+}
+
+/// A mod file found by a scan whose metadata still has to be parsed.
+struct ModScanJob {
+    path: PathBuf,
+    filename: String,
+    enabled: bool,
+    size: u64,
+    modified_ns: Option<u64>,
+}
+
+/// Cancellation flag of one scan, registered under its ID while the scan runs.
+struct ModScanRegistration {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl ModScanRegistration {
+    fn new(scan_id: Option<&str>) -> Self {
+        let cancelled = Arc::new(AtomicBool::new(false));
+        if let Some(scan_id) = scan_id {
+            MOD_SCAN_CANCELLATIONS
+                .lock()
+                .unwrap_or_else(|error| error.into_inner())
+                .push((scan_id.to_string(), cancelled.clone()));
+        }
+        Self { cancelled }
+    }
+}
+
+impl Drop for ModScanRegistration {
+    fn drop(&mut self) {
+        MOD_SCAN_CANCELLATIONS
+            .lock()
+            .unwrap_or_else(|error| error.into_inner())
+            .retain(|(_, cancelled)| !Arc::ptr_eq(cancelled, &self.cancelled));
+    }
+}
+
 #[derive(Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct LocalModImportFailure {
@@ -122,7 +356,235 @@ pub struct LocalModImportResult {
     pub failed: Vec<LocalModImportFailure>,
 }
 
-fn mods_directory(game_directory: &str) -> Result<PathBuf, String> {
+/// A mod to download into the mods directory, verified against at least one of its hashes.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalModDownload {
+    pub url: String,
+    pub filename: String,
+    pub sha1: Option<String>,
+    pub sha512: Option<String>,
+    /// Move installed jars declaring the same mod ID to the trash, unless they are newer.
+    #[serde(default)]
+    pub replace_older: bool,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalModInstallResult {
+    pub installed: LocalModFile,
+    pub replaced: Vec<TrashedLocalMod>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModFileHashes {
+    pub sha1: String,
+    pub sha512: String,
+}
+
+#[derive(Default)]
+pub struct ModFileHasher {
+    sha1: Sha1,
+    sha512: Sha512,
+}
+
+impl ModFileHasher {
+    pub fn update(&mut self, bytes: &[u8]) {
+        self.sha1.update(bytes);
+        self.sha512.update(bytes);
+    }
+
+    pub fn finish(self) -> ModFileHashes {
+        ModFileHashes {
+            sha1: hex_digest(&self.sha1.finalize()),
+            sha512: hex_digest(&self.sha512.finalize()),
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrashedLocalMod {
+    /// Name of the file inside the trash folder.
+    pub id: String,
+    /// Original filename in `mods/`, including a `.disabled` suffix if it had one.
+    pub filename: String,
+    pub size: u64,
+    pub deleted_at_ms: u64,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ModTrashManifest {
+    entries: Vec<TrashedLocalMod>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ModMetadataCacheEntry {
+    size: u64,
+    modified_ns: u64,
+    metadata: LocalModMetadata,
+    curseforge_fingerprint: Option<u32>,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ModMetadataCache {
+    version: u32,
+    /// Keyed by the actual filename; a toggled mod reuses its counterpart's entry.
+    entries: BTreeMap<String, ModMetadataCacheEntry>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalModOperationFailure {
+    pub filename: String,
+    pub reason: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalModDeleteResult {
+    pub deleted: Vec<TrashedLocalMod>,
+    pub failed: Vec<LocalModOperationFailure>,
+}
+
+/// Every requested filename ends up in exactly one list.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalModStateBatchResult {
+    /// Empty whenever anything failed, since the whole batch is then rolled back.
+    pub changed: Vec<LocalModRename>,
+    pub unchanged: Vec<String>,
+    pub failed: Vec<LocalModOperationFailure>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalModRestoreResult {
+    pub restored: Vec<String>,
+    pub failed: Vec<LocalModOperationFailure>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalModDependencyProblem {
+    /// "missing", "versionMismatch", "breaks" or "conflicts".
+    pub kind: String,
+    /// "error" when the loader refuses to start, "warning" otherwise.
+    pub severity: String,
+    pub mod_id: String,
+    pub filename: String,
+    pub dependency_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version_requirement: Option<String>,
+    pub found_versions: Vec<String>,
+    pub dependency_filenames: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalModProvidedDependency {
+    pub mod_id: String,
+    pub filename: String,
+    pub dependency_id: String,
+    pub provider_mod_id: String,
+    pub provider_filename: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalModDependencyReport {
+    pub version: InstanceVersionInfo,
+    pub problems: Vec<LocalModDependencyProblem>,
+    pub provided: Vec<LocalModProvidedDependency>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalModDuplicateProvider {
+    pub filename: String,
+    pub loader: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalModDuplicateGroup {
+    pub mod_id: String,
+    pub providers: Vec<LocalModDuplicateProvider>,
+    /// Filename of the newest provider; `None` when the versions are missing, tied or incomparable.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub suggested_keep: Option<String>,
+}
+
+/// A named set of mods to enable together; every other mod is disabled when it is applied.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalModProfile {
+    pub name: String,
+    /// Enabled filenames (without `.disabled`), sorted.
+    pub mods: Vec<String>,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ModProfileStore {
+    profiles: Vec<LocalModProfile>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalModProfileApplyResult {
+    /// Filenames after the switch, for mods that were enabled or disabled by it.
+    pub enabled: Vec<String>,
+    pub disabled: Vec<String>,
+    /// Profile mods that are not installed.
+    pub missing: Vec<String>,
+}
+
+/// A bisection over the enabled mods, persisted between launches.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalModBisectSession {
+    /// Enabled filenames (without `.disabled`) when the session started, restored when it ends.
+    pub original_enabled: Vec<String>,
+    /// Mods that may still be the culprit; the other original mods stay enabled.
+    pub suspects: Vec<String>,
+    /// Suspects enabled for the current launch; the remaining suspects are disabled.
+    pub testing: Vec<String>,
+    /// Suspects in `testing` that are only enabled because other tested mods require them.
+    pub kept_for_dependencies: Vec<String>,
+    /// 1-based number of the current launch.
+    pub round: u32,
+    /// The single remaining suspect once the session has finished.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub culprit: Option<String>,
+    /// Set once the original state has been restored; `suspects` then holds what was left.
+    #[serde(default)]
+    pub finished: bool,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum DependencyKind {
+    Required,
+    Optional,
+    Breaks,
+    Conflicts,
+    Ordering,
+}
+
+/// Something that satisfies a mod ID: a jar, or the game and loader themselves.
+struct ModProvider<'a> {
+    filename: Option<&'a str>,
+    mod_id: &'a str,
+    version: Option<&'a str>,
+    via_provides: bool,
+}
+
+pub fn game_directory_path(game_directory: &str) -> Result<PathBuf, String> {
     if game_directory.trim().is_empty() {
         return Err("Game directory cannot be empty.".to_string());
     }
@@ -143,7 +605,41 @@ fn mods_directory(game_directory: &str) -> Result<PathBuf, String> {
         ));
     }
 
-    Ok(game_directory.join("mods"))
+    Ok(game_directory)
+}
+
+pub fn mods_directory(game_directory: &str) -> Result<PathBuf, String> {
+    Ok(game_directory_path(game_directory)?.join("mods"))
+}
+
+fn mod_trash_directory(game_directory: &str) -> Result<PathBuf, String> {
+    Ok(game_directory_path(game_directory)?
+        .join(LAUNCHER_DIRECTORY_NAME)
+        .join(MOD_TRASH_DIRECTORY_NAME))
+}
+
+pub fn create_launcher_directory(directory: &Path) -> Result<(), String> {
+    fs::create_dir_all(directory).map_err(|error| {
+        format!(
+            "Failed to create launcher directory '{}': {}",
+            directory.display(),
+            error
+        )
+    })?;
+    let metadata = fs::symlink_metadata(directory).map_err(|error| {
+        format!(
+            "Failed to access launcher directory '{}': {}",
+            directory.display(),
+            error
+        )
+    })?;
+    if metadata.file_type().is_symlink() || !metadata.is_dir() {
+        return Err(format!(
+            "Launcher directory '{}' must be a real directory.",
+            directory.display()
+        ));
+    }
+    Ok(())
 }
 
 fn ensure_existing_mods_directory(mods_directory: &Path) -> Result<bool, String> {
@@ -212,23 +708,6 @@ fn enabled_filename(filename: &str) -> Option<String> {
     Some(filename.get(..prefix_length)?.to_string())
 }
 
-fn validate_single_filename(filename: &str) -> Result<(), String> {
-    if filename.is_empty() || filename == "." || filename == ".." {
-        return Err("Mod filename is invalid.".to_string());
-    }
-
-    if filename.contains('/') || filename.contains('\\') {
-        return Err("Mod filename must not contain path separators.".to_string());
-    }
-
-    let mut components = Path::new(filename).components();
-    if !matches!(components.next(), Some(Component::Normal(_))) || components.next().is_some() {
-        return Err("Mod filename must be a single file name.".to_string());
-    }
-
-    Ok(())
-}
-
 fn validate_mod_filename(filename: &str) -> Result<(), String> {
     validate_single_filename(filename)?;
     if !is_mod_filename(filename) {
@@ -245,123 +724,89 @@ fn validate_import_filename(filename: &str) -> Result<(), String> {
     Ok(())
 }
 
-fn path_exists(path: &Path) -> Result<bool, String> {
-    match fs::symlink_metadata(path) {
-        Ok(_) => Ok(true),
-        Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(false),
-        Err(error) => Err(format!(
-            "Failed to check destination '{}': {}",
-            path.display(),
-            error
-        )),
-    }
+fn unix_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
 }
 
-#[cfg(any(target_os = "linux", target_os = "android", target_vendor = "apple"))]
-fn rename_without_overwrite(source: &Path, destination: &Path) -> Result<(), io::Error> {
-    rustix::fs::renameat_with(
-        rustix::fs::CWD,
-        source,
-        rustix::fs::CWD,
-        destination,
-        rustix::fs::RenameFlags::NOREPLACE,
-    )
-    .map_err(io::Error::from)
-}
-
-#[cfg(target_os = "windows")]
-fn rename_without_overwrite(source: &Path, destination: &Path) -> Result<(), io::Error> {
-    // Windows rename semantics reject an existing destination.
-    fs::rename(source, destination)
-}
-
-#[cfg(not(any(
-    target_os = "linux",
-    target_os = "android",
-    target_vendor = "apple",
-    target_os = "windows"
-)))]
-fn rename_without_overwrite(source: &Path, destination: &Path) -> Result<(), io::Error> {
-    match fs::symlink_metadata(destination) {
-        Ok(_) => {
-            return Err(io::Error::new(
-                io::ErrorKind::AlreadyExists,
-                "Destination already exists.",
+fn write_json_atomically(path: &Path, value: &impl Serialize) -> Result<(), String> {
+    let bytes = serde_json::to_vec_pretty(value)
+        .map_err(|error| format!("Failed to serialize '{}': {error}", path.display()))?;
+    write_file_atomically(path, &bytes)
+}
+
+fn read_mod_trash_manifest(trash_directory: &Path) -> Result<ModTrashManifest, String> {
+    let path = trash_directory.join(MOD_TRASH_MANIFEST_FILENAME);
+    let text = match fs::read_to_string(&path) {
+        Ok(text) => text,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => {
+            return Ok(ModTrashManifest::default());
+        }
+        Err(error) => {
+            return Err(format!(
+                "Failed to read mod trash manifest '{}': {error}",
+                path.display()
             ));
         }
-        Err(error) if error.kind() == io::ErrorKind::NotFound => {}
-        Err(error) => return Err(error),
-    }
-
-    fs::rename(source, destination)
+    };
+    // A corrupt manifest is never overwritten; it is the only record of what can be restored.
+    serde_json::from_str(&text).map_err(|error| {
+        format!(
+            "Mod trash manifest '{}' is corrupt: {error}",
+            path.display()
+        )
+    })
 }
 
-fn create_import_temp_file(mods_directory: &Path) -> Result<(PathBuf, File), String> {
-    let timestamp = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .unwrap_or_default()
-        .as_nanos();
+fn write_mod_trash_manifest(
+    trash_directory: &Path,
+    manifest: &ModTrashManifest,
+) -> Result<(), String> {
+    write_json_atomically(&trash_directory.join(MOD_TRASH_MANIFEST_FILENAME), manifest)
+}
 
-    for _ in 0..IMPORT_TEMP_CREATE_ATTEMPTS {
-        let counter = IMPORT_TEMP_COUNTER.fetch_add(1, Ordering::Relaxed);
-        let filename = format!(
-            ".epherome-mod-import-{}-{timestamp}-{counter}.tmp",
-            std::process::id()
-        );
-        let path = mods_directory.join(filename);
+fn move_mod_to_trash(
+    mods_directory: &Path,
+    trash_directory: &Path,
+    filename: &str,
+) -> Result<TrashedLocalMod, String> {
+    validate_mod_filename(filename)?;
+    let source = mods_directory.join(filename);
+    let metadata = fs::symlink_metadata(&source)
+        .map_err(|error| format!("Failed to read mod file '{}': {}", source.display(), error))?;
+    if !metadata.is_file() {
+        return Err(format!("Mod path '{}' is not a file.", source.display()));
+    }
 
-        match OpenOptions::new().write(true).create_new(true).open(&path) {
-            Ok(file) => return Ok((path, file)),
+    let deleted_at_ms = unix_millis();
+    for _ in 0..MOD_TRASH_ID_ATTEMPTS {
+        let counter = MOD_TRASH_COUNTER.fetch_add(1, Ordering::Relaxed);
+        let id = format!("{deleted_at_ms}-{counter}-{filename}");
+        match rename_without_overwrite(&source, &trash_directory.join(&id)) {
+            Ok(()) => {
+                return Ok(TrashedLocalMod {
+                    id,
+                    filename: filename.to_string(),
+                    size: metadata.len(),
+                    deleted_at_ms,
+                });
+            }
             Err(error) if error.kind() == io::ErrorKind::AlreadyExists => continue,
             Err(error) => {
                 return Err(format!(
-                    "Failed to create temporary import file in '{}': {}",
-                    mods_directory.display(),
-                    error
+                    "Failed to move '{filename}' to the mod trash: {error}"
                 ));
             }
         }
     }
 
     Err(format!(
-        "Failed to create a unique temporary import file in '{}'.",
-        mods_directory.display()
+        "Failed to find a unique name for '{filename}' in the mod trash."
     ))
 }
 
-fn remove_file_if_exists(path: &Path) -> Result<(), io::Error> {
-    match fs::remove_file(path) {
-        Ok(()) => Ok(()),
-        Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(()),
-        Err(error) => Err(error),
-    }
-}
-
-fn cleanup_import_failure(temp_path: &Path, reason: String) -> String {
-    match remove_file_if_exists(temp_path) {
-        Ok(()) => reason,
-        Err(cleanup_error) => format!(
-            "{reason} Also failed to remove temporary file '{}': {cleanup_error}",
-            temp_path.display()
-        ),
-    }
-}
-
-fn publish_import_temp(temp_path: &Path, destination: &Path, filename: &str) -> Result<(), String> {
-    if let Err(error) = rename_without_overwrite(temp_path, destination) {
-        let reason = if error.kind() == io::ErrorKind::AlreadyExists {
-            format!("A mod named '{filename}' was installed while the import was in progress.")
-        } else {
-            format!(
-                "Failed to atomically install '{filename}' without overwriting another file: {error}"
-            )
-        };
-        return Err(cleanup_import_failure(temp_path, reason));
-    }
-
-    Ok(())
-}
-
 fn non_empty_string(value: &str) -> Option<String> {
     let value = value.trim();
     if value.is_empty() {
@@ -445,6 +890,22 @@ fn quilt_contributors(value: Option<&JsonValue>) -> Vec<String> {
     }
 }
 
+/// Fabric lists provided IDs as strings; Quilt also allows `{"id": ..., "version": ...}` objects.
+fn json_provides(value: Option<&JsonValue>) -> Vec<String> {
+    let Some(JsonValue::Array(values)) = value else {
+        return Vec::new();
+    };
+
+    values
+        .iter()
+        .take(MAX_DEPENDENCIES_PER_ENTRY)
+        .filter_map(|value| match value {
+            JsonValue::Object(provided) => json_object_text(provided, "id"),
+            _ => json_value_text(value),
+        })
+        .collect()
+}
+
 fn dependency_version(dependencies: &[LocalModDependency], mod_ids: &[&str]) -> Option<String> {
     dependencies
         .iter()
@@ -518,6 +979,7 @@ fn fabric_metadata_entry(
         game_version,
         environment: root.get("environment").and_then(json_value_text),
         dependencies,
+        provides: json_provides(root.get("provides")),
     }
 }
 
@@ -675,6 +1137,7 @@ fn parse_quilt_metadata(text: &str, source: &str) -> Result<Vec<LocalModMetadata
             .and_then(|minecraft| minecraft.get("environment"))
             .and_then(json_value_text),
         dependencies,
+        provides: json_provides(quilt_loader.get("provides")),
     }])
 }
 
@@ -839,6 +1302,7 @@ fn parse_forge_metadata(
                     .or_else(|| mod_entry.get("displayTest"))
                     .and_then(toml_value_text),
                 dependencies,
+                provides: Vec::new(),
             }
         })
         .collect())
@@ -976,490 +1440,29 @@ fn parse_mcmod_metadata(text: &str, source: &str) -> Result<Vec<LocalModMetadata
                     .and_then(json_value_text),
                 environment: entry.get("side").and_then(json_value_text),
                 dependencies,
+                provides: Vec::new(),
             }
         })
         .collect())
 }
 
-fn little_endian_u16(bytes: &[u8], offset: usize) -> Option<u16> {
-    let bytes = bytes.get(offset..offset + 2)?;
-    Some(u16::from_le_bytes([bytes[0], bytes[1]]))
-}
-
-fn little_endian_u32(bytes: &[u8], offset: usize) -> Option<u32> {
-    let bytes = bytes.get(offset..offset + 4)?;
-    Some(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
-}
-
-fn little_endian_u64(bytes: &[u8], offset: usize) -> Option<u64> {
-    let bytes = bytes.get(offset..offset + 8)?;
-    Some(u64::from_le_bytes([
-        bytes[0], bytes[1], bytes[2], bytes[3], bytes[4], bytes[5], bytes[6], bytes[7],
-    ]))
-}
-
-fn read_file_range(file: &mut File, offset: u64, length: usize) -> Result<Vec<u8>, String> {
-    file.seek(SeekFrom::Start(offset))
-        .map_err(|error| format!("Failed to seek in the JAR: {error}"))?;
-    let mut bytes = vec![0; length];
-    file.read_exact(&mut bytes)
-        .map_err(|error| format!("Failed to read the JAR directory: {error}"))?;
-    Ok(bytes)
+struct InspectedZipArchive {
+    directory: ZipDirectorySummary,
+    /// Records for `ZIP_TARGET_PATHS`, in the same order.
+    targets: Vec<Option<ZipTargetRecord>>,
 }
 
-struct ZipDirectorySummary {
-    entry_count: u64,
-    declared_size: u64,
-    offset: u64,
-    end_structure_offset: u64,
-}
-
-#[derive(Clone, Copy)]
-struct ZipTargetEntry {
-    flags: u16,
-    compression_method: u16,
-    compressed_size: u64,
-    uncompressed_size: u64,
-    local_header_offset: u64,
-}
-
-enum ZipTargetRecord {
-    Entry(ZipTargetEntry),
-    Invalid(String),
-}
-
-struct InspectedZipArchive {
-    directory: ZipDirectorySummary,
-    targets: [Option<ZipTargetRecord>; ZIP_TARGET_PATHS.len()],
-}
-
-fn zip_directory_summary(file: &mut File) -> Result<ZipDirectorySummary, String> {
-    let file_size = file
-        .metadata()
-        .map_err(|error| format!("Failed to inspect the JAR size: {error}"))?
-        .len();
-    if file_size < ZIP_EOCD_MIN_SIZE {
-        return Err("The JAR is too small to contain a ZIP directory.".to_string());
-    }
-
-    let search_length = file_size.min(ZIP_EOCD_MAX_SEARCH);
-    let tail = read_file_range(
-        file,
-        file_size - search_length,
-        usize::try_from(search_length)
-            .map_err(|_| "The ZIP directory search range is too large.".to_string())?,
-    )?;
-    let end_record_index = (0..=tail.len() - ZIP_EOCD_MIN_SIZE as usize)
-        .rev()
-        .find(|index| {
-            if tail.get(*index..*index + 4) != Some(b"PK\x05\x06") {
-                return false;
-            }
-            let Some(comment_length) = little_endian_u16(&tail, *index + 20) else {
-                return false;
-            };
-            *index + ZIP_EOCD_MIN_SIZE as usize + comment_length as usize == tail.len()
-        })
-        .ok_or_else(|| "The JAR does not contain a valid ZIP end record.".to_string())?;
-
-    let entry_count = little_endian_u16(&tail, end_record_index + 10)
-        .ok_or_else(|| "The ZIP end record is incomplete.".to_string())?;
-    let central_directory_size = little_endian_u32(&tail, end_record_index + 12)
-        .ok_or_else(|| "The ZIP end record is incomplete.".to_string())?;
-    let central_directory_offset = little_endian_u32(&tail, end_record_index + 16)
-        .ok_or_else(|| "The ZIP end record is incomplete.".to_string())?;
-    let end_record_offset = file_size - search_length + end_record_index as u64;
-
-    if entry_count != u16::MAX
-        && central_directory_size != u32::MAX
-        && central_directory_offset != u32::MAX
-    {
-        return Ok(ZipDirectorySummary {
-            entry_count: entry_count as u64,
-            declared_size: central_directory_size as u64,
-            offset: central_directory_offset as u64,
-            end_structure_offset: end_record_offset,
-        });
-    }
-
-    let locator_offset = end_record_offset
-        .checked_sub(20)
-        .ok_or_else(|| "The ZIP64 locator is missing.".to_string())?;
-    let locator = read_file_range(file, locator_offset, 20)?;
-    if locator.get(0..4) != Some(b"PK\x06\x07") {
-        return Err("The ZIP64 locator is missing or invalid.".to_string());
-    }
-    let zip64_end_offset = little_endian_u64(&locator, 8)
-        .ok_or_else(|| "The ZIP64 locator is incomplete.".to_string())?;
-    let zip64_end = read_file_range(file, zip64_end_offset, 56)?;
-    if zip64_end.get(0..4) != Some(b"PK\x06\x06") {
-        return Err("The ZIP64 end record is missing or invalid.".to_string());
-    }
-    let zip64_record_size = little_endian_u64(&zip64_end, 4)
-        .ok_or_else(|| "The ZIP64 end record is incomplete.".to_string())?;
-    if zip64_record_size < 44 {
-        return Err("The ZIP64 end record is too short.".to_string());
-    }
-    if zip64_record_size - 44 > MAX_ZIP64_EXTENSIBLE_DATA_SIZE {
-        return Err(format!(
-            "The ZIP64 extensible data sector is {} bytes; the inspection limit is {MAX_ZIP64_EXTENSIBLE_DATA_SIZE} bytes.",
-            zip64_record_size - 44
-        ));
-    }
-    let zip64_end_record_end = zip64_end_offset
-        .checked_add(12)
-        .and_then(|offset| offset.checked_add(zip64_record_size))
-        .ok_or_else(|| "The ZIP64 end record size overflows the JAR.".to_string())?;
-    if zip64_end_record_end > locator_offset {
-        return Err("The ZIP64 end record overlaps its locator.".to_string());
-    }
-
-    let entry_count = little_endian_u64(&zip64_end, 32)
-        .ok_or_else(|| "The ZIP64 entry count is missing.".to_string())?;
-    let central_directory_size = little_endian_u64(&zip64_end, 40)
-        .ok_or_else(|| "The ZIP64 directory size is missing.".to_string())?;
-    let central_directory_offset = little_endian_u64(&zip64_end, 48)
-        .ok_or_else(|| "The ZIP64 directory offset is missing.".to_string())?;
-    Ok(ZipDirectorySummary {
-        entry_count,
-        declared_size: central_directory_size,
-        offset: central_directory_offset,
-        end_structure_offset: zip64_end_offset,
-    })
-}
-
-fn zip_target_path_index(path: &[u8]) -> Option<usize> {
-    ZIP_TARGET_PATHS
-        .iter()
-        .position(|target| target.as_bytes() == path)
-}
-
-fn read_zip64_target_entry(
-    file: &mut File,
-    extra_offset: u64,
-    extra_length: u64,
-    compressed_size_32: u32,
-    uncompressed_size_32: u32,
-    local_header_offset_32: u32,
-    disk_start_16: u16,
-) -> Result<(u64, u64, u64), String> {
-    let needs_uncompressed_size = uncompressed_size_32 == u32::MAX;
-    let needs_compressed_size = compressed_size_32 == u32::MAX;
-    let needs_local_header_offset = local_header_offset_32 == u32::MAX;
-    let needs_disk_start = disk_start_16 == u16::MAX;
-    if !needs_uncompressed_size
-        && !needs_compressed_size
-        && !needs_local_header_offset
-        && !needs_disk_start
-    {
-        if disk_start_16 != 0 {
-            return Err("Multi-disk ZIP entries are not supported.".to_string());
-        }
-        return Ok((
-            compressed_size_32 as u64,
-            uncompressed_size_32 as u64,
-            local_header_offset_32 as u64,
-        ));
-    }
-
-    let extra_end = extra_offset
-        .checked_add(extra_length)
-        .ok_or_else(|| "The ZIP extra field range overflows the JAR.".to_string())?;
-    let mut position = extra_offset;
-    let mut extra_header = [0_u8; 4];
-    while position < extra_end {
-        let header_end = position
-            .checked_add(extra_header.len() as u64)
-            .ok_or_else(|| "A ZIP extra field header offset overflows the JAR.".to_string())?;
-        if header_end > extra_end {
-            return Err("A ZIP extra field header is truncated.".to_string());
-        }
-
-        file.seek(SeekFrom::Start(position))
-            .map_err(|error| format!("Failed to seek to a ZIP extra field: {error}"))?;
-        file.read_exact(&mut extra_header)
-            .map_err(|error| format!("Failed to read a ZIP extra field header: {error}"))?;
-        let field_id = little_endian_u16(&extra_header, 0)
-            .ok_or_else(|| "A ZIP extra field ID is missing.".to_string())?;
-        let field_size = little_endian_u16(&extra_header, 2)
-            .ok_or_else(|| "A ZIP extra field size is missing.".to_string())?
-            as u64;
-        let data_offset = header_end;
-        let field_end = data_offset
-            .checked_add(field_size)
-            .ok_or_else(|| "A ZIP extra field size overflows the JAR.".to_string())?;
-        if field_end > extra_end {
-            return Err("A ZIP extra field is truncated.".to_string());
-        }
-
-        if field_id == ZIP64_EXTRA_FIELD_ID {
-            let required_size = usize::from(needs_disk_start) * 4
-                + (usize::from(needs_uncompressed_size)
-                    + usize::from(needs_compressed_size)
-                    + usize::from(needs_local_header_offset))
-                    * 8;
-            if field_size
-                < u64::try_from(required_size)
-                    .map_err(|_| "The ZIP64 extra field size overflows.".to_string())?
-            {
-                return Err("The ZIP64 extra field is missing required values.".to_string());
-            }
-
-            let mut values = [0_u8; 28];
-            file.seek(SeekFrom::Start(data_offset))
-                .map_err(|error| format!("Failed to seek to the ZIP64 extra field: {error}"))?;
-            file.read_exact(&mut values[..required_size])
-                .map_err(|error| format!("Failed to read the ZIP64 extra field: {error}"))?;
-
-            let mut cursor = 0;
-            let uncompressed_size = if needs_uncompressed_size {
-                let value = little_endian_u64(&values, cursor)
-                    .ok_or_else(|| "The ZIP64 uncompressed size is missing.".to_string())?;
-                cursor += 8;
-                value
-            } else {
-                uncompressed_size_32 as u64
-            };
-            let compressed_size = if needs_compressed_size {
-                let value = little_endian_u64(&values, cursor)
-                    .ok_or_else(|| "The ZIP64 compressed size is missing.".to_string())?;
-                cursor += 8;
-                value
-            } else {
-                compressed_size_32 as u64
-            };
-            let local_header_offset = if needs_local_header_offset {
-                let value = little_endian_u64(&values, cursor)
-                    .ok_or_else(|| "The ZIP64 local header offset is missing.".to_string())?;
-                cursor += 8;
-                value
-            } else {
-                local_header_offset_32 as u64
-            };
-            let disk_start = if needs_disk_start {
-                little_endian_u32(&values, cursor)
-                    .ok_or_else(|| "The ZIP64 disk start is missing.".to_string())?
-            } else {
-                disk_start_16 as u32
-            };
-            if disk_start != 0 {
-                return Err("Multi-disk ZIP entries are not supported.".to_string());
-            }
-
-            return Ok((compressed_size, uncompressed_size, local_header_offset));
-        }
-
-        position = field_end;
-    }
-
-    Err("The central directory entry is missing its ZIP64 extra field.".to_string())
-}
-
-fn collect_central_directory_entries(
-    file: &mut File,
-    summary: &ZipDirectorySummary,
-) -> Result<[Option<ZipTargetRecord>; ZIP_TARGET_PATHS.len()], String> {
-    if summary.offset > summary.end_structure_offset {
-        return Err("The ZIP directory starts after its end structure.".to_string());
-    }
-
-    let declared_end = summary
-        .offset
-        .checked_add(summary.declared_size)
-        .ok_or_else(|| "The declared ZIP directory range overflows the JAR.".to_string())?;
-    if declared_end > summary.end_structure_offset {
-        return Err("The declared ZIP directory overlaps its end structure.".to_string());
-    }
-
-    let mut position = summary.offset;
-    let mut actual_size = 0_u64;
-    let mut header = [0_u8; ZIP_CENTRAL_DIRECTORY_HEADER_SIZE as usize];
-    let mut targets = std::array::from_fn(|_| None);
-
-    for index in 0..summary.entry_count {
-        let header_end = position
-            .checked_add(ZIP_CENTRAL_DIRECTORY_HEADER_SIZE)
-            .ok_or_else(|| "A ZIP directory header offset overflows the JAR.".to_string())?;
-        if header_end > summary.end_structure_offset {
-            return Err(format!(
-                "ZIP directory entry {} crosses into the ZIP end structure.",
-                index + 1
-            ));
-        }
-
-        file.seek(SeekFrom::Start(position)).map_err(|error| {
-            format!(
-                "Failed to seek to ZIP directory entry {}: {error}",
-                index + 1
-            )
-        })?;
-        file.read_exact(&mut header).map_err(|error| {
-            format!("Failed to read ZIP directory entry {}: {error}", index + 1)
-        })?;
-        if header.get(0..4) != Some(b"PK\x01\x02") {
-            return Err(format!(
-                "ZIP directory entry {} has an invalid central directory signature.",
-                index + 1
-            ));
-        }
-
-        let filename_length = little_endian_u16(&header, 28)
-            .ok_or_else(|| "The ZIP directory filename length is missing.".to_string())?
-            as u64;
-        let extra_length = little_endian_u16(&header, 30)
-            .ok_or_else(|| "The ZIP directory extra length is missing.".to_string())?
-            as u64;
-        let comment_length = little_endian_u16(&header, 32)
-            .ok_or_else(|| "The ZIP directory comment length is missing.".to_string())?
-            as u64;
-        let entry_size = ZIP_CENTRAL_DIRECTORY_HEADER_SIZE
-            .checked_add(filename_length)
-            .and_then(|size| size.checked_add(extra_length))
-            .and_then(|size| size.checked_add(comment_length))
-            .ok_or_else(|| "A ZIP directory entry size overflows the JAR.".to_string())?;
-        actual_size = actual_size
-            .checked_add(entry_size)
-            .ok_or_else(|| "The actual ZIP directory size overflows the JAR.".to_string())?;
-        if actual_size > MAX_ZIP_CENTRAL_DIRECTORY_SIZE {
-            return Err(format!(
-                "The actual JAR ZIP directory exceeds the {MAX_ZIP_CENTRAL_DIRECTORY_SIZE}-byte inspection limit."
-            ));
-        }
-
-        let entry_end = position
-            .checked_add(entry_size)
-            .ok_or_else(|| "A ZIP directory entry offset overflows the JAR.".to_string())?;
-        if entry_end > summary.end_structure_offset {
-            return Err(format!(
-                "ZIP directory entry {} crosses into the ZIP end structure.",
-                index + 1
-            ));
-        }
-
-        let filename_length_usize = usize::try_from(filename_length)
-            .map_err(|_| "A ZIP directory filename length is too large.".to_string())?;
-        let could_be_target = filename_length_usize <= ZIP_TARGET_PATH_BUFFER_SIZE
-            && ZIP_TARGET_PATHS
-                .iter()
-                .any(|target| target.len() == filename_length_usize);
-        if could_be_target {
-            let mut filename = [0_u8; ZIP_TARGET_PATH_BUFFER_SIZE];
-            file.seek(SeekFrom::Start(header_end))
-                .map_err(|error| format!("Failed to seek to a ZIP filename: {error}"))?;
-            file.read_exact(&mut filename[..filename_length_usize])
-                .map_err(|error| format!("Failed to read a ZIP filename: {error}"))?;
-
-            if let Some(target_index) = zip_target_path_index(&filename[..filename_length_usize]) {
-                if targets[target_index].is_some() {
-                    targets[target_index] = Some(ZipTargetRecord::Invalid(format!(
-                        "The ZIP contains ambiguous duplicate '{}' entries.",
-                        ZIP_TARGET_PATHS[target_index]
-                    )));
-                } else {
-                    let flags = little_endian_u16(&header, 8)
-                        .ok_or_else(|| "The ZIP entry flags are missing.".to_string())?;
-                    let compression_method = little_endian_u16(&header, 10)
-                        .ok_or_else(|| "The ZIP compression method is missing.".to_string())?;
-                    let compressed_size = little_endian_u32(&header, 20)
-                        .ok_or_else(|| "The ZIP compressed size is missing.".to_string())?;
-                    let uncompressed_size = little_endian_u32(&header, 24)
-                        .ok_or_else(|| "The ZIP uncompressed size is missing.".to_string())?;
-                    let disk_start = little_endian_u16(&header, 34)
-                        .ok_or_else(|| "The ZIP disk start is missing.".to_string())?;
-                    let local_header_offset = little_endian_u32(&header, 42)
-                        .ok_or_else(|| "The ZIP local header offset is missing.".to_string())?;
-                    let extra_offset = header_end
-                        .checked_add(filename_length)
-                        .ok_or_else(|| "The ZIP extra field offset overflows.".to_string())?;
-                    targets[target_index] = Some(
-                        match read_zip64_target_entry(
-                            file,
-                            extra_offset,
-                            extra_length,
-                            compressed_size,
-                            uncompressed_size,
-                            local_header_offset,
-                            disk_start,
-                        ) {
-                            Ok((compressed_size, uncompressed_size, local_header_offset)) => {
-                                ZipTargetRecord::Entry(ZipTargetEntry {
-                                    flags,
-                                    compression_method,
-                                    compressed_size,
-                                    uncompressed_size,
-                                    local_header_offset,
-                                })
-                            }
-                            Err(error) => ZipTargetRecord::Invalid(error),
-                        },
-                    );
-                }
-            }
-        }
-
-        position = entry_end;
-    }
-
-    if actual_size > summary.declared_size {
-        return Err(format!(
-            "The actual ZIP directory entries occupy {actual_size} bytes, exceeding the declared size of {} bytes.",
-            summary.declared_size
-        ));
-    }
-
-    Ok(targets)
-}
-
-fn inspect_zip_archive(file: &mut File) -> Result<InspectedZipArchive, String> {
-    let summary = zip_directory_summary(file)?;
-    if summary.entry_count > MAX_ZIP_ENTRIES {
-        return Err(format!(
-            "The JAR contains {} ZIP entries; the inspection limit is {MAX_ZIP_ENTRIES}.",
-            summary.entry_count
-        ));
-    }
-    if summary.declared_size > MAX_ZIP_CENTRAL_DIRECTORY_SIZE {
-        return Err(format!(
-            "The declared JAR ZIP directory is {} bytes; the inspection limit is {MAX_ZIP_CENTRAL_DIRECTORY_SIZE} bytes.",
-            summary.declared_size
-        ));
-    }
-    let targets = collect_central_directory_entries(file, &summary)?;
-    Ok(InspectedZipArchive {
-        directory: summary,
-        targets,
-    })
-}
-
-#[cfg(test)]
-fn preflight_zip_archive(file: &mut File) -> Result<(), String> {
-    inspect_zip_archive(file).map(|_| ())
-}
-
-fn read_metadata_stream(reader: impl Read, expected_size: u64) -> Result<Vec<u8>, String> {
-    let capacity = usize::try_from(expected_size)
-        .map_err(|_| "The metadata output size is too large.".to_string())?;
-    let mut bytes = Vec::with_capacity(capacity);
-    reader
-        .take(MAX_METADATA_FILE_SIZE + 1)
-        .read_to_end(&mut bytes)
-        .map_err(|error| format!("Failed to read the metadata stream: {error}"))?;
-    if bytes.len() as u64 > MAX_METADATA_FILE_SIZE {
-        return Err(format!(
-            "The decompressed entry exceeds the {MAX_METADATA_FILE_SIZE}-byte inspection limit."
-        ));
-    }
-    if bytes.len() as u64 != expected_size {
-        return Err(format!(
-            "The metadata stream contains {} bytes, but the central directory declares {expected_size} bytes.",
-            bytes.len()
-        ));
-    }
-    Ok(bytes)
+fn inspect_zip_archive(file: &mut (impl Read + Seek)) -> Result<InspectedZipArchive, String> {
+    let summary = checked_zip_directory_summary(file)?;
+    let targets = collect_central_directory_entries(file, &summary, &ZIP_TARGET_PATHS)?;
+    Ok(InspectedZipArchive {
+        directory: summary,
+        targets,
+    })
 }
 
 fn read_archive_text(
-    file: &mut File,
+    file: &mut (impl Read + Seek),
     archive: &InspectedZipArchive,
     source: &str,
 ) -> Result<Option<String>, String> {
@@ -1470,113 +1473,14 @@ fn read_archive_text(
     let Some(record) = archive.targets[target_index].as_ref() else {
         return Ok(None);
     };
-    let entry = match record {
-        ZipTargetRecord::Entry(entry) => entry,
-        ZipTargetRecord::Invalid(error) => return Err(error.clone()),
-    };
-    if entry.uncompressed_size > MAX_METADATA_FILE_SIZE {
-        return Err(format!(
-            "The entry is too large to inspect ({} bytes; limit is {MAX_METADATA_FILE_SIZE} bytes).",
-            entry.uncompressed_size
-        ));
-    }
-    if entry.compressed_size > MAX_METADATA_COMPRESSED_SIZE {
-        return Err(format!(
-            "The compressed entry is too large to inspect ({} bytes; limit is {MAX_METADATA_COMPRESSED_SIZE} bytes).",
-            entry.compressed_size
-        ));
-    }
-    if entry.flags & ZIP_ENCRYPTION_FLAGS != 0 {
-        return Err("Encrypted ZIP metadata entries are not supported.".to_string());
-    }
-    if !matches!(entry.compression_method, 0 | 8) {
-        return Err(format!(
-            "ZIP compression method {} is not supported for metadata inspection.",
-            entry.compression_method
-        ));
-    }
-
-    let local_header_end = entry
-        .local_header_offset
-        .checked_add(ZIP_LOCAL_FILE_HEADER_SIZE)
-        .ok_or_else(|| "The ZIP local header offset overflows the JAR.".to_string())?;
-    if local_header_end > archive.directory.offset {
-        return Err("The ZIP local header crosses into the central directory.".to_string());
-    }
-    file.seek(SeekFrom::Start(entry.local_header_offset))
-        .map_err(|error| format!("Failed to seek to the ZIP local header: {error}"))?;
-    let mut local_header = [0_u8; ZIP_LOCAL_FILE_HEADER_SIZE as usize];
-    file.read_exact(&mut local_header)
-        .map_err(|error| format!("Failed to read the ZIP local header: {error}"))?;
-    if local_header.get(0..4) != Some(b"PK\x03\x04") {
-        return Err("The ZIP local file header signature is invalid.".to_string());
-    }
-    let local_flags = little_endian_u16(&local_header, 6)
-        .ok_or_else(|| "The ZIP local entry flags are missing.".to_string())?;
-    if local_flags & ZIP_ENCRYPTION_FLAGS != 0 {
-        return Err("Encrypted ZIP metadata entries are not supported.".to_string());
-    }
-    let local_compression_method = little_endian_u16(&local_header, 8)
-        .ok_or_else(|| "The ZIP local compression method is missing.".to_string())?;
-    if local_compression_method != entry.compression_method {
-        return Err(
-            "The ZIP local and central directory compression methods do not match.".to_string(),
-        );
-    }
-    let local_filename_length = little_endian_u16(&local_header, 26)
-        .ok_or_else(|| "The ZIP local filename length is missing.".to_string())?
-        as u64;
-    let local_extra_length = little_endian_u16(&local_header, 28)
-        .ok_or_else(|| "The ZIP local extra length is missing.".to_string())?
-        as u64;
-    if local_filename_length != source.len() as u64 {
-        return Err("The ZIP local and central directory filenames do not match.".to_string());
-    }
-
-    let data_offset = local_header_end
-        .checked_add(local_filename_length)
-        .and_then(|offset| offset.checked_add(local_extra_length))
-        .ok_or_else(|| "The ZIP metadata data offset overflows the JAR.".to_string())?;
-    let data_end = data_offset
-        .checked_add(entry.compressed_size)
-        .ok_or_else(|| "The ZIP metadata data range overflows the JAR.".to_string())?;
-    if data_offset > archive.directory.offset || data_end > archive.directory.offset {
-        return Err("The ZIP metadata data crosses into the central directory.".to_string());
-    }
-
-    let mut local_filename = [0_u8; ZIP_TARGET_PATH_BUFFER_SIZE];
-    file.read_exact(&mut local_filename[..source.len()])
-        .map_err(|error| format!("Failed to read the ZIP local filename: {error}"))?;
-    if &local_filename[..source.len()] != source.as_bytes() {
-        return Err("The ZIP local and central directory filenames do not match.".to_string());
-    }
-
-    file.seek(SeekFrom::Start(data_offset))
-        .map_err(|error| format!("Failed to seek to ZIP metadata data: {error}"))?;
-    let bytes = match entry.compression_method {
-        0 => {
-            if entry.compressed_size != entry.uncompressed_size {
-                return Err(
-                    "A stored ZIP metadata entry has inconsistent compressed and uncompressed sizes."
-                        .to_string(),
-                );
-            }
-            let reader = (&mut *file).take(entry.compressed_size);
-            read_metadata_stream(reader, entry.uncompressed_size)?
-        }
-        8 => {
-            let reader = (&mut *file).take(entry.compressed_size);
-            read_metadata_stream(DeflateDecoder::new(reader), entry.uncompressed_size)?
-        }
-        _ => unreachable!(),
-    };
-
-    if bytes.len() as u64 > MAX_METADATA_FILE_SIZE {
-        return Err(format!(
-            "The decompressed entry exceeds the {MAX_METADATA_FILE_SIZE}-byte inspection limit."
-        ));
-    }
-
+    let bytes = read_archive_entry(
+        file,
+        &archive.directory,
+        source,
+        record,
+        MAX_METADATA_FILE_SIZE,
+        MAX_METADATA_COMPRESSED_SIZE,
+    )?;
     String::from_utf8(bytes)
         .map(Some)
         .map_err(|error| format!("The entry is not valid UTF-8: {error}"))
@@ -1723,85 +1627,526 @@ fn append_metadata_entries(
     metadata.entries.extend(accepted_entries);
 }
 
-fn parse_mod_metadata(path: &Path) -> LocalModMetadata {
-    let mut metadata = LocalModMetadata::default();
-    let mut file = match File::open(path) {
-        Ok(file) => file,
+/// Nested JAR paths declared by a Fabric/Quilt descriptor or Forge's jarjar metadata.
+fn declared_nested_jar_paths(source: &str, text: &str) -> Vec<String> {
+    let path_key = match source {
+        "fabric.mod.json" | "quilt.mod.json" => "file",
+        JARJAR_METADATA_PATH => "path",
+        _ => return Vec::new(),
+    };
+    let Ok(document) = serde_json::from_str::<JsonValue>(text) else {
+        return Vec::new();
+    };
+    let jars = match source {
+        "quilt.mod.json" => document
+            .get("quilt_loader")
+            .and_then(|quilt_loader| quilt_loader.get("jars")),
+        _ => document.get("jars"),
+    };
+
+    jars.and_then(JsonValue::as_array)
+        .into_iter()
+        .flatten()
+        .take(MAX_NESTED_JARS_PER_JAR + 1)
+        .filter_map(|jar| match jar {
+            JsonValue::Object(jar) => json_object_text(jar, path_key),
+            _ => json_value_text(jar),
+        })
+        .collect()
+}
+
+fn validate_nested_jar_path(path: &str) -> Result<(), String> {
+    validate_archive_path(path)?;
+    if !path.to_ascii_lowercase().ends_with(".jar") {
+        return Err("The path does not name a JAR.".to_string());
+    }
+    Ok(())
+}
+
+/// Shared limits for every nested JAR below one mod file.
+struct NestedJarBudget {
+    remaining_jars: usize,
+    remaining_bytes: u64,
+    /// What is left of `MAX_METADATA_SERIALIZED_BYTES_PER_JAR` for the whole tree.
+    remaining_serialized_bytes: usize,
+}
+
+impl NestedJarBudget {
+    fn new() -> Self {
+        Self {
+            remaining_jars: MAX_NESTED_JARS_PER_JAR,
+            remaining_bytes: MAX_NESTED_JAR_TOTAL_SIZE,
+            remaining_serialized_bytes: MAX_METADATA_SERIALIZED_BYTES_PER_JAR,
+        }
+    }
+}
+
+/// Serialized size of one archive's own entries and diagnostics, without its nested JARs.
+fn serialized_metadata_size(metadata: &LocalModMetadata) -> usize {
+    let entries = serde_json::to_vec(&metadata.entries).map_or(usize::MAX, |bytes| bytes.len());
+    let diagnostics =
+        serde_json::to_vec(&metadata.diagnostics).map_or(usize::MAX, |bytes| bytes.len());
+    entries.saturating_add(diagnostics)
+}
+
+fn inspect_nested_jars(
+    file: &mut (impl Read + Seek),
+    archive: &InspectedZipArchive,
+    mut paths: Vec<String>,
+    depth: usize,
+    budget: &mut NestedJarBudget,
+    metadata: &mut LocalModMetadata,
+) {
+    let mut seen = HashSet::new();
+    paths.retain(|path| seen.insert(path.clone()));
+    paths.retain(|path| match validate_nested_jar_path(path) {
+        Ok(()) => true,
         Err(error) => {
-            push_metadata_diagnostic(
-                &mut metadata,
-                format!("Failed to open the JAR for metadata inspection: {error}"),
-            );
-            return metadata;
+            push_metadata_diagnostic(metadata, format!("{path}: {error}"));
+            false
         }
-    };
-    let archive = match inspect_zip_archive(&mut file) {
-        Ok(archive) => archive,
+    });
+    if paths.is_empty() {
+        return;
+    }
+    if depth >= MAX_NESTED_JAR_DEPTH {
+        push_metadata_diagnostic(
+            metadata,
+            format!(
+                "{} nested JARs were not inspected beyond the nesting limit of {MAX_NESTED_JAR_DEPTH}.",
+                paths.len()
+            ),
+        );
+        return;
+    }
+
+    let path_refs: Vec<&str> = paths.iter().map(String::as_str).collect();
+    let records = match collect_central_directory_entries(file, &archive.directory, &path_refs) {
+        Ok(records) => records,
         Err(error) => {
             push_metadata_diagnostic(
-                &mut metadata,
-                format!("Metadata inspection was skipped: {error}"),
+                metadata,
+                format!("Nested JAR inspection was skipped: {error}"),
             );
-            return metadata;
+            return;
         }
     };
 
-    let jar_version = match read_archive_text(&mut file, &archive, MANIFEST_PATH) {
-        Ok(Some(manifest)) => manifest_implementation_version(&manifest),
-        Ok(None) => None,
-        Err(error) => {
-            push_metadata_diagnostic(&mut metadata, format!("{MANIFEST_PATH}: {error}"));
-            None
+    for (path, record) in paths.into_iter().zip(records) {
+        if budget.remaining_jars == 0 {
+            push_metadata_diagnostic(
+                metadata,
+                format!(
+                    "Nested JARs were skipped to keep the per-mod limit at {MAX_NESTED_JARS_PER_JAR}."
+                ),
+            );
+            break;
         }
-    };
-
-    let mut found_descriptor = false;
-    for source in MOD_DESCRIPTOR_PATHS {
-        let text = match read_archive_text(&mut file, &archive, source) {
-            Ok(Some(text)) => {
-                found_descriptor = true;
-                text
-            }
-            Ok(None) => continue,
+        if budget.remaining_serialized_bytes < NESTED_JAR_SERIALIZED_OVERHEAD + path.len() {
+            push_metadata_diagnostic(
+                metadata,
+                format!(
+                    "Nested JARs were skipped to keep the per-JAR serialized data limit at {MAX_METADATA_SERIALIZED_BYTES_PER_JAR} bytes."
+                ),
+            );
+            break;
+        }
+        let Some(record) = record else {
+            push_metadata_diagnostic(
+                metadata,
+                format!("{path}: the declared nested JAR is missing."),
+            );
+            continue;
+        };
+        budget.remaining_jars -= 1;
+
+        let size_limit = MAX_NESTED_JAR_SIZE.min(budget.remaining_bytes);
+        let bytes = match read_archive_entry(
+            file,
+            &archive.directory,
+            &path,
+            &record,
+            size_limit,
+            size_limit,
+        ) {
+            Ok(bytes) => bytes,
             Err(error) => {
-                found_descriptor = true;
-                push_metadata_diagnostic(&mut metadata, format!("{source}: {error}"));
+                push_metadata_diagnostic(metadata, format!("{path}: {error}"));
                 continue;
             }
         };
+        budget.remaining_bytes -= bytes.len() as u64;
+
+        let size = bytes.len() as u64;
+        budget.remaining_serialized_bytes -= NESTED_JAR_SERIALIZED_OVERHEAD + path.len();
+        let nested_metadata =
+            parse_archive_metadata(&mut io::Cursor::new(bytes), depth + 1, budget);
+        metadata.nested.push(LocalNestedMod {
+            path,
+            size,
+            metadata: nested_metadata,
+        });
+    }
+}
 
-        let parsed = match source {
-            "fabric.mod.json" => parse_fabric_metadata(&text, source),
-            "quilt.mod.json" => parse_quilt_metadata(&text, source),
-            "META-INF/mods.toml" => {
-                parse_forge_metadata(&text, source, "Forge / NeoForge", jar_version.as_deref())
-            }
-            "META-INF/neoforge.mods.toml" => {
-                parse_forge_metadata(&text, source, "NeoForge", jar_version.as_deref())
-            }
-            "mcmod.info" => parse_mcmod_metadata(&text, source),
-            _ => unreachable!(),
-        };
-
-        match parsed {
-            Ok(entries) if entries.is_empty() => push_metadata_diagnostic(
-                &mut metadata,
-                format!("{source}: no mod entries were found."),
-            ),
-            Ok(entries) => append_metadata_entries(&mut metadata, source, entries),
-            Err(error) => {
-                push_metadata_diagnostic(&mut metadata, format!("{source}: {error}"));
-            }
-        }
+fn icon_path_candidates(value: Option<&JsonValue>) -> Vec<(Option<u32>, String)> {
+    match value {
+        Some(JsonValue::Object(sizes)) => sizes
+            .iter()
+            .filter_map(|(size, path)| Some((Some(size.parse().ok()?), json_value_text(path)?)))
+            .collect(),
+        Some(value) => json_value_text(value)
+            .map(|path| vec![(None, path)])
+            .unwrap_or_default(),
+        None => Vec::new(),
     }
+}
 
-    if !found_descriptor {
-        push_metadata_diagnostic(
+/// Icon paths declared by one descriptor, with their pixel size when the descriptor says.
+fn declared_icon_paths(source: &str, text: &str) -> Vec<(Option<u32>, String)> {
+    let candidates = match source {
+        "fabric.mod.json" => serde_json::from_str::<JsonValue>(text)
+            .map(|document| icon_path_candidates(document.get("icon")))
+            .unwrap_or_default(),
+        "quilt.mod.json" => serde_json::from_str::<JsonValue>(text)
+            .map(|document| {
+                icon_path_candidates(
+                    document
+                        .get("quilt_loader")
+                        .and_then(|quilt_loader| quilt_loader.get("metadata"))
+                        .and_then(|metadata| metadata.get("icon")),
+                )
+            })
+            .unwrap_or_default(),
+        "META-INF/mods.toml" | "META-INF/neoforge.mods.toml" => text
+            .parse::<toml::Table>()
+            .map(|document| {
+                document
+                    .get("mods")
+                    .and_then(TomlValue::as_array)
+                    .into_iter()
+                    .flatten()
+                    .filter_map(TomlValue::as_table)
+                    .chain(std::iter::once(&document))
+                    .filter_map(|table| toml_object_text(table, "logoFile"))
+                    .map(|path| (None, path))
+                    .collect()
+            })
+            .unwrap_or_default(),
+        "mcmod.info" => serde_json::from_str::<JsonValue>(text)
+            .map(|document| {
+                let entries = match &document {
+                    JsonValue::Array(entries) => entries.clone(),
+                    JsonValue::Object(root) => match root.get("modList") {
+                        Some(JsonValue::Array(entries)) => entries.clone(),
+                        _ => vec![document.clone()],
+                    },
+                    _ => Vec::new(),
+                };
+                entries
+                    .iter()
+                    .filter_map(|entry| entry.get("logoFile").and_then(json_value_text))
+                    .map(|path| (None, path))
+                    .collect()
+            })
+            .unwrap_or_default(),
+        _ => Vec::new(),
+    };
+
+    candidates
+        .into_iter()
+        .map(|(size, path)| (size, path.trim_start_matches('/').to_string()))
+        .filter(|(_, path)| !path.is_empty())
+        .collect()
+}
+
+/// Prefer the declared size closest to `requested`, then the larger one.
+fn closest_icon_path(candidates: Vec<(Option<u32>, String)>, requested: u32) -> Option<String> {
+    candidates
+        .into_iter()
+        .min_by_key(|(size, _)| match size {
+            Some(size) => (size.abs_diff(requested), cmp::Reverse(*size)),
+            None => (u32::MAX, cmp::Reverse(0)),
+        })
+        .map(|(_, path)| path)
+}
+
+/// Read the PNG icon declared by the first descriptor that names one.
+fn read_mod_icon(file: &mut (impl Read + Seek), requested: u32) -> Result<Option<Vec<u8>>, String> {
+    let archive = inspect_zip_archive(file)?;
+    let mut icon_path = None;
+    for source in MOD_DESCRIPTOR_PATHS {
+        let Ok(Some(text)) = read_archive_text(file, &archive, source) else {
+            continue;
+        };
+        icon_path = closest_icon_path(declared_icon_paths(source, &text), requested);
+        if icon_path.is_some() {
+            break;
+        }
+    }
+    let Some(icon_path) = icon_path else {
+        return Ok(None);
+    };
+    validate_archive_path(&icon_path).map_err(|error| format!("{icon_path}: {error}"))?;
+
+    let Some(record) = collect_central_directory_entries(file, &archive.directory, &[&icon_path])?
+        .pop()
+        .flatten()
+    else {
+        return Ok(None);
+    };
+    let bytes = read_archive_entry(
+        file,
+        &archive.directory,
+        &icon_path,
+        &record,
+        MAX_MOD_ICON_SIZE,
+        MAX_MOD_ICON_SIZE,
+    )
+    .map_err(|error| format!("{icon_path}: {error}"))?;
+    // Old Forge mods often ship JPEG or GIF logos, which are treated as no icon.
+    Ok(bytes.starts_with(PNG_SIGNATURE).then_some(bytes))
+}
+
+/// Hash a mod file with SHA-1 and SHA-512 in a single pass.
+pub fn mod_file_hashes(path: &Path) -> Result<ModFileHashes, String> {
+    let mut file = File::open(path)
+        .map_err(|error| format!("Failed to open mod file '{}': {}", path.display(), error))?;
+    let mut hasher = ModFileHasher::default();
+    let mut buffer = [0_u8; 64 * 1024];
+    loop {
+        let read = file
+            .read(&mut buffer)
+            .map_err(|error| format!("Failed to hash mod file '{}': {}", path.display(), error))?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    Ok(hasher.finish())
+}
+
+/// Streaming MurmurHash2 (seed 1) over the bytes CurseForge keeps for fingerprints.
+struct CurseForgeFingerprint {
+    hash: u32,
+    pending: u32,
+    pending_len: u32,
+}
+
+impl CurseForgeFingerprint {
+    fn is_ignored_byte(byte: u8) -> bool {
+        matches!(byte, 9 | 10 | 13 | 32)
+    }
+
+    /// MurmurHash2 mixes the input length in first, so it must be counted up front.
+    fn new(normalized_len: u32) -> Self {
+        Self {
+            hash: 1 ^ normalized_len,
+            pending: 0,
+            pending_len: 0,
+        }
+    }
+
+    fn update(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            if Self::is_ignored_byte(byte) {
+                continue;
+            }
+            self.pending |= u32::from(byte) << (8 * self.pending_len);
+            self.pending_len += 1;
+            if self.pending_len == 4 {
+                let mut block = self.pending.wrapping_mul(MURMUR2_MULTIPLIER);
+                block ^= block >> 24;
+                block = block.wrapping_mul(MURMUR2_MULTIPLIER);
+                self.hash = self.hash.wrapping_mul(MURMUR2_MULTIPLIER) ^ block;
+                self.pending = 0;
+                self.pending_len = 0;
+            }
+        }
+    }
+
+    fn finish(self) -> u32 {
+        let mut hash = self.hash;
+        if self.pending_len > 0 {
+            hash = (hash ^ self.pending).wrapping_mul(MURMUR2_MULTIPLIER);
+        }
+        hash ^= hash >> 13;
+        hash = hash.wrapping_mul(MURMUR2_MULTIPLIER);
+        hash ^ (hash >> 15)
+    }
+}
+
+/// Compute a file's CurseForge fingerprint in two streaming passes.
+fn curseforge_fingerprint(file: &mut (impl Read + Seek)) -> Result<u32, String> {
+    let mut buffer = [0_u8; 64 * 1024];
+    let mut normalized_len: u32 = 0;
+    loop {
+        let read = file
+            .read(&mut buffer)
+            .map_err(|error| format!("Failed to fingerprint the JAR: {error}"))?;
+        if read == 0 {
+            break;
+        }
+        let kept = buffer[..read]
+            .iter()
+            .filter(|byte| !CurseForgeFingerprint::is_ignored_byte(**byte))
+            .count();
+        normalized_len = normalized_len.wrapping_add(kept as u32);
+    }
+
+    file.seek(SeekFrom::Start(0))
+        .map_err(|error| format!("Failed to fingerprint the JAR: {error}"))?;
+    let mut fingerprint = CurseForgeFingerprint::new(normalized_len);
+    loop {
+        let read = file
+            .read(&mut buffer)
+            .map_err(|error| format!("Failed to fingerprint the JAR: {error}"))?;
+        if read == 0 {
+            break;
+        }
+        fingerprint.update(&buffer[..read]);
+    }
+    Ok(fingerprint.finish())
+}
+
+/// Parsed metadata and CurseForge fingerprint of one mod file.
+type InspectedModFile = (LocalModMetadata, Option<u32>);
+
+/// Parse a mod's metadata and fingerprint it; an unreadable file has no fingerprint.
+fn inspect_mod_file(path: &Path) -> InspectedModFile {
+    let fingerprint = File::open(path)
+        .ok()
+        .and_then(|mut file| curseforge_fingerprint(&mut file).ok());
+    (parse_mod_metadata(path), fingerprint)
+}
+
+fn parse_mod_metadata(path: &Path) -> LocalModMetadata {
+    let mut metadata = LocalModMetadata::default();
+    let mut file = match File::open(path) {
+        Ok(file) => file,
+        Err(error) => {
+            push_metadata_diagnostic(
+                &mut metadata,
+                format!("Failed to open the JAR for metadata inspection: {error}"),
+            );
+            return metadata;
+        }
+    };
+    parse_archive_metadata(&mut file, 0, &mut NestedJarBudget::new())
+}
+
+/// Read the descriptors of a mod archive, following declared nested JARs below `depth`.
+fn parse_archive_metadata(
+    file: &mut (impl Read + Seek),
+    depth: usize,
+    budget: &mut NestedJarBudget,
+) -> LocalModMetadata {
+    let mut metadata = LocalModMetadata::default();
+    let archive = match inspect_zip_archive(file) {
+        Ok(archive) => archive,
+        Err(error) => {
+            push_metadata_diagnostic(
+                &mut metadata,
+                format!("Metadata inspection was skipped: {error}"),
+            );
+            return metadata;
+        }
+    };
+
+    let jar_version = match read_archive_text(file, &archive, MANIFEST_PATH) {
+        Ok(Some(manifest)) => manifest_implementation_version(&manifest),
+        Ok(None) => None,
+        Err(error) => {
+            push_metadata_diagnostic(&mut metadata, format!("{MANIFEST_PATH}: {error}"));
+            None
+        }
+    };
+
+    let mut found_descriptor = false;
+    let mut nested_jar_paths = Vec::new();
+    for source in MOD_DESCRIPTOR_PATHS {
+        let text = match read_archive_text(file, &archive, source) {
+            Ok(Some(text)) => {
+                found_descriptor = true;
+                text
+            }
+            Ok(None) => continue,
+            Err(error) => {
+                found_descriptor = true;
+                push_metadata_diagnostic(&mut metadata, format!("{source}: {error}"));
+                continue;
+            }
+        };
+
+        let parsed = match source {
+            "fabric.mod.json" => parse_fabric_metadata(&text, source),
+            "quilt.mod.json" => parse_quilt_metadata(&text, source),
+            "META-INF/mods.toml" => {
+                parse_forge_metadata(&text, source, "Forge / NeoForge", jar_version.as_deref())
+            }
+            "META-INF/neoforge.mods.toml" => {
+                parse_forge_metadata(&text, source, "NeoForge", jar_version.as_deref())
+            }
+            "mcmod.info" => parse_mcmod_metadata(&text, source),
+            _ => unreachable!(),
+        };
+        nested_jar_paths.extend(declared_nested_jar_paths(source, &text));
+
+        match parsed {
+            Ok(entries) if entries.is_empty() => push_metadata_diagnostic(
+                &mut metadata,
+                format!("{source}: no mod entries were found."),
+            ),
+            Ok(entries) => append_metadata_entries(&mut metadata, source, entries),
+            Err(error) => {
+                push_metadata_diagnostic(&mut metadata, format!("{source}: {error}"));
+            }
+        }
+    }
+
+    if !found_descriptor {
+        push_metadata_diagnostic(
             &mut metadata,
             "No supported mod metadata descriptor was found in the JAR.".to_string(),
         );
     }
 
+    match read_archive_text(file, &archive, JARJAR_METADATA_PATH) {
+        Ok(Some(text)) => {
+            nested_jar_paths.extend(declared_nested_jar_paths(JARJAR_METADATA_PATH, &text))
+        }
+        Ok(None) => {}
+        Err(error) => {
+            push_metadata_diagnostic(&mut metadata, format!("{JARJAR_METADATA_PATH}: {error}"))
+        }
+    }
+    // Nested metadata is charged to the same serialized limit as the outermost JAR.
+    let own_size = serialized_metadata_size(&metadata);
+    if depth > 0 && own_size > budget.remaining_serialized_bytes {
+        metadata.entries.clear();
+        metadata.diagnostics.clear();
+        push_metadata_diagnostic(
+            &mut metadata,
+            format!(
+                "Metadata was dropped to keep the per-JAR serialized data limit at {MAX_METADATA_SERIALIZED_BYTES_PER_JAR} bytes."
+            ),
+        );
+        budget.remaining_serialized_bytes = budget
+            .remaining_serialized_bytes
+            .saturating_sub(serialized_metadata_size(&metadata));
+        return metadata;
+    }
+    budget.remaining_serialized_bytes = budget.remaining_serialized_bytes.saturating_sub(own_size);
+
+    inspect_nested_jars(
+        file,
+        &archive,
+        nested_jar_paths,
+        depth,
+        budget,
+        &mut metadata,
+    );
+
     metadata
 }
 
@@ -1813,14 +2158,123 @@ fn local_mod_file(path: &Path, filename: String, enabled: bool) -> Result<LocalM
         return Err(format!("Mod path '{}' is not a file.", path.display()));
     }
 
+    let (mod_metadata, curseforge_fingerprint) = inspect_mod_file(path);
     Ok(LocalModFile {
         filename,
         enabled,
         size: metadata.len(),
-        metadata: parse_mod_metadata(path),
+        metadata: mod_metadata,
+        curseforge_fingerprint,
+        modrinth: None,
+        curseforge: None,
+        compatibility: None,
+        side_warnings: Vec::new(),
+    })
+}
+
+fn mod_metadata_cache_path(game_directory: &str) -> Result<PathBuf, String> {
+    Ok(game_directory_path(game_directory)?
+        .join(LAUNCHER_DIRECTORY_NAME)
+        .join(MOD_METADATA_CACHE_FILENAME))
+}
+
+/// Load the scan cache; a missing, oversized, corrupt or outdated cache is treated as empty.
+fn read_mod_metadata_cache(path: &Path) -> ModMetadataCache {
+    let cache = fs::metadata(path)
+        .ok()
+        .filter(|metadata| metadata.is_file() && metadata.len() <= MAX_MOD_METADATA_CACHE_SIZE)
+        .and_then(|_| fs::read(path).ok())
+        .and_then(|bytes| serde_json::from_slice::<ModMetadataCache>(&bytes).ok());
+    match cache {
+        Some(cache) if cache.version == MOD_METADATA_CACHE_VERSION => cache,
+        _ => ModMetadataCache {
+            version: MOD_METADATA_CACHE_VERSION,
+            entries: BTreeMap::new(),
+        },
+    }
+}
+
+/// The cache entry for a file whose size and mtime still match. A toggle renames the file but
+/// keeps both, so the counterpart's entry stays valid until the next scan drops it.
+fn fresh_mod_metadata_cache_entry<'a>(
+    cache: &'a ModMetadataCache,
+    filename: &str,
+    size: u64,
+    modified_ns: Option<u64>,
+) -> Option<&'a ModMetadataCacheEntry> {
+    let counterpart = if is_enabled_mod_filename(filename) {
+        Some(format!("{filename}{DISABLED_SUFFIX}"))
+    } else {
+        enabled_filename(filename)
+    };
+    let is_fresh = |cached: &&ModMetadataCacheEntry| {
+        cached.size == size && Some(cached.modified_ns) == modified_ns
+    };
+    cache.entries.get(filename).filter(is_fresh).or_else(|| {
+        counterpart
+            .and_then(|counterpart| cache.entries.get(&counterpart))
+            .filter(is_fresh)
     })
 }
 
+/// Metadata of every installed mod file, taken from the scan cache where it is still fresh.
+fn installed_mod_metadata(game_directory: &str) -> Result<Vec<(String, LocalModMetadata)>, String> {
+    let mods_directory = mods_directory(game_directory)?;
+    let entries = fs::read_dir(&mods_directory).map_err(|error| {
+        format!(
+            "Failed to read mods directory '{}': {}",
+            mods_directory.display(),
+            error
+        )
+    })?;
+    let cache = read_mod_metadata_cache(&mod_metadata_cache_path(game_directory)?);
+    let mut installed = Vec::new();
+    for entry in entries {
+        let entry = entry.map_err(|error| {
+            format!(
+                "Failed to read an entry in mods directory '{}': {}",
+                mods_directory.display(),
+                error
+            )
+        })?;
+        let Ok(filename) = entry.file_name().into_string() else {
+            continue;
+        };
+        if !is_mod_filename(&filename) || is_launcher_temp_filename(&filename) {
+            continue;
+        }
+        let path = entry.path();
+        let Ok(file_metadata) = fs::symlink_metadata(&path) else {
+            continue;
+        };
+        if !file_metadata.is_file() {
+            continue;
+        }
+        let metadata = fresh_mod_metadata_cache_entry(
+            &cache,
+            &filename,
+            file_metadata.len(),
+            file_modified_ns(&file_metadata),
+        )
+        .map(|cached| cached.metadata.clone())
+        .unwrap_or_else(|| parse_mod_metadata(&path));
+        installed.push((filename, metadata));
+    }
+    Ok(installed)
+}
+
+fn write_mod_metadata_cache(path: &Path, cache: &ModMetadataCache) -> Result<(), String> {
+    if let Some(directory) = path.parent() {
+        create_launcher_directory(directory)?;
+    }
+    write_json_atomically(path, cache)
+}
+
+fn file_modified_ns(metadata: &fs::Metadata) -> Option<u64> {
+    let modified = metadata.modified().ok()?.duration_since(UNIX_EPOCH).ok()?;
+    u64::try_from(modified.as_nanos()).ok()
+}
+
 fn import_mod(mods_directory: &Path, source_path: &str) -> Result<String, String> {
     if source_path.trim().is_empty() {
         return Err("Source path cannot be empty.".to_string());
@@ -1839,6 +2293,19 @@ fn import_mod(mods_directory: &Path, source_path: &str) -> Result<String, String
         return Err("Source path is not a regular file.".to_string());
     }
 
+    let mut source_file =
+        File::open(source).map_err(|error| format!("Failed to open source file: {}", error))?;
+    install_imported_mod(mods_directory, filename, &mut source_file)?;
+
+    Ok(filename.to_string())
+}
+
+/// Copy one validated mod into the mods directory without replacing an installed one.
+fn install_imported_mod(
+    mods_directory: &Path,
+    filename: &str,
+    source: &mut impl Read,
+) -> Result<(), String> {
     let destination = mods_directory.join(filename);
     let disabled_destination = mods_directory.join(format!("{filename}{DISABLED_SUFFIX}"));
     if path_exists(&destination)? || path_exists(&disabled_destination)? {
@@ -1847,11 +2314,9 @@ fn import_mod(mods_directory: &Path, source_path: &str) -> Result<String, String
         ));
     }
 
-    let mut source_file =
-        File::open(source).map_err(|error| format!("Failed to open source file: {}", error))?;
     let (temp_path, mut temp_file) = create_import_temp_file(mods_directory)?;
 
-    if let Err(error) = io::copy(&mut source_file, &mut temp_file) {
+    if let Err(error) = io::copy(source, &mut temp_file) {
         drop(temp_file);
         return Err(cleanup_import_failure(
             &temp_path,
@@ -1868,7 +2333,6 @@ fn import_mod(mods_directory: &Path, source_path: &str) -> Result<String, String
     }
 
     drop(temp_file);
-    drop(source_file);
 
     match path_exists(&disabled_destination) {
         Ok(false) => {}
@@ -1881,54 +2345,589 @@ fn import_mod(mods_directory: &Path, source_path: &str) -> Result<String, String
         Err(error) => return Err(cleanup_import_failure(&temp_path, error)),
     }
 
-    publish_import_temp(&temp_path, &destination, filename)?;
+    publish_import_temp(&temp_path, &destination, filename)
+}
 
-    Ok(filename.to_string())
+fn is_mod_bundle_archive_filename(filename: &str) -> bool {
+    filename.to_ascii_lowercase().ends_with(".zip")
 }
 
-fn scan_local_mods_blocking(game_directory: &str) -> Result<Vec<LocalModFile>, String> {
-    let mods_directory = mods_directory(game_directory)?;
-    create_mods_directory(&mods_directory)?;
+/// Whether a bundle entry is a mod JAR rather than a folder or archiver metadata.
+fn is_bundled_mod_path(path: &str) -> bool {
+    !path.ends_with('/')
+        && is_enabled_mod_filename(path)
+        && !path
+            .split('/')
+            .any(|component| component == "__MACOSX" || component.starts_with("._"))
+}
 
-    let entries = fs::read_dir(&mods_directory).map_err(|error| {
-        format!(
-            "Failed to read mods directory '{}': {}",
-            mods_directory.display(),
-            error
-        )
-    })?;
-    let mut mods = Vec::new();
+/// Import every JAR inside a ZIP bundle, reporting each entry as `bundle.zip/path/mod.jar`.
+fn import_mod_zip_bundle(
+    mods_directory: &Path,
+    source: &Path,
+    display_name: &str,
+    result: &mut LocalModImportResult,
+) -> Result<(), String> {
+    let mut file =
+        File::open(source).map_err(|error| format!("Failed to open the ZIP bundle: {error}"))?;
+    let summary = checked_zip_directory_summary(&mut file)?;
+    let mut paths = Vec::new();
+    let mut unreadable = Vec::new();
+    for name in collect_central_directory_names(&mut file, &summary)? {
+        match name {
+            Ok(path) if is_bundled_mod_path(&path) => paths.push(path),
+            Ok(_) => {}
+            Err(reason) => unreadable.push(reason),
+        }
+    }
+    if paths.is_empty() && unreadable.is_empty() {
+        return Err("The ZIP bundle does not contain any '.jar' files.".to_string());
+    }
+    if paths.len() > MAX_BUNDLE_IMPORT_ENTRIES {
+        return Err(format!(
+            "The ZIP bundle contains {} JARs; the import limit is {MAX_BUNDLE_IMPORT_ENTRIES}.",
+            paths.len()
+        ));
+    }
 
-    for entry in entries {
-        let entry = entry.map_err(|error| {
-            format!(
-                "Failed to read an entry in mods directory '{}': {}",
-                mods_directory.display(),
-                error
-            )
-        })?;
-        let filename = match entry.file_name().into_string() {
-            Ok(filename) => filename,
-            Err(_) => continue,
-        };
+    // Names that cannot be paths might hide a JAR, so they are reported rather than skipped.
+    result
+        .failed
+        .extend(unreadable.into_iter().map(|reason| LocalModImportFailure {
+            filename: display_name.to_string(),
+            reason,
+        }));
 
-        if !is_mod_filename(&filename) {
-            continue;
-        }
+    let path_refs: Vec<&str> = paths.iter().map(String::as_str).collect();
+    let records = collect_central_directory_entries(&mut file, &summary, &path_refs)?;
+
+    for (path, record) in paths.iter().zip(records) {
+        let filename = path.rsplit('/').next().unwrap_or(path);
+        let imported = validate_archive_path(path)
+            .and_then(|()| validate_import_filename(filename))
+            .and_then(|()| {
+                let record = record
+                    .ok_or_else(|| "The entry disappeared from the ZIP directory.".to_string())?;
+                read_archive_entry(
+                    &mut file,
+                    &summary,
+                    path,
+                    &record,
+                    MAX_BUNDLED_MOD_SIZE,
+                    MAX_BUNDLED_MOD_SIZE,
+                )
+            })
+            .and_then(|bytes| {
+                preflight_zip_archive(&mut io::Cursor::new(bytes.as_slice()))
+                    .map_err(|error| format!("The bundled file is not a valid JAR: {error}"))?;
+                install_imported_mod(mods_directory, filename, &mut bytes.as_slice())
+            });
 
-        let file_type = entry
-            .file_type()
-            .map_err(|error| format!("Failed to inspect mod file '{filename}': {error}"))?;
-        if !file_type.is_file() {
-            continue;
+        match imported {
+            Ok(()) => result.imported.push(filename.to_string()),
+            Err(reason) => result.failed.push(LocalModImportFailure {
+                filename: format!("{display_name}/{path}"),
+                reason,
+            }),
         }
-
-        let enabled = is_enabled_mod_filename(&filename);
-        mods.push(local_mod_file(&entry.path(), filename, enabled)?);
     }
 
-    mods.sort_by_cached_key(|local_mod| local_mod.filename.to_ascii_lowercase());
-    Ok(mods)
+    Ok(())
+}
+
+/// Collect JARs below a folder bundle, skipping symlinks and anything deeper than the limit.
+fn collect_bundle_folder_jars(
+    directory: &Path,
+    relative: &str,
+    depth: usize,
+    jars: &mut Vec<(String, PathBuf)>,
+) -> Result<(), String> {
+    let mut entries = fs::read_dir(directory)
+        .map_err(|error| format!("Failed to read folder '{}': {error}", directory.display()))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|error| format!("Failed to read folder '{}': {error}", directory.display()))?;
+    entries.sort_by_key(|entry| entry.file_name());
+
+    for entry in entries {
+        let Ok(name) = entry.file_name().into_string() else {
+            continue;
+        };
+        let file_type = entry
+            .file_type()
+            .map_err(|error| format!("Failed to inspect '{name}': {error}"))?;
+        let path = if relative.is_empty() {
+            name.clone()
+        } else {
+            format!("{relative}/{name}")
+        };
+
+        if file_type.is_dir() && depth < MAX_BUNDLE_FOLDER_DEPTH {
+            collect_bundle_folder_jars(&entry.path(), &path, depth + 1, jars)?;
+        } else if file_type.is_file() && is_bundled_mod_path(&path) {
+            if jars.len() >= MAX_BUNDLE_IMPORT_ENTRIES {
+                return Err(format!(
+                    "The folder contains more than {MAX_BUNDLE_IMPORT_ENTRIES} JARs."
+                ));
+            }
+            jars.push((path, entry.path()));
+        }
+    }
+
+    Ok(())
+}
+
+/// Import every JAR below a folder, reporting each entry as `folder/path/mod.jar`.
+fn import_mod_folder_bundle(
+    mods_directory: &Path,
+    source: &Path,
+    display_name: &str,
+    result: &mut LocalModImportResult,
+) -> Result<(), String> {
+    let mut jars = Vec::new();
+    collect_bundle_folder_jars(source, "", 0, &mut jars)?;
+    if jars.is_empty() {
+        return Err("The folder does not contain any '.jar' files.".to_string());
+    }
+
+    for (path, jar_path) in jars {
+        let imported = File::open(&jar_path)
+            .map_err(|error| format!("Failed to open source file: {error}"))
+            .and_then(|mut file| {
+                preflight_zip_archive(&mut file)
+                    .map_err(|error| format!("The bundled file is not a valid JAR: {error}"))
+            })
+            .and_then(|()| import_mod(mods_directory, &jar_path.to_string_lossy()));
+        match imported {
+            Ok(filename) => result.imported.push(filename),
+            Err(reason) => result.failed.push(LocalModImportFailure {
+                filename: format!("{display_name}/{path}"),
+                reason,
+            }),
+        }
+    }
+
+    Ok(())
+}
+
+/// Stream a download into a fresh import temp file, hashing it on the way.
+async fn download_to_import_temp(
+    url: &str,
+    mods_directory: &Path,
+) -> Result<(PathBuf, ModFileHashes), String> {
+    let (temp_path, temp_file) = create_import_temp_file(mods_directory)?;
+    let mut temp_file = tokio::fs::File::from_std(temp_file);
+    let download = async {
+        let mut response = get_http_client()
+            .get(url)
+            .send()
+            .await
+            .map_err(|error| format!("Failed to download '{url}': {error}"))?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(format!("Downloading '{url}' failed with HTTP {status}."));
+        }
+        if response
+            .content_length()
+            .is_some_and(|length| length > MAX_MOD_DOWNLOAD_SIZE)
+        {
+            return Err(format!(
+                "The download exceeds the {MAX_MOD_DOWNLOAD_SIZE}-byte mod size limit."
+            ));
+        }
+
+        let mut hasher = ModFileHasher::default();
+        let mut downloaded: u64 = 0;
+        while let Some(chunk) = response
+            .chunk()
+            .await
+            .map_err(|error| format!("Failed to download '{url}': {error}"))?
+        {
+            downloaded += chunk.len() as u64;
+            if downloaded > MAX_MOD_DOWNLOAD_SIZE {
+                return Err(format!(
+                    "The download exceeds the {MAX_MOD_DOWNLOAD_SIZE}-byte mod size limit."
+                ));
+            }
+            hasher.update(&chunk);
+            temp_file
+                .write_all(&chunk)
+                .await
+                .map_err(|error| format!("Failed to write the downloaded mod: {error}"))?;
+        }
+        temp_file
+            .sync_all()
+            .await
+            .map_err(|error| format!("Failed to finish writing the downloaded mod: {error}"))?;
+        Ok(hasher.finish())
+    }
+    .await;
+    drop(temp_file);
+
+    match download {
+        Ok(hashes) => Ok((temp_path, hashes)),
+        Err(reason) => Err(cleanup_import_failure(&temp_path, reason)),
+    }
+}
+
+/// Downloads are only published after a hash check, so one of the hashes is required.
+fn validate_download_hashes(download: &LocalModDownload) -> Result<(), String> {
+    let has_hash = |hash: Option<&str>| hash.is_some_and(|hash| !hash.trim().is_empty());
+    if has_hash(download.sha1.as_deref()) || has_hash(download.sha512.as_deref()) {
+        Ok(())
+    } else {
+        Err("The download needs a SHA-1 or SHA-512 hash to be verified.".to_string())
+    }
+}
+
+fn verify_download_hash(
+    algorithm: &str,
+    expected: Option<&str>,
+    actual: &str,
+) -> Result<(), String> {
+    match expected.map(str::trim) {
+        Some(expected) if !expected.eq_ignore_ascii_case(actual) => Err(format!(
+            "The downloaded file's {algorithm} is {actual}, but {expected} was expected."
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Installed jars declaring one of `mod_ids`, or an error if any of them is newer than `version`.
+fn older_mods_to_replace(
+    installed: &[(String, LocalModMetadata)],
+    mod_ids: &HashSet<String>,
+    version: Option<&str>,
+) -> Result<Vec<String>, String> {
+    let mut replaced = Vec::new();
+    for (filename, metadata) in installed {
+        let Some(entry) = metadata.entries.iter().find(|entry| {
+            entry
+                .mod_id
+                .as_deref()
+                .is_some_and(|mod_id| mod_ids.contains(&mod_id.to_ascii_lowercase()))
+        }) else {
+            continue;
+        };
+        let installed_version = entry.version.as_deref();
+        if let (Some(installed_version), Some(version)) = (installed_version, version) {
+            if compare_versions(installed_version, version) == Some(cmp::Ordering::Greater) {
+                return Err(format!(
+                    "'{filename}' already provides a newer version ({installed_version}) of this mod."
+                ));
+            }
+        }
+        replaced.push(filename.clone());
+    }
+    Ok(replaced)
+}
+
+fn move_trashed_mods_back(
+    mods_directory: &Path,
+    trash_directory: &Path,
+    trashed: &[TrashedLocalMod],
+    reason: String,
+) -> String {
+    let mut reason = reason;
+    for trashed in trashed {
+        if let Err(error) = rename_without_overwrite(
+            &trash_directory.join(&trashed.id),
+            &mods_directory.join(&trashed.filename),
+        ) {
+            reason.push_str(&format!(
+                " Also failed to move '{}' back out of the mod trash: {error}",
+                trashed.id
+            ));
+        }
+    }
+    reason
+}
+
+/// Verify a downloaded temp file and publish it, trashing older jars of the same mod first.
+fn install_downloaded_mod(
+    game_directory: &str,
+    temp_path: &Path,
+    download: &LocalModDownload,
+    hashes: &ModFileHashes,
+) -> Result<LocalModInstallResult, String> {
+    let mods_directory = mods_directory(game_directory)?;
+    let prepared = (|| {
+        verify_download_hash("SHA-1", download.sha1.as_deref(), &hashes.sha1)?;
+        verify_download_hash("SHA-512", download.sha512.as_deref(), &hashes.sha512)?;
+        let mut file = File::open(temp_path)
+            .map_err(|error| format!("Failed to open the downloaded mod: {error}"))?;
+        preflight_zip_archive(&mut file)
+            .map_err(|error| format!("The downloaded file is not a valid JAR: {error}"))?;
+        drop(file);
+
+        let metadata = parse_mod_metadata(temp_path);
+        let mod_ids: HashSet<String> = metadata
+            .entries
+            .iter()
+            .filter_map(|entry| entry.mod_id.as_deref())
+            .map(str::to_ascii_lowercase)
+            .collect();
+        if !download.replace_older || mod_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        let version = metadata
+            .entries
+            .iter()
+            .find_map(|entry| entry.version.as_deref());
+        older_mods_to_replace(&installed_mod_metadata(game_directory)?, &mod_ids, version)
+    })();
+    let replaced_filenames =
+        prepared.map_err(|reason| cleanup_import_failure(temp_path, reason))?;
+
+    let filename = download.filename.as_str();
+    let destination = mods_directory.join(filename);
+    let disabled_filename = format!("{filename}{DISABLED_SUFFIX}");
+    for occupied in [filename, disabled_filename.as_str()] {
+        let is_replaced = replaced_filenames
+            .iter()
+            .any(|replaced| replaced == occupied);
+        match path_exists(&mods_directory.join(occupied)) {
+            Ok(true) if !is_replaced => {
+                return Err(cleanup_import_failure(
+                    temp_path,
+                    format!("A mod named '{filename}' is already installed or disabled."),
+                ));
+            }
+            Ok(_) => {}
+            Err(error) => return Err(cleanup_import_failure(temp_path, error)),
+        }
+    }
+
+    let mut replaced = Vec::new();
+    if !replaced_filenames.is_empty() {
+        let trash_directory = mod_trash_directory(game_directory)?;
+        let trashed = (|| {
+            create_launcher_directory(&trash_directory)?;
+            let mut manifest = read_mod_trash_manifest(&trash_directory)?;
+            for replaced_filename in &replaced_filenames {
+                replaced.push(move_mod_to_trash(
+                    &mods_directory,
+                    &trash_directory,
+                    replaced_filename,
+                )?);
+            }
+            manifest.entries.extend(replaced.iter().cloned());
+            write_mod_trash_manifest(&trash_directory, &manifest)
+        })();
+        if let Err(reason) = trashed {
+            let reason =
+                move_trashed_mods_back(&mods_directory, &trash_directory, &replaced, reason);
+            return Err(cleanup_import_failure(temp_path, reason));
+        }
+
+        if let Err(reason) = publish_import_temp(temp_path, &destination, filename) {
+            let mut reason =
+                move_trashed_mods_back(&mods_directory, &trash_directory, &replaced, reason);
+            let restored = read_mod_trash_manifest(&trash_directory).and_then(|mut manifest| {
+                manifest
+                    .entries
+                    .retain(|entry| !replaced.iter().any(|trashed| trashed.id == entry.id));
+                write_mod_trash_manifest(&trash_directory, &manifest)
+            });
+            if let Err(error) = restored {
+                reason.push_str(&format!(" Also failed to update the mod trash: {error}"));
+            }
+            return Err(reason);
+        }
+    } else {
+        publish_import_temp(temp_path, &destination, filename)?;
+    }
+
+    Ok(LocalModInstallResult {
+        installed: local_mod_file(&destination, filename.to_string(), true)?,
+        replaced,
+    })
+}
+
+pub fn scan_local_mods_blocking(game_directory: &str) -> Result<Vec<LocalModFile>, String> {
+    scan_local_mods_with_progress(game_directory, &AtomicBool::new(false), &|_, _, _| {})
+}
+
+/// Parse metadata and fingerprints for jobs on a bounded pool, stopping early once `cancelled` is set.
+fn parse_mod_scan_jobs(
+    jobs: &[ModScanJob],
+    cancelled: &AtomicBool,
+    on_parsed: &(dyn Fn(&ModScanJob) + Sync),
+) -> Vec<Option<InspectedModFile>> {
+    let worker_count = std::thread::available_parallelism()
+        .map(|count| count.get())
+        .unwrap_or(1)
+        .min(MAX_MOD_SCAN_WORKERS)
+        .min(jobs.len());
+    let next_job = AtomicUsize::new(0);
+    let results: Vec<std::sync::Mutex<Option<InspectedModFile>>> =
+        jobs.iter().map(|_| std::sync::Mutex::new(None)).collect();
+
+    std::thread::scope(|scope| {
+        for _ in 0..worker_count {
+            scope.spawn(|| {
+                while !cancelled.load(Ordering::Relaxed) {
+                    let index = next_job.fetch_add(1, Ordering::Relaxed);
+                    let Some(job) = jobs.get(index) else {
+                        break;
+                    };
+                    let inspected = inspect_mod_file(&job.path);
+                    *results[index]
+                        .lock()
+                        .unwrap_or_else(|error| error.into_inner()) = Some(inspected);
+                    on_parsed(job);
+                }
+            });
+        }
+    });
+
+    results
+        .into_iter()
+        .map(|result| {
+            result
+                .into_inner()
+                .unwrap_or_else(|error| error.into_inner())
+        })
+        .collect()
+}
+
+/// Scan the mods directory, reporting `(parsed, total, filename)` after each JAR.
+fn scan_local_mods_with_progress(
+    game_directory: &str,
+    cancelled: &AtomicBool,
+    on_progress: &(dyn Fn(usize, usize, &str) + Sync),
+) -> Result<Vec<LocalModFile>, String> {
+    let mods_directory = mods_directory(game_directory)?;
+    create_mods_directory(&mods_directory)?;
+
+    let entries = fs::read_dir(&mods_directory).map_err(|error| {
+        format!(
+            "Failed to read mods directory '{}': {}",
+            mods_directory.display(),
+            error
+        )
+    })?;
+    let cache_path = mod_metadata_cache_path(game_directory)?;
+    let previous_cache = read_mod_metadata_cache(&cache_path);
+    let mut next_cache = ModMetadataCache {
+        version: MOD_METADATA_CACHE_VERSION,
+        entries: BTreeMap::new(),
+    };
+    let mut mods = Vec::new();
+    let mut jobs = Vec::new();
+
+    for entry in entries {
+        let entry = entry.map_err(|error| {
+            format!(
+                "Failed to read an entry in mods directory '{}': {}",
+                mods_directory.display(),
+                error
+            )
+        })?;
+        let filename = match entry.file_name().into_string() {
+            Ok(filename) => filename,
+            Err(_) => continue,
+        };
+
+        if !is_mod_filename(&filename) {
+            continue;
+        }
+
+        let file_type = entry
+            .file_type()
+            .map_err(|error| format!("Failed to inspect mod file '{filename}': {error}"))?;
+        if !file_type.is_file() {
+            continue;
+        }
+
+        let path = entry.path();
+        let file_metadata = fs::symlink_metadata(&path)
+            .map_err(|error| format!("Failed to read mod file '{}': {}", path.display(), error))?;
+        if !file_metadata.is_file() {
+            return Err(format!("Mod path '{}' is not a file.", path.display()));
+        }
+        let size = file_metadata.len();
+        let modified_ns = file_modified_ns(&file_metadata);
+        let enabled = is_enabled_mod_filename(&filename);
+        let cached =
+            fresh_mod_metadata_cache_entry(&previous_cache, &filename, size, modified_ns).cloned();
+
+        match cached {
+            Some(cached) => {
+                mods.push(LocalModFile {
+                    filename: filename.clone(),
+                    enabled,
+                    size,
+                    metadata: cached.metadata.clone(),
+                    curseforge_fingerprint: cached.curseforge_fingerprint,
+                    modrinth: None,
+                    curseforge: None,
+                    compatibility: None,
+                    side_warnings: Vec::new(),
+                });
+                next_cache.entries.insert(filename, cached);
+            }
+            None => jobs.push(ModScanJob {
+                path,
+                filename,
+                enabled,
+                size,
+                modified_ns,
+            }),
+        }
+    }
+
+    let total = mods.len() + jobs.len();
+    let parsed = AtomicUsize::new(mods.len());
+    for (index, local_mod) in mods.iter().enumerate() {
+        on_progress(index + 1, total, &local_mod.filename);
+    }
+    let results = parse_mod_scan_jobs(&jobs, cancelled, &|job| {
+        on_progress(
+            parsed.fetch_add(1, Ordering::Relaxed) + 1,
+            total,
+            &job.filename,
+        );
+    });
+    if cancelled.load(Ordering::Relaxed) {
+        return Err("The mod scan was cancelled.".to_string());
+    }
+
+    let cache_changed =
+        !jobs.is_empty() || previous_cache.entries.keys().ne(next_cache.entries.keys());
+    for (job, result) in jobs.into_iter().zip(results) {
+        let (metadata, curseforge_fingerprint) =
+            result.ok_or_else(|| "The mod scan stopped early.".to_string())?;
+        if let Some(modified_ns) = job.modified_ns {
+            next_cache.entries.insert(
+                job.filename.clone(),
+                ModMetadataCacheEntry {
+                    size: job.size,
+                    modified_ns,
+                    metadata: metadata.clone(),
+                    curseforge_fingerprint,
+                },
+            );
+        }
+        mods.push(LocalModFile {
+            filename: job.filename,
+            enabled: job.enabled,
+            size: job.size,
+            metadata,
+            curseforge_fingerprint,
+            modrinth: None,
+            curseforge: None,
+            compatibility: None,
+            side_warnings: Vec::new(),
+        });
+    }
+
+    if cache_changed {
+        // The cache only saves time; a read-only launcher directory must not fail the scan.
+        let _ = write_mod_metadata_cache(&cache_path, &next_cache);
+    }
+
+    mods.sort_by_cached_key(|local_mod| local_mod.filename.to_ascii_lowercase());
+    Ok(mods)
 }
 
 fn import_local_mods_blocking(
@@ -1949,8 +2948,34 @@ fn import_local_mods_blocking(
             .map(|filename| filename.to_string_lossy().into_owned())
             .unwrap_or_else(|| source_path.clone());
 
-        match import_mod(&mods_directory, &source_path) {
-            Ok(filename) => result.imported.push(filename),
+        let source = Path::new(&source_path);
+        let bundle = if source_path.trim().is_empty() {
+            None
+        } else if source.is_dir() {
+            Some(import_mod_folder_bundle(
+                &mods_directory,
+                source,
+                &display_filename,
+                &mut result,
+            ))
+        } else if is_mod_bundle_archive_filename(&display_filename) {
+            Some(import_mod_zip_bundle(
+                &mods_directory,
+                source,
+                &display_filename,
+                &mut result,
+            ))
+        } else {
+            None
+        };
+
+        let imported = match bundle {
+            Some(bundle) => bundle.map(|()| None),
+            None => import_mod(&mods_directory, &source_path).map(Some),
+        };
+        match imported {
+            Ok(Some(filename)) => result.imported.push(filename),
+            Ok(None) => {}
             Err(reason) => result.failed.push(LocalModImportFailure {
                 filename: display_filename,
                 reason,
@@ -2020,59 +3045,2017 @@ fn set_local_mod_enabled_blocking(
     Ok(current_mod)
 }
 
-#[tauri::command]
-pub async fn scan_local_mods(game_directory: String) -> Result<Vec<LocalModFile>, String> {
-    let operation_guard = MODS_OPERATION_LOCK.lock().await;
-    tokio::task::spawn_blocking(move || {
-        let _operation_guard = operation_guard;
-        scan_local_mods_blocking(&game_directory)
-    })
-    .await
-    .map_err(|error| format!("Local mod scan task failed: {error}"))?
-}
+/// Enable or disable several mods as one transaction, validating all of them before renaming any.
+fn set_local_mods_enabled_blocking(
+    game_directory: &str,
+    filenames: Vec<String>,
+    enabled: bool,
+) -> Result<LocalModStateBatchResult, String> {
+    let mods_directory = mods_directory(game_directory)?;
+    if !ensure_existing_mods_directory(&mods_directory)? {
+        return Err(format!(
+            "Mods directory '{}' does not exist.",
+            mods_directory.display()
+        ));
+    }
 
-#[tauri::command]
-pub async fn import_local_mods(
-    game_directory: String,
-    source_paths: Vec<String>,
-) -> Result<LocalModImportResult, String> {
-    let operation_guard = MODS_OPERATION_LOCK.lock().await;
-    tokio::task::spawn_blocking(move || {
-        let _operation_guard = operation_guard;
-        import_local_mods_blocking(&game_directory, source_paths)
-    })
-    .await
-    .map_err(|error| format!("Local mod import task failed: {error}"))?
-}
+    let mut result = LocalModStateBatchResult {
+        changed: Vec::new(),
+        unchanged: Vec::new(),
+        failed: Vec::new(),
+    };
+    let mut seen = HashSet::new();
+    let mut pending: Vec<(String, LocalModFile)> = Vec::new();
+    for filename in filenames {
+        let prepared = (|| {
+            validate_mod_filename(&filename)?;
+            let identity = enabled_filename(&filename).unwrap_or_else(|| filename.clone());
+            if !seen.insert(identity) {
+                return Err("The mod is listed more than once.".to_string());
+            }
+            let currently_enabled = is_enabled_mod_filename(&filename);
+            let source = mods_directory.join(&filename);
+            if !path_exists(&source)? {
+                return Err("The mod is not installed.".to_string());
+            }
+            if currently_enabled == enabled {
+                return Ok(None);
+            }
+            let destination_filename = if enabled {
+                enabled_filename(&filename)
+                    .ok_or_else(|| "Disabled mod filename is invalid.".to_string())?
+            } else {
+                format!("{filename}{DISABLED_SUFFIX}")
+            };
+            if path_exists(&mods_directory.join(&destination_filename))? {
+                return Err(format!(
+                    "Cannot change mod state because '{destination_filename}' already exists."
+                ));
+            }
+            let file = local_mod_file(&source, destination_filename, enabled)?;
+            Ok(Some(file))
+        })();
+        match prepared {
+            Ok(Some(file)) => pending.push((filename, file)),
+            Ok(None) => result.unchanged.push(filename),
+            Err(reason) => result
+                .failed
+                .push(LocalModOperationFailure { filename, reason }),
+        }
+    }
 
-#[tauri::command]
-pub async fn set_local_mod_enabled(
-    game_directory: String,
-    filename: String,
-    enabled: bool,
-) -> Result<LocalModFile, String> {
-    let operation_guard = MODS_OPERATION_LOCK.lock().await;
-    tokio::task::spawn_blocking(move || {
-        let _operation_guard = operation_guard;
-        set_local_mod_enabled_blocking(&game_directory, filename, enabled)
-    })
-    .await
-    .map_err(|error| format!("Local mod state task failed: {error}"))?
-}
+    if !result.failed.is_empty() {
+        for (filename, _) in pending {
+            result.failed.push(LocalModOperationFailure {
+                filename,
+                reason: "Not changed because another mod in the batch failed.".to_string(),
+            });
+        }
+        return Ok(result);
+    }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use std::io::Write;
-    use zip::write::SimpleFileOptions;
+    let renames: Vec<(String, String)> = pending
+        .iter()
+        .map(|(filename, file)| (filename.clone(), file.filename.clone()))
+        .collect();
+    if let Err(failure) = rename_mods_in_transaction(&mods_directory, &renames) {
+        for (index, (filename, _)) in pending.into_iter().enumerate() {
+            let reason = if index == failure.index {
+                failure.reason.clone()
+            } else if let Some((_, error)) = failure
+                .not_rolled_back
+                .iter()
+                .find(|(stuck, _)| *stuck == index)
+            {
+                format!("Renamed, but rolling back failed: {error}")
+            } else {
+                format!(
+                    "Not changed because '{}' could not be renamed.",
+                    renames[failure.index].0
+                )
+            };
+            result
+                .failed
+                .push(LocalModOperationFailure { filename, reason });
+        }
+        return Ok(result);
+    }
 
-    #[test]
-    fn parses_fabric_metadata() {
-        let entries = parse_fabric_metadata(
-            r#"{
-                "id": "example",
-                "version": "1.2.3",
-                "name": "Example Mod",
+    result.changed = pending
+        .into_iter()
+        .map(|(from, file)| LocalModRename { from, file })
+        .collect();
+    Ok(result)
+}
+
+fn delete_local_mods_blocking(
+    game_directory: &str,
+    filenames: Vec<String>,
+) -> Result<LocalModDeleteResult, String> {
+    let mods_directory = mods_directory(game_directory)?;
+    if !ensure_existing_mods_directory(&mods_directory)? {
+        return Err(format!(
+            "Mods directory '{}' does not exist.",
+            mods_directory.display()
+        ));
+    }
+    let trash_directory = mod_trash_directory(game_directory)?;
+    create_launcher_directory(&trash_directory)?;
+    let mut manifest = read_mod_trash_manifest(&trash_directory)?;
+
+    let mut result = LocalModDeleteResult {
+        deleted: Vec::with_capacity(filenames.len()),
+        failed: Vec::new(),
+    };
+    for filename in filenames {
+        match move_mod_to_trash(&mods_directory, &trash_directory, &filename) {
+            Ok(trashed) => result.deleted.push(trashed),
+            Err(reason) => result
+                .failed
+                .push(LocalModOperationFailure { filename, reason }),
+        }
+    }
+
+    if result.deleted.is_empty() {
+        return Ok(result);
+    }
+
+    manifest.entries.extend(result.deleted.iter().cloned());
+    if let Err(error) = write_mod_trash_manifest(&trash_directory, &manifest) {
+        // Without a manifest entry the trashed files could not be restored, so put them back.
+        for trashed in result.deleted.drain(..) {
+            let reason = match rename_without_overwrite(
+                &trash_directory.join(&trashed.id),
+                &mods_directory.join(&trashed.filename),
+            ) {
+                Ok(()) => error.clone(),
+                Err(rollback_error) => format!(
+                    "{error} Also failed to move '{}' back out of the mod trash: {rollback_error}",
+                    trashed.id
+                ),
+            };
+            result.failed.push(LocalModOperationFailure {
+                filename: trashed.filename,
+                reason,
+            });
+        }
+    }
+
+    Ok(result)
+}
+
+fn list_trashed_local_mods_blocking(game_directory: &str) -> Result<Vec<TrashedLocalMod>, String> {
+    let trash_directory = mod_trash_directory(game_directory)?;
+    let mut entries = read_mod_trash_manifest(&trash_directory)?.entries;
+    entries.sort_by_key(|entry| cmp::Reverse(entry.deleted_at_ms));
+    Ok(entries)
+}
+
+fn restore_trashed_mod(
+    mods_directory: &Path,
+    trash_directory: &Path,
+    trashed: &TrashedLocalMod,
+) -> Result<(), String> {
+    validate_single_filename(&trashed.id)?;
+    validate_mod_filename(&trashed.filename)?;
+
+    let destination = mods_directory.join(&trashed.filename);
+    let sibling_filename = enabled_filename(&trashed.filename)
+        .unwrap_or_else(|| format!("{}{DISABLED_SUFFIX}", trashed.filename));
+    if path_exists(&destination)? || path_exists(&mods_directory.join(&sibling_filename))? {
+        return Err(format!(
+            "Cannot restore '{}' because a mod with the same name is installed.",
+            trashed.filename
+        ));
+    }
+
+    rename_without_overwrite(&trash_directory.join(&trashed.id), &destination).map_err(|error| {
+        if error.kind() == io::ErrorKind::AlreadyExists {
+            format!(
+                "Cannot restore '{}' because a mod with the same name is installed.",
+                trashed.filename
+            )
+        } else {
+            format!("Failed to restore '{}': {error}", trashed.filename)
+        }
+    })
+}
+
+fn restore_trashed_local_mods_blocking(
+    game_directory: &str,
+    ids: Vec<String>,
+) -> Result<LocalModRestoreResult, String> {
+    let mods_directory = mods_directory(game_directory)?;
+    create_mods_directory(&mods_directory)?;
+    let trash_directory = mod_trash_directory(game_directory)?;
+    let mut manifest = read_mod_trash_manifest(&trash_directory)?;
+
+    let mut result = LocalModRestoreResult {
+        restored: Vec::with_capacity(ids.len()),
+        failed: Vec::new(),
+    };
+    for id in ids {
+        let Some(index) = manifest.entries.iter().position(|entry| entry.id == id) else {
+            result.failed.push(LocalModOperationFailure {
+                filename: id,
+                reason: "The mod is not in the trash.".to_string(),
+            });
+            continue;
+        };
+        match restore_trashed_mod(&mods_directory, &trash_directory, &manifest.entries[index]) {
+            Ok(()) => {
+                let trashed = manifest.entries.remove(index);
+                result.restored.push(trashed.filename);
+            }
+            Err(reason) => result.failed.push(LocalModOperationFailure {
+                filename: manifest.entries[index].filename.clone(),
+                reason,
+            }),
+        }
+    }
+
+    if !result.restored.is_empty() {
+        // The files are already back in place; a stale entry only fails a later restore.
+        if let Err(error) = write_mod_trash_manifest(&trash_directory, &manifest) {
+            result.failed.push(LocalModOperationFailure {
+                filename: MOD_TRASH_MANIFEST_FILENAME.to_string(),
+                reason: format!(
+                    "The mods were restored, but the trash list could not be updated: {error}"
+                ),
+            });
+        }
+    }
+
+    Ok(result)
+}
+
+fn mod_profiles_path(game_directory: &str) -> Result<PathBuf, String> {
+    Ok(game_directory_path(game_directory)?
+        .join(LAUNCHER_DIRECTORY_NAME)
+        .join(MOD_PROFILES_FILENAME))
+}
+
+fn read_mod_profiles(path: &Path) -> Result<ModProfileStore, String> {
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => {
+            return Ok(ModProfileStore::default());
+        }
+        Err(error) => {
+            return Err(format!(
+                "Failed to read mod profiles '{}': {error}",
+                path.display()
+            ));
+        }
+    };
+    serde_json::from_str(&text)
+        .map_err(|error| format!("Mod profiles '{}' are corrupt: {error}", path.display()))
+}
+
+fn write_mod_profiles(path: &Path, store: &ModProfileStore) -> Result<(), String> {
+    if let Some(directory) = path.parent() {
+        create_launcher_directory(directory)?;
+    }
+    write_json_atomically(path, store)
+}
+
+fn validate_mod_profile_name(name: &str) -> Result<String, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Mod profile name cannot be empty.".to_string());
+    }
+    if name.chars().count() > MAX_MOD_PROFILE_NAME_CHARS {
+        return Err(format!(
+            "Mod profile name cannot be longer than {MAX_MOD_PROFILE_NAME_CHARS} characters."
+        ));
+    }
+    Ok(name.to_string())
+}
+
+/// Where a transactional rename stopped, with earlier renames that could not be undone.
+struct ModRenameFailure {
+    index: usize,
+    reason: String,
+    not_rolled_back: Vec<(usize, String)>,
+}
+
+impl ModRenameFailure {
+    fn describe(&self, renames: &[(String, String)]) -> String {
+        let mut message = self.reason.clone();
+        for (index, error) in &self.not_rolled_back {
+            message.push_str(&format!(
+                " Rolling back '{}' also failed: {error}",
+                renames[*index].1
+            ));
+        }
+        message
+    }
+}
+
+/// Rename `(from, to)` pairs in order; on failure, undo the renames already done, newest first.
+fn rename_mods_in_transaction(
+    mods_directory: &Path,
+    renames: &[(String, String)],
+) -> Result<(), ModRenameFailure> {
+    for (done, (from, to)) in renames.iter().enumerate() {
+        let Err(error) =
+            rename_without_overwrite(&mods_directory.join(from), &mods_directory.join(to))
+        else {
+            continue;
+        };
+        let mut failure = ModRenameFailure {
+            index: done,
+            reason: format!("Failed to rename mod '{from}' to '{to}': {error}"),
+            not_rolled_back: Vec::new(),
+        };
+        for (index, (from, to)) in renames[..done].iter().enumerate().rev() {
+            if let Err(error) =
+                rename_without_overwrite(&mods_directory.join(to), &mods_directory.join(from))
+            {
+                failure.not_rolled_back.push((index, error.to_string()));
+            }
+        }
+        return Err(failure);
+    }
+    Ok(())
+}
+
+fn list_mod_profiles_blocking(game_directory: &str) -> Result<Vec<LocalModProfile>, String> {
+    let mut profiles = read_mod_profiles(&mod_profiles_path(game_directory)?)?.profiles;
+    profiles.sort_by(|left, right| left.name.cmp(&right.name));
+    Ok(profiles)
+}
+
+/// Create or replace a profile; without `mods`, it captures the currently enabled mods.
+fn save_mod_profile_blocking(
+    game_directory: &str,
+    name: &str,
+    mods: Option<Vec<String>>,
+) -> Result<LocalModProfile, String> {
+    let name = validate_mod_profile_name(name)?;
+    let mods = match mods {
+        Some(mods) => mods,
+        None => mod_directory_snapshot(&mods_directory(game_directory)?)
+            .into_keys()
+            .filter(|filename| is_enabled_mod_filename(filename))
+            .collect(),
+    };
+    let mut mods: Vec<String> = mods
+        .into_iter()
+        .map(|filename| enabled_filename(&filename).unwrap_or(filename))
+        .collect();
+    for filename in &mods {
+        validate_mod_filename(filename)?;
+    }
+    mods.sort();
+    mods.dedup();
+
+    let path = mod_profiles_path(game_directory)?;
+    let mut store = read_mod_profiles(&path)?;
+    let profile = LocalModProfile { name, mods };
+    store
+        .profiles
+        .retain(|existing| existing.name != profile.name);
+    store.profiles.push(profile.clone());
+    write_mod_profiles(&path, &store)?;
+    Ok(profile)
+}
+
+fn delete_mod_profile_blocking(game_directory: &str, name: &str) -> Result<bool, String> {
+    let path = mod_profiles_path(game_directory)?;
+    let mut store = read_mod_profiles(&path)?;
+    let count = store.profiles.len();
+    store.profiles.retain(|profile| profile.name != name.trim());
+    if store.profiles.len() == count {
+        return Ok(false);
+    }
+    write_mod_profiles(&path, &store)?;
+    Ok(true)
+}
+
+/// Enable exactly the profile's mods and disable the rest, all or nothing.
+fn apply_mod_profile_blocking(
+    game_directory: &str,
+    name: &str,
+) -> Result<LocalModProfileApplyResult, String> {
+    let store = read_mod_profiles(&mod_profiles_path(game_directory)?)?;
+    let profile = store
+        .profiles
+        .iter()
+        .find(|profile| profile.name == name.trim())
+        .ok_or_else(|| format!("Mod profile '{}' does not exist.", name.trim()))?;
+    let wanted: BTreeSet<&str> = profile.mods.iter().map(String::as_str).collect();
+    apply_enabled_mod_set(game_directory, &wanted, None)
+}
+
+/// Enable exactly `wanted` (filenames without `.disabled`) and disable the rest, all or nothing.
+///
+/// With a `scope`, mods outside it are left as they are.
+fn apply_enabled_mod_set(
+    game_directory: &str,
+    wanted: &BTreeSet<&str>,
+    scope: Option<&BTreeSet<&str>>,
+) -> Result<LocalModProfileApplyResult, String> {
+    let mods_directory = mods_directory(game_directory)?;
+    if !ensure_existing_mods_directory(&mods_directory)? {
+        return Err(format!(
+            "Mods directory '{}' does not exist.",
+            mods_directory.display()
+        ));
+    }
+    let installed: Vec<String> = mod_directory_snapshot(&mods_directory)
+        .into_keys()
+        .collect();
+
+    let mut identities = BTreeSet::new();
+    let mut renames = Vec::new();
+    let mut result = LocalModProfileApplyResult {
+        enabled: Vec::new(),
+        disabled: Vec::new(),
+        missing: Vec::new(),
+    };
+    for filename in &installed {
+        let enabled = is_enabled_mod_filename(filename);
+        let identity = enabled_filename(filename).unwrap_or_else(|| filename.clone());
+        if scope.is_some_and(|scope| !scope.contains(identity.as_str())) {
+            continue;
+        }
+        if !identities.insert(identity.clone()) {
+            return Err(format!(
+                "'{identity}' is installed both enabled and disabled; remove one copy first."
+            ));
+        }
+        match (enabled, wanted.contains(identity.as_str())) {
+            (false, true) => {
+                renames.push((filename.clone(), identity.clone()));
+                result.enabled.push(identity);
+            }
+            (true, false) => {
+                let disabled = format!("{filename}{DISABLED_SUFFIX}");
+                renames.push((filename.clone(), disabled.clone()));
+                result.disabled.push(disabled);
+            }
+            _ => {}
+        }
+    }
+    result.missing = wanted
+        .iter()
+        .filter(|filename| !identities.contains(**filename))
+        .map(|filename| filename.to_string())
+        .collect();
+
+    rename_mods_in_transaction(&mods_directory, &renames)
+        .map_err(|failure| failure.describe(&renames))?;
+    Ok(result)
+}
+
+fn mod_bisect_path(game_directory: &str) -> Result<PathBuf, String> {
+    Ok(game_directory_path(game_directory)?
+        .join(LAUNCHER_DIRECTORY_NAME)
+        .join(MOD_BISECT_FILENAME))
+}
+
+fn read_mod_bisect(path: &Path) -> Result<Option<LocalModBisectSession>, String> {
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(error) => {
+            return Err(format!(
+                "Failed to read mod bisection '{}': {error}",
+                path.display()
+            ));
+        }
+    };
+    serde_json::from_str(&text)
+        .map(Some)
+        .map_err(|error| format!("Mod bisection '{}' is corrupt: {error}", path.display()))
+}
+
+fn write_mod_bisect(path: &Path, session: &LocalModBisectSession) -> Result<(), String> {
+    if let Some(directory) = path.parent() {
+        create_launcher_directory(directory)?;
+    }
+    write_json_atomically(path, session)
+}
+
+/// For each mod (without `.disabled`), the other installed mods that provide its required dependencies.
+fn required_mod_providers(mods: &[LocalModFile]) -> BTreeMap<String, BTreeSet<String>> {
+    let identity = |local_mod: &LocalModFile| {
+        enabled_filename(&local_mod.filename).unwrap_or_else(|| local_mod.filename.clone())
+    };
+    let mut providers: HashMap<String, BTreeSet<String>> = HashMap::new();
+    for local_mod in mods {
+        let mut entries = Vec::new();
+        collect_metadata_entries(&local_mod.metadata, &mut entries);
+        for entry in entries {
+            for mod_id in entry.mod_id.iter().chain(&entry.provides) {
+                providers
+                    .entry(mod_id.to_ascii_lowercase())
+                    .or_default()
+                    .insert(identity(local_mod));
+            }
+        }
+    }
+
+    mods.iter()
+        .map(|local_mod| {
+            let filename = identity(local_mod);
+            let mut required = BTreeSet::new();
+            for entry in &local_mod.metadata.entries {
+                for dependency in &entry.dependencies {
+                    if dependency_kind(&entry.loader, dependency) != DependencyKind::Required {
+                        continue;
+                    }
+                    let Some(candidates) = providers.get(&dependency.mod_id.to_ascii_lowercase())
+                    else {
+                        continue;
+                    };
+                    // Satisfied by the jar itself or its bundled jars.
+                    if candidates.contains(&filename) {
+                        continue;
+                    }
+                    required.extend(candidates.iter().cloned());
+                }
+            }
+            (filename, required)
+        })
+        .collect()
+}
+
+/// `seed` and everything it transitively requires among `available`.
+fn required_mod_closure(
+    seed: &str,
+    dependencies: &BTreeMap<String, BTreeSet<String>>,
+    available: &BTreeSet<&str>,
+) -> BTreeSet<String> {
+    let mut closure = BTreeSet::from([seed.to_string()]);
+    let mut pending = vec![seed.to_string()];
+    while let Some(filename) = pending.pop() {
+        for required in dependencies.get(&filename).into_iter().flatten() {
+            if available.contains(required.as_str()) && closure.insert(required.clone()) {
+                pending.push(required.clone());
+            }
+        }
+    }
+    closure
+}
+
+/// Pick about half of the suspects to test, with the suspects they or the cleared mods require;
+/// `false` when every choice would pull in all of them.
+fn plan_mod_bisect_round(
+    session: &mut LocalModBisectSession,
+    dependencies: &BTreeMap<String, BTreeSet<String>>,
+) -> bool {
+    let available: BTreeSet<&str> = session
+        .original_enabled
+        .iter()
+        .map(String::as_str)
+        .collect();
+    let suspects: BTreeSet<&str> = session.suspects.iter().map(String::as_str).collect();
+    let half = session.suspects.len() / 2;
+    let mut seeds = BTreeSet::new();
+    // Suspects the always-enabled mods require have to stay enabled in every round.
+    let mut testing: BTreeSet<String> = available
+        .difference(&suspects)
+        .flat_map(|cleared| required_mod_closure(cleared, dependencies, &available))
+        .filter(|filename| suspects.contains(filename.as_str()))
+        .collect();
+    if testing.len() >= session.suspects.len() {
+        return false;
+    }
+    for suspect in &session.suspects {
+        if testing.len() >= half.max(1) {
+            break;
+        }
+        if testing.contains(suspect) {
+            continue;
+        }
+        let closure: BTreeSet<String> = required_mod_closure(suspect, dependencies, &available)
+            .into_iter()
+            .filter(|filename| suspects.contains(filename.as_str()))
+            .collect();
+        if testing.union(&closure).count() < session.suspects.len() {
+            seeds.insert(suspect.clone());
+            testing.extend(closure);
+        }
+    }
+    if testing.is_empty() {
+        return false;
+    }
+    session.kept_for_dependencies = testing.difference(&seeds).cloned().collect();
+    session.testing = testing.into_iter().collect();
+    true
+}
+
+/// The mods a bisection may rename: the originally enabled ones, suspects included.
+fn mod_bisect_scope(session: &LocalModBisectSession) -> BTreeSet<&str> {
+    session
+        .original_enabled
+        .iter()
+        .map(String::as_str)
+        .collect()
+}
+
+/// Enable the cleared mods and the tested suspects for the next launch.
+fn apply_mod_bisect_round(
+    game_directory: &str,
+    session: &LocalModBisectSession,
+) -> Result<(), String> {
+    let mut wanted: BTreeSet<&str> = session
+        .original_enabled
+        .iter()
+        .map(String::as_str)
+        .collect();
+    for suspect in &session.suspects {
+        wanted.remove(suspect.as_str());
+    }
+    wanted.extend(session.testing.iter().map(String::as_str));
+    apply_enabled_mod_set(game_directory, &wanted, Some(&mod_bisect_scope(session))).map(|_| ())
+}
+
+/// Restore the original mods, forget the session and return it marked as finished.
+fn finish_mod_bisect(
+    game_directory: &str,
+    mut session: LocalModBisectSession,
+) -> Result<LocalModBisectSession, String> {
+    let wanted = mod_bisect_scope(&session);
+    apply_enabled_mod_set(game_directory, &wanted, Some(&wanted))?;
+    remove_file_if_exists(&mod_bisect_path(game_directory)?)
+        .map_err(|error| format!("Failed to remove the mod bisection: {error}"))?;
+    session.culprit = match session.suspects.as_slice() {
+        [culprit] => Some(culprit.clone()),
+        _ => None,
+    };
+    session.testing.clear();
+    session.kept_for_dependencies.clear();
+    session.finished = true;
+    Ok(session)
+}
+
+fn start_mod_bisect_blocking(
+    game_directory: &str,
+    suspects: Option<Vec<String>>,
+) -> Result<LocalModBisectSession, String> {
+    let path = mod_bisect_path(game_directory)?;
+    if read_mod_bisect(&path)?.is_some() {
+        return Err("A mod bisection is already running; finish or cancel it first.".to_string());
+    }
+    let mods = scan_local_mods_blocking(game_directory)?;
+    let original_enabled: Vec<String> = mods
+        .iter()
+        .filter(|local_mod| local_mod.enabled)
+        .map(|local_mod| local_mod.filename.clone())
+        .collect();
+    let mut suspects = match suspects {
+        Some(suspects) => suspects
+            .into_iter()
+            .map(|filename| enabled_filename(&filename).unwrap_or(filename))
+            .collect(),
+        None => original_enabled.clone(),
+    };
+    suspects.sort();
+    suspects.dedup();
+    if let Some(unknown) = suspects
+        .iter()
+        .find(|suspect| !original_enabled.contains(suspect))
+    {
+        return Err(format!("'{unknown}' is not an enabled mod."));
+    }
+    if suspects.len() < 2 {
+        return Err("A mod bisection needs at least two enabled suspects.".to_string());
+    }
+
+    let mut session = LocalModBisectSession {
+        original_enabled,
+        suspects,
+        testing: Vec::new(),
+        kept_for_dependencies: Vec::new(),
+        round: 1,
+        culprit: None,
+        finished: false,
+    };
+    if !plan_mod_bisect_round(&mut session, &required_mod_providers(&mods)) {
+        return Err("The suspects all require each other, so they cannot be split.".to_string());
+    }
+    // Record the original state before touching any mod so it can always be restored.
+    write_mod_bisect(&path, &session)?;
+    if let Err(error) = apply_mod_bisect_round(game_directory, &session) {
+        let _ = remove_file_if_exists(&path);
+        return Err(error);
+    }
+    Ok(session)
+}
+
+/// Narrow the suspects by whether the last launch worked, then set up the next one.
+fn record_mod_bisect_result_blocking(
+    game_directory: &str,
+    works: bool,
+) -> Result<LocalModBisectSession, String> {
+    let path = mod_bisect_path(game_directory)?;
+    let mut session =
+        read_mod_bisect(&path)?.ok_or_else(|| "No mod bisection is running.".to_string())?;
+    session.suspects = if works {
+        session
+            .suspects
+            .iter()
+            .filter(|suspect| !session.testing.contains(suspect))
+            .cloned()
+            .collect()
+    } else {
+        session.testing.clone()
+    };
+    session.round += 1;
+    if session.suspects.len() < 2 {
+        return finish_mod_bisect(game_directory, session);
+    }
+
+    let mods = scan_local_mods_blocking(game_directory)?;
+    if !plan_mod_bisect_round(&mut session, &required_mod_providers(&mods)) {
+        return finish_mod_bisect(game_directory, session);
+    }
+    apply_mod_bisect_round(game_directory, &session)?;
+    write_mod_bisect(&path, &session)?;
+    Ok(session)
+}
+
+fn cancel_mod_bisect_blocking(game_directory: &str) -> Result<bool, String> {
+    let Some(session) = read_mod_bisect(&mod_bisect_path(game_directory)?)? else {
+        return Ok(false);
+    };
+    finish_mod_bisect(game_directory, session)?;
+    Ok(true)
+}
+
+#[cfg(unix)]
+fn file_inode(metadata: &fs::Metadata) -> Option<u64> {
+    use std::os::unix::fs::MetadataExt;
+    Some(metadata.ino())
+}
+
+#[cfg(not(unix))]
+fn file_inode(_metadata: &fs::Metadata) -> Option<u64> {
+    None
+}
+
+/// Size, mtime and inode of every mod file; a missing directory is an empty snapshot.
+fn mod_directory_snapshot(mods_directory: &Path) -> BTreeMap<String, ModFileStamp> {
+    let Ok(entries) = fs::read_dir(mods_directory) else {
+        return BTreeMap::new();
+    };
+    entries
+        .filter_map(Result::ok)
+        .filter_map(|entry| {
+            let filename = entry.file_name().into_string().ok()?;
+            if !is_mod_filename(&filename) || is_launcher_temp_filename(&filename) {
+                return None;
+            }
+            let metadata = fs::symlink_metadata(entry.path()).ok()?;
+            metadata.is_file().then(|| {
+                (
+                    filename,
+                    ModFileStamp {
+                        size: metadata.len(),
+                        modified_ns: file_modified_ns(&metadata),
+                        inode: file_inode(&metadata),
+                    },
+                )
+            })
+        })
+        .collect()
+}
+
+/// Compare two snapshots, pairing a removed and an added file with the same stamp as a rename.
+fn diff_mod_directory_snapshots(
+    previous: &BTreeMap<String, ModFileStamp>,
+    current: &BTreeMap<String, ModFileStamp>,
+) -> ModDirectoryDiff {
+    let mut diff = ModDirectoryDiff::default();
+    let mut added: Vec<&String> = current
+        .iter()
+        .filter(|(filename, stamp)| previous.get(*filename) != Some(*stamp))
+        .map(|(filename, _)| filename)
+        .collect();
+
+    for (filename, stamp) in previous {
+        if current.contains_key(filename) {
+            continue;
+        }
+        let renamed_to = added.iter().position(|candidate| {
+            !previous.contains_key(*candidate)
+                && current.get(*candidate) == Some(stamp)
+                && stamp.modified_ns.is_some()
+        });
+        match renamed_to {
+            Some(index) => diff
+                .renamed
+                .push((filename.clone(), added.remove(index).clone())),
+            None => diff.removed.push(filename.clone()),
+        }
+    }
+    diff.added = added.into_iter().cloned().collect();
+    diff
+}
+
+/// Wakes the watcher when the directory may have changed.
+enum ModDirectoryWaker {
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    Inotify(rustix::fd::OwnedFd),
+    Polling(Instant),
+}
+
+impl ModDirectoryWaker {
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    fn new(mods_directory: &Path) -> Self {
+        use rustix::fs::inotify::{self, CreateFlags, WatchFlags};
+
+        let watch = inotify::init(CreateFlags::CLOEXEC | CreateFlags::NONBLOCK).and_then(|fd| {
+            inotify::add_watch(
+                &fd,
+                mods_directory,
+                WatchFlags::CREATE
+                    | WatchFlags::DELETE
+                    | WatchFlags::MODIFY
+                    | WatchFlags::CLOSE_WRITE
+                    | WatchFlags::MOVED_FROM
+                    | WatchFlags::MOVED_TO
+                    | WatchFlags::DELETE_SELF
+                    | WatchFlags::MOVE_SELF
+                    | WatchFlags::ONLYDIR,
+            )?;
+            Ok(fd)
+        });
+        match watch {
+            Ok(fd) => ModDirectoryWaker::Inotify(fd),
+            // Missing directories and exhausted watch limits fall back to polling.
+            Err(_) => ModDirectoryWaker::Polling(Instant::now()),
+        }
+    }
+
+    #[cfg(not(any(target_os = "linux", target_os = "android")))]
+    fn new(_mods_directory: &Path) -> Self {
+        ModDirectoryWaker::Polling(Instant::now())
+    }
+
+    /// Whether anything relevant happened since the last call.
+    fn changed(&mut self, mods_directory: &Path) -> bool {
+        match self {
+            #[cfg(any(target_os = "linux", target_os = "android"))]
+            ModDirectoryWaker::Inotify(fd) => {
+                use rustix::fs::inotify::{ReadFlags, Reader};
+
+                let mut buffer = [std::mem::MaybeUninit::uninit(); 4096];
+                let mut reader = Reader::new(&*fd, &mut buffer);
+                let mut changed = false;
+                let mut watch_removed = false;
+                loop {
+                    match reader.next() {
+                        Ok(event) => {
+                            let ignored = event
+                                .file_name()
+                                .and_then(|name| name.to_str().ok())
+                                .is_some_and(is_launcher_temp_filename);
+                            // The watch dies with its directory; a replacement needs a new one.
+                            watch_removed |= event.events().intersects(
+                                ReadFlags::DELETE_SELF | ReadFlags::MOVE_SELF | ReadFlags::IGNORED,
+                            );
+                            changed |=
+                                !ignored || event.events().intersects(ReadFlags::QUEUE_OVERFLOW);
+                        }
+                        Err(rustix::io::Errno::AGAIN) => break,
+                        Err(_) => {
+                            watch_removed = true;
+                            break;
+                        }
+                    }
+                }
+                if watch_removed {
+                    *self = ModDirectoryWaker::Polling(Instant::now());
+                    return true;
+                }
+                changed
+            }
+            ModDirectoryWaker::Polling(last_poll) => {
+                if last_poll.elapsed() < MOD_WATCH_POLL_INTERVAL {
+                    return false;
+                }
+                *last_poll = Instant::now();
+                // Pick events up again once the directory exists, e.g. after it was replaced.
+                let rewatched = ModDirectoryWaker::new(mods_directory);
+                if !matches!(rewatched, ModDirectoryWaker::Polling(_)) {
+                    *self = rewatched;
+                }
+                true
+            }
+        }
+    }
+}
+
+fn local_mod_directory_change(
+    mods_directory: &Path,
+    watch_id: &str,
+    diff: ModDirectoryDiff,
+) -> LocalModDirectoryChange {
+    let parse = |filename: &str| {
+        local_mod_file(
+            &mods_directory.join(filename),
+            filename.to_string(),
+            is_enabled_mod_filename(filename),
+        )
+        .ok()
+    };
+    LocalModDirectoryChange {
+        watch_id: watch_id.to_string(),
+        // A file that vanished again before parsing is reported by the next batch.
+        added: diff
+            .added
+            .iter()
+            .filter_map(|filename| parse(filename))
+            .collect(),
+        removed: diff.removed,
+        renamed: diff
+            .renamed
+            .into_iter()
+            .filter_map(|(from, to)| {
+                Some(LocalModRename {
+                    file: parse(&to)?,
+                    from,
+                })
+            })
+            .collect(),
+    }
+}
+
+fn watch_mods_directory(
+    app: AppHandle,
+    watch_id: String,
+    mods_directory: PathBuf,
+    stopped: Arc<AtomicBool>,
+) {
+    let mut snapshot = mod_directory_snapshot(&mods_directory);
+    let mut waker = ModDirectoryWaker::new(&mods_directory);
+    let mut pending_since: Option<Instant> = None;
+
+    while !stopped.load(Ordering::Relaxed) {
+        std::thread::sleep(MOD_WATCH_TICK);
+        if waker.changed(&mods_directory) {
+            pending_since = Some(Instant::now());
+        }
+        if pending_since.is_none_or(|since| since.elapsed() < MOD_WATCH_DEBOUNCE) {
+            continue;
+        }
+        pending_since = None;
+
+        // Parse under the operation lock so launcher imports and toggles are never half-seen.
+        let _operation_guard = MODS_OPERATION_LOCK.blocking_lock();
+        let current = mod_directory_snapshot(&mods_directory);
+        let diff = diff_mod_directory_snapshots(&snapshot, &current);
+        snapshot = current;
+        if diff == ModDirectoryDiff::default() {
+            continue;
+        }
+        let change = local_mod_directory_change(&mods_directory, &watch_id, diff);
+        let _ = app.emit(MOD_DIRECTORY_CHANGE_EVENT, change);
+    }
+}
+
+fn dependency_kind(loader: &str, dependency: &LocalModDependency) -> DependencyKind {
+    let relation = dependency.relation.to_ascii_lowercase();
+    if loader == "Forge Legacy" {
+        // mcmod.info `dependencies` and `dependants` only order loading.
+        return match relation.as_str() {
+            "required" | "required-after" | "required-before" => DependencyKind::Required,
+            _ => DependencyKind::Ordering,
+        };
+    }
+
+    match relation.as_str() {
+        "depends" | "dependency" | "required" => {
+            if dependency.required == Some(false) {
+                DependencyKind::Optional
+            } else {
+                DependencyKind::Required
+            }
+        }
+        "recommends" | "suggests" | "optional" => DependencyKind::Optional,
+        "breaks" | "incompatible" => DependencyKind::Breaks,
+        "conflicts" | "discouraged" => DependencyKind::Conflicts,
+        _ => DependencyKind::Ordering,
+    }
+}
+
+fn collect_metadata_entries<'a>(
+    metadata: &'a LocalModMetadata,
+    entries: &mut Vec<&'a LocalModMetadataEntry>,
+) {
+    entries.extend(&metadata.entries);
+    for nested in &metadata.nested {
+        collect_metadata_entries(&nested.metadata, entries);
+    }
+}
+
+fn builtin_providers(version: &InstanceVersionInfo) -> Vec<ModProvider<'_>> {
+    let mut providers = Vec::new();
+    if let Some(game_version) = version.game_version.as_deref() {
+        providers.push(ModProvider {
+            filename: None,
+            mod_id: "minecraft",
+            version: Some(game_version),
+            via_provides: false,
+        });
+    }
+
+    let loader_version = version.loader_version.as_deref();
+    let loader_ids: &[(&str, bool)] = match version.loader.as_deref() {
+        Some("Fabric") => &[("fabricloader", true)],
+        // Quilt loads Fabric mods and answers for `fabricloader` with its own compatibility version.
+        Some("Quilt") => &[("quilt_loader", true), ("fabricloader", false)],
+        Some("Forge") => &[("forge", true), ("fml", false)],
+        Some("NeoForge") if loader_version.is_some_and(|version| version.starts_with("47.")) => {
+            &[("neoforge", true), ("forge", true)]
+        }
+        Some("NeoForge") => &[("neoforge", true)],
+        _ => &[],
+    };
+    for (mod_id, versioned) in loader_ids {
+        providers.push(ModProvider {
+            filename: None,
+            mod_id,
+            version: if *versioned { loader_version } else { None },
+            via_provides: false,
+        });
+    }
+
+    providers
+}
+
+/// Whether an instance running `version` loads metadata written for `entry_loader`.
+fn loader_accepts(version: &InstanceVersionInfo, entry_loader: &str) -> bool {
+    match (version.loader.as_deref(), entry_loader) {
+        (Some("Fabric"), "Fabric") => true,
+        (Some("Quilt"), "Quilt" | "Fabric") => true,
+        (Some("Forge"), "Forge / NeoForge" | "Forge Legacy") => true,
+        (Some("NeoForge"), "NeoForge") => true,
+        // NeoForge stopped reading `mods.toml` in 1.20.5.
+        (Some("NeoForge"), "Forge / NeoForge") => {
+            version.game_version.as_deref().is_none_or(|game_version| {
+                compare_versions(game_version, "1.20.5").is_none_or(|order| order.is_lt())
+            })
+        }
+        _ => false,
+    }
+}
+
+/// Compare a mod's declared loader and its `minecraft` and loader requirements with the instance.
+fn mod_compatibility(
+    local_mod: &LocalModFile,
+    version: &InstanceVersionInfo,
+) -> LocalModCompatibility {
+    let entries = &local_mod.metadata.entries;
+    if entries.is_empty() {
+        return LocalModCompatibility {
+            verdict: "unknown".to_string(),
+            issues: Vec::new(),
+        };
+    }
+
+    let mut accepted: Vec<&LocalModMetadataEntry> = entries
+        .iter()
+        .filter(|entry| loader_accepts(version, &entry.loader))
+        .collect();
+    if accepted.is_empty() {
+        let mut loaders: Vec<&str> = entries.iter().map(|entry| entry.loader.as_str()).collect();
+        loaders.sort_unstable();
+        loaders.dedup();
+        return LocalModCompatibility {
+            verdict: "incompatible".to_string(),
+            issues: vec![LocalModCompatibilityIssue {
+                kind: "loader".to_string(),
+                required: loaders.join(" or "),
+                found: version.loader.clone(),
+            }],
+        };
+    }
+    // Quilt reads `quilt.mod.json` in preference to a bundled `fabric.mod.json`.
+    if accepted.iter().any(|entry| entry.loader == "Quilt") {
+        accepted.retain(|entry| entry.loader == "Quilt");
+    }
+
+    let providers = builtin_providers(version);
+    let mut issues = Vec::new();
+    let mut undetermined = false;
+    for entry in accepted {
+        // `mcmod.info` has no version ranges, only the `mcversion` the mod was built for.
+        if entry.loader == "Forge Legacy" {
+            match (
+                entry.game_version.as_deref(),
+                version.game_version.as_deref(),
+            ) {
+                (Some(required), Some(found)) => match compare_versions(required, found) {
+                    Some(order) if order.is_eq() || found.starts_with(&format!("{required}.")) => {}
+                    Some(_) => {
+                        let issue = LocalModCompatibilityIssue {
+                            kind: "gameVersion".to_string(),
+                            required: required.to_string(),
+                            found: Some(found.to_string()),
+                        };
+                        if !issues.contains(&issue) {
+                            issues.push(issue);
+                        }
+                    }
+                    None => undetermined = true,
+                },
+                _ => undetermined = true,
+            }
+        }
+        for dependency in &entry.dependencies {
+            let Some(requirement) = dependency.version.as_deref() else {
+                continue;
+            };
+            if dependency_kind(&entry.loader, dependency) != DependencyKind::Required {
+                continue;
+            }
+            let Some(found) = providers
+                .iter()
+                .filter(|provider| provider.mod_id.eq_ignore_ascii_case(&dependency.mod_id))
+                .find_map(|provider| provider.version)
+            else {
+                continue;
+            };
+            let matches = version_requirement_matches(
+                VersionSyntax::for_loader(&entry.loader),
+                requirement,
+                found,
+            );
+            if matches != Some(false) {
+                continue;
+            }
+            let issue = LocalModCompatibilityIssue {
+                kind: if dependency.mod_id.eq_ignore_ascii_case("minecraft") {
+                    "gameVersion"
+                } else {
+                    "loaderVersion"
+                }
+                .to_string(),
+                required: requirement.to_string(),
+                found: Some(found.to_string()),
+            };
+            if !issues.contains(&issue) {
+                issues.push(issue);
+            }
+        }
+    }
+
+    LocalModCompatibility {
+        verdict: if !issues.is_empty() {
+            "incompatible"
+        } else if undetermined {
+            "unknown"
+        } else {
+            "compatible"
+        }
+        .to_string(),
+        issues,
+    }
+}
+
+fn attach_mod_compatibility(mods: &mut [LocalModFile], version: &InstanceVersionInfo) {
+    for local_mod in mods {
+        local_mod.compatibility = Some(mod_compatibility(local_mod, version));
+    }
+}
+
+/// The only side a mod runs on, when all of its entries agree on one.
+fn mod_side(local_mod: &LocalModFile) -> Option<InstanceSide> {
+    let mut sides = local_mod.metadata.entries.iter().map(|entry| {
+        entry
+            .environment
+            .as_deref()
+            .and_then(InstanceSide::from_environment)
+    });
+    let first = sides.next()??;
+    sides.all(|side| side == Some(first)).then_some(first)
+}
+
+/// Warn about enabled mods, and mods only they require, that belong on the other side.
+fn mod_side_warnings(mods: &[LocalModFile], side: InstanceSide) -> Vec<Vec<LocalModSideWarning>> {
+    let other = side.other();
+    let sides: Vec<Option<InstanceSide>> = mods.iter().map(mod_side).collect();
+    let mut providers: HashMap<String, Vec<usize>> = HashMap::new();
+    for (index, local_mod) in mods.iter().enumerate() {
+        if !local_mod.enabled {
+            continue;
+        }
+        for entry in &local_mod.metadata.entries {
+            for mod_id in entry.mod_id.iter().chain(&entry.provides) {
+                providers
+                    .entry(mod_id.to_ascii_lowercase())
+                    .or_default()
+                    .push(index);
+            }
+        }
+    }
+
+    let mut needed_here = vec![false; mods.len()];
+    let mut needed_elsewhere: Vec<Vec<String>> = vec![Vec::new(); mods.len()];
+    for (index, local_mod) in mods.iter().enumerate() {
+        if !local_mod.enabled {
+            continue;
+        }
+        for entry in &local_mod.metadata.entries {
+            for dependency in &entry.dependencies {
+                if dependency_kind(&entry.loader, dependency) != DependencyKind::Required {
+                    continue;
+                }
+                // A dependency of an other-side mod is only needed there as well.
+                let elsewhere = sides[index] == Some(other)
+                    || dependency
+                        .side
+                        .as_deref()
+                        .and_then(InstanceSide::from_environment)
+                        == Some(other);
+                let targets = providers
+                    .get(&dependency.mod_id.to_ascii_lowercase())
+                    .map(Vec::as_slice)
+                    .unwrap_or_default();
+                for &target in targets.iter().filter(|&&target| target != index) {
+                    if !elsewhere {
+                        needed_here[target] = true;
+                    } else if !needed_elsewhere[target].contains(&local_mod.filename) {
+                        needed_elsewhere[target].push(local_mod.filename.clone());
+                    }
+                }
+            }
+        }
+    }
+
+    mods.iter()
+        .enumerate()
+        .map(|(index, local_mod)| {
+            if !local_mod.enabled || sides[index] == Some(side) {
+                return Vec::new();
+            }
+            if sides[index] == Some(other) {
+                return vec![LocalModSideWarning {
+                    kind: "otherSide".to_string(),
+                    side: other,
+                    required_by: Vec::new(),
+                }];
+            }
+            if needed_here[index] || needed_elsewhere[index].is_empty() {
+                return Vec::new();
+            }
+            vec![LocalModSideWarning {
+                kind: "otherSideDependency".to_string(),
+                side: other,
+                required_by: std::mem::take(&mut needed_elsewhere[index]),
+            }]
+        })
+        .collect()
+}
+
+fn attach_mod_side_warnings(mods: &mut [LocalModFile], side: InstanceSide) {
+    let warnings = mod_side_warnings(mods, side);
+    for (local_mod, warnings) in mods.iter_mut().zip(warnings) {
+        local_mod.side_warnings = warnings;
+    }
+}
+
+/// Disable every enabled mod the side check warns about, as one batch.
+fn disable_other_side_mods_blocking(
+    game_directory: &str,
+    side: InstanceSide,
+) -> Result<LocalModStateBatchResult, String> {
+    let mods = scan_local_mods_blocking(game_directory)?;
+    let filenames: Vec<String> = mods
+        .iter()
+        .zip(mod_side_warnings(&mods, side))
+        .filter(|(_, warnings)| !warnings.is_empty())
+        .map(|(local_mod, _)| local_mod.filename.clone())
+        .collect();
+    if filenames.is_empty() {
+        return Ok(LocalModStateBatchResult {
+            changed: Vec::new(),
+            unchanged: Vec::new(),
+            failed: Vec::new(),
+        });
+    }
+    set_local_mods_enabled_blocking(game_directory, filenames, false)
+}
+
+/// Check the declared relations of every enabled mod against each other and the instance.
+fn analyze_mod_dependencies(
+    mods: &[LocalModFile],
+    version: &InstanceVersionInfo,
+) -> (
+    Vec<LocalModDependencyProblem>,
+    Vec<LocalModProvidedDependency>,
+) {
+    let enabled_mods: Vec<&LocalModFile> =
+        mods.iter().filter(|local_mod| local_mod.enabled).collect();
+    let mut providers: HashMap<String, Vec<ModProvider>> = HashMap::new();
+    for provider in builtin_providers(version) {
+        providers
+            .entry(provider.mod_id.to_ascii_lowercase())
+            .or_default()
+            .push(provider);
+    }
+    for local_mod in &enabled_mods {
+        // Loaders load jar-in-jar mods too, so bundled entries satisfy dependencies.
+        let mut entries = Vec::new();
+        collect_metadata_entries(&local_mod.metadata, &mut entries);
+        for entry in entries {
+            let Some(mod_id) = entry.mod_id.as_deref() else {
+                continue;
+            };
+            providers
+                .entry(mod_id.to_ascii_lowercase())
+                .or_default()
+                .push(ModProvider {
+                    filename: Some(&local_mod.filename),
+                    mod_id,
+                    version: entry.version.as_deref(),
+                    via_provides: false,
+                });
+            for provided in &entry.provides {
+                providers
+                    .entry(provided.to_ascii_lowercase())
+                    .or_default()
+                    .push(ModProvider {
+                        filename: Some(&local_mod.filename),
+                        mod_id,
+                        version: entry.version.as_deref(),
+                        via_provides: true,
+                    });
+            }
+        }
+    }
+
+    let mut problems = Vec::new();
+    let mut provided = Vec::new();
+    for local_mod in &enabled_mods {
+        for entry in &local_mod.metadata.entries {
+            let Some(mod_id) = entry.mod_id.as_deref() else {
+                continue;
+            };
+            for dependency in &entry.dependencies {
+                let dependency_id = dependency.mod_id.to_ascii_lowercase();
+                let kind = dependency_kind(&entry.loader, dependency);
+                if kind == DependencyKind::Ordering
+                    || dependency_id == "java"
+                    || dependency_id == mod_id.to_ascii_lowercase()
+                    || (dependency_id == "minecraft" && version.game_version.is_none())
+                {
+                    continue;
+                }
+
+                let candidates: Vec<&ModProvider> = providers
+                    .get(&dependency_id)
+                    .map(|candidates| {
+                        candidates
+                            .iter()
+                            .filter(|candidate| {
+                                // A jar never breaks or conflicts with its own bundled mods.
+                                !matches!(kind, DependencyKind::Breaks | DependencyKind::Conflicts)
+                                    || candidate.filename != Some(local_mod.filename.as_str())
+                            })
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                let requirement = dependency.version.as_deref();
+                let matches = |candidate: &ModProvider| match (requirement, candidate.version) {
+                    (Some(requirement), Some(found)) => version_requirement_matches(
+                        VersionSyntax::for_loader(&entry.loader),
+                        requirement,
+                        found,
+                    ),
+                    _ => None,
+                };
+                let problem = |kind: &str, severity: &str, offenders: &[&&ModProvider]| {
+                    LocalModDependencyProblem {
+                        kind: kind.to_string(),
+                        severity: severity.to_string(),
+                        mod_id: mod_id.to_string(),
+                        filename: local_mod.filename.clone(),
+                        dependency_id: dependency.mod_id.clone(),
+                        version_requirement: dependency.version.clone(),
+                        found_versions: offenders
+                            .iter()
+                            .filter_map(|candidate| candidate.version.map(str::to_string))
+                            .collect(),
+                        dependency_filenames: offenders
+                            .iter()
+                            .filter_map(|candidate| candidate.filename.map(str::to_string))
+                            .collect(),
+                    }
+                };
+
+                match kind {
+                    DependencyKind::Required | DependencyKind::Optional => {
+                        if candidates.is_empty() {
+                            if kind == DependencyKind::Required {
+                                problems.push(problem("missing", "error", &[]));
+                            }
+                            continue;
+                        }
+                        // Unknown syntax or versions count as satisfied to avoid false alarms.
+                        let Some(satisfier) = candidates
+                            .iter()
+                            .find(|candidate| matches(candidate) != Some(false))
+                        else {
+                            let severity = if kind == DependencyKind::Required {
+                                "error"
+                            } else {
+                                "warning"
+                            };
+                            let offenders: Vec<&&ModProvider> = candidates.iter().collect();
+                            problems.push(problem("versionMismatch", severity, &offenders));
+                            continue;
+                        };
+                        if let (true, Some(provider_filename)) =
+                            (satisfier.via_provides, satisfier.filename)
+                        {
+                            provided.push(LocalModProvidedDependency {
+                                mod_id: mod_id.to_string(),
+                                filename: local_mod.filename.clone(),
+                                dependency_id: dependency.mod_id.clone(),
+                                provider_mod_id: satisfier.mod_id.to_string(),
+                                provider_filename: provider_filename.to_string(),
+                            });
+                        }
+                    }
+                    DependencyKind::Breaks | DependencyKind::Conflicts => {
+                        let offenders: Vec<&&ModProvider> = candidates
+                            .iter()
+                            .filter(|candidate| {
+                                requirement.is_none() || matches(candidate) == Some(true)
+                            })
+                            .collect();
+                        if offenders.is_empty() {
+                            continue;
+                        }
+                        problems.push(if kind == DependencyKind::Breaks {
+                            problem("breaks", "error", &offenders)
+                        } else {
+                            problem("conflicts", "warning", &offenders)
+                        });
+                    }
+                    DependencyKind::Ordering => {}
+                }
+            }
+        }
+    }
+
+    (problems, provided)
+}
+
+fn analyze_local_mod_dependencies_blocking(
+    game_directory: &str,
+    version_id: &str,
+) -> Result<LocalModDependencyReport, String> {
+    let version = read_instance_version_info(&game_directory_path(game_directory)?, version_id)?;
+    let mods = scan_local_mods_blocking(game_directory)?;
+    let (problems, provided) = analyze_mod_dependencies(&mods, &version);
+    Ok(LocalModDependencyReport {
+        version,
+        problems,
+        provided,
+    })
+}
+
+fn suggested_duplicate_to_keep(providers: &[LocalModDuplicateProvider]) -> Option<String> {
+    let mut newest = providers.first()?;
+    let mut tied = false;
+    for provider in &providers[1..] {
+        match compare_versions(provider.version.as_deref()?, newest.version.as_deref()?)? {
+            cmp::Ordering::Greater => {
+                newest = provider;
+                tied = false;
+            }
+            cmp::Ordering::Equal => tied = true,
+            cmp::Ordering::Less => {}
+        }
+    }
+    (!tied).then(|| newest.filename.clone())
+}
+
+/// Group enabled jars that declare the same mod ID; loaders refuse to start with duplicates.
+fn find_duplicate_mods(mods: &[LocalModFile]) -> Vec<LocalModDuplicateGroup> {
+    let mut groups: BTreeMap<String, (String, Vec<LocalModDuplicateProvider>)> = BTreeMap::new();
+    for local_mod in mods.iter().filter(|local_mod| local_mod.enabled) {
+        for entry in &local_mod.metadata.entries {
+            let Some(mod_id) = entry.mod_id.as_deref() else {
+                continue;
+            };
+            let (_, providers) = groups
+                .entry(mod_id.to_ascii_lowercase())
+                .or_insert_with(|| (mod_id.to_string(), Vec::new()));
+            // A jar shipping both fabric.mod.json and quilt.mod.json still counts once.
+            if providers
+                .iter()
+                .any(|provider| provider.filename == local_mod.filename)
+            {
+                continue;
+            }
+            providers.push(LocalModDuplicateProvider {
+                filename: local_mod.filename.clone(),
+                loader: entry.loader.clone(),
+                version: entry.version.clone(),
+            });
+        }
+    }
+
+    groups
+        .into_values()
+        .filter(|(_, providers)| providers.len() > 1)
+        .map(|(mod_id, providers)| LocalModDuplicateGroup {
+            mod_id,
+            suggested_keep: suggested_duplicate_to_keep(&providers),
+            providers,
+        })
+        .collect()
+}
+
+fn local_mod_icon_blocking(
+    game_directory: &str,
+    filename: &str,
+    size: u32,
+) -> Result<Option<String>, String> {
+    validate_mod_filename(filename)?;
+    let path = mods_directory(game_directory)?.join(filename);
+    let mut file = File::open(&path)
+        .map_err(|error| format!("Failed to open mod file '{}': {}", path.display(), error))?;
+
+    // Keyed by content, so toggles, copies and timestamp-preserving rewrites all resolve correctly.
+    let content_hash = sha1_reader_hex(&mut file)?;
+    file.seek(SeekFrom::Start(0))
+        .map_err(|error| format!("Failed to read mod file '{}': {}", path.display(), error))?;
+    let cache_directory = game_directory_path(game_directory)?
+        .join(LAUNCHER_DIRECTORY_NAME)
+        .join(MOD_ICON_CACHE_DIRECTORY_NAME);
+    let cached_path = cache_directory.join(format!("{content_hash}-{size}.png"));
+    if path_exists(&cached_path)? {
+        return Ok(Some(cached_path.to_string_lossy().into_owned()));
+    }
+
+    let Some(bytes) = read_mod_icon(&mut file, size)? else {
+        return Ok(None);
+    };
+    create_launcher_directory(&cache_directory)?;
+    // Icon reads run without the mods lock, so each writer needs its own temp file.
+    let (temp_path, mut temp_file) = create_import_temp_file(&cache_directory)?;
+    let written = temp_file
+        .write_all(&bytes)
+        .and_then(|()| temp_file.sync_all());
+    drop(temp_file);
+    if let Err(error) = written.and_then(|()| fs::rename(&temp_path, &cached_path)) {
+        return Err(cleanup_import_failure(
+            &temp_path,
+            format!("Failed to cache the mod icon: {error}"),
+        ));
+    }
+    prune_mod_icon_cache(&cache_directory);
+    Ok(Some(cached_path.to_string_lossy().into_owned()))
+}
+
+/// Drop the least recently written icons once the cache holds too many.
+fn prune_mod_icon_cache(cache_directory: &Path) {
+    let Ok(entries) = fs::read_dir(cache_directory) else {
+        return;
+    };
+    let mut icons: Vec<(SystemTime, PathBuf)> = entries
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_name().to_string_lossy().ends_with(".png"))
+        .filter_map(|entry| {
+            let modified = entry.metadata().ok()?.modified().ok()?;
+            Some((modified, entry.path()))
+        })
+        .collect();
+    if icons.len() <= MAX_MOD_ICON_CACHE_ENTRIES {
+        return;
+    }
+    icons.sort();
+    let excess = icons.len() - MAX_MOD_ICON_CACHE_ENTRIES;
+    for (_, path) in icons.into_iter().take(excess) {
+        // Another reader may have pruned it already; the cache only saves time.
+        let _ = remove_file_if_exists(&path);
+    }
+}
+
+fn find_duplicate_local_mods_blocking(
+    game_directory: &str,
+) -> Result<Vec<LocalModDuplicateGroup>, String> {
+    Ok(find_duplicate_mods(&scan_local_mods_blocking(
+        game_directory,
+    )?))
+}
+
+#[tauri::command]
+pub async fn scan_local_mods(
+    app: AppHandle,
+    game_directory: String,
+    scan_id: Option<String>,
+    version_id: Option<String>,
+    side: Option<InstanceSide>,
+) -> Result<Vec<LocalModFile>, String> {
+    // Register before waiting for the lock so a queued scan can be cancelled too.
+    let registration = ModScanRegistration::new(scan_id.as_deref());
+    let operation_guard = MODS_OPERATION_LOCK.lock().await;
+    tokio::task::spawn_blocking(move || {
+        let _operation_guard = operation_guard;
+        // An unreadable version only costs the verdicts, not the scan.
+        let version = version_id.and_then(|version_id| {
+            let directory = game_directory_path(&game_directory).ok()?;
+            read_instance_version_info(&directory, &version_id).ok()
+        });
+        let mut mods = scan_local_mods_with_progress(
+            &game_directory,
+            &registration.cancelled,
+            &|parsed, total, filename| {
+                let _ = app.emit(
+                    MOD_SCAN_PROGRESS_EVENT,
+                    LocalModScanProgress {
+                        scan_id: scan_id.clone(),
+                        parsed,
+                        total,
+                        filename: filename.to_string(),
+                    },
+                );
+            },
+        )?;
+        if let Some(version) = version {
+            attach_mod_compatibility(&mut mods, &version);
+        }
+        if let Some(side) = side {
+            attach_mod_side_warnings(&mut mods, side);
+        }
+        Ok(mods)
+    })
+    .await
+    .map_err(|error| format!("Local mod scan task failed: {error}"))?
+}
+
+/// Start watching an instance's mods directory; returns the ID used to stop it.
+///
+/// Subscribers of the same directory share one watcher and its ID.
+#[tauri::command]
+pub async fn watch_local_mods(app: AppHandle, game_directory: String) -> Result<String, String> {
+    let mods_directory = mods_directory(&game_directory)?;
+    let mods_directory = fs::canonicalize(&mods_directory).unwrap_or(mods_directory);
+    let mut watchers = MOD_WATCHERS
+        .lock()
+        .unwrap_or_else(|error| error.into_inner());
+    if let Some(watcher) = watchers
+        .iter_mut()
+        .find(|watcher| watcher.mods_directory == mods_directory)
+    {
+        watcher.subscribers += 1;
+        return Ok(watcher.watch_id.clone());
+    }
+
+    let watch_id = format!(
+        "mods-watch-{}",
+        MOD_WATCH_COUNTER.fetch_add(1, Ordering::Relaxed)
+    );
+    let stopped = Arc::new(AtomicBool::new(false));
+    let thread_watch_id = watch_id.clone();
+    let thread_directory = mods_directory.clone();
+    let thread_stopped = stopped.clone();
+    std::thread::Builder::new()
+        .name(watch_id.clone())
+        .spawn(move || watch_mods_directory(app, thread_watch_id, thread_directory, thread_stopped))
+        .map_err(|error| format!("Failed to start the mods directory watcher: {error}"))?;
+    watchers.push(ModDirectoryWatcher {
+        watch_id: watch_id.clone(),
+        mods_directory,
+        subscribers: 1,
+        stopped,
+    });
+    Ok(watch_id)
+}
+
+/// Drop one subscription; the watcher stops once its last subscriber is gone.
+#[tauri::command]
+pub async fn unwatch_local_mods(watch_id: String) -> Result<bool, String> {
+    let mut watchers = MOD_WATCHERS
+        .lock()
+        .unwrap_or_else(|error| error.into_inner());
+    let Some(index) = watchers
+        .iter()
+        .position(|watcher| watcher.watch_id == watch_id)
+    else {
+        return Ok(false);
+    };
+    watchers[index].subscribers -= 1;
+    if watchers[index].subscribers == 0 {
+        let watcher = watchers.remove(index);
+        watcher.stopped.store(true, Ordering::Relaxed);
+    }
+    Ok(true)
+}
+
+/// Ask a running or queued scan to stop; returns whether a scan with that ID was found.
+#[tauri::command]
+pub async fn cancel_local_mod_scan(scan_id: String) -> Result<bool, String> {
+    let cancellations = MOD_SCAN_CANCELLATIONS
+        .lock()
+        .unwrap_or_else(|error| error.into_inner());
+    let mut found = false;
+    for (_, cancelled) in cancellations.iter().filter(|(id, _)| *id == scan_id) {
+        cancelled.store(true, Ordering::Relaxed);
+        found = true;
+    }
+    Ok(found)
+}
+
+#[tauri::command]
+pub async fn import_local_mods(
+    game_directory: String,
+    source_paths: Vec<String>,
+) -> Result<LocalModImportResult, String> {
+    let operation_guard = MODS_OPERATION_LOCK.lock().await;
+    tokio::task::spawn_blocking(move || {
+        let _operation_guard = operation_guard;
+        import_local_mods_blocking(&game_directory, source_paths)
+    })
+    .await
+    .map_err(|error| format!("Local mod import task failed: {error}"))?
+}
+
+#[tauri::command]
+pub async fn install_mod_from_url(
+    game_directory: String,
+    download: LocalModDownload,
+) -> Result<LocalModInstallResult, String> {
+    validate_import_filename(&download.filename)?;
+    validate_download_hashes(&download)?;
+    let mods_directory = mods_directory(&game_directory)?;
+    create_mods_directory(&mods_directory)?;
+    // The temp file is invisible to scans, so only publishing needs the operation lock.
+    let (temp_path, hashes) = download_to_import_temp(&download.url, &mods_directory).await?;
+
+    let operation_guard = MODS_OPERATION_LOCK.lock().await;
+    tokio::task::spawn_blocking(move || {
+        let _operation_guard = operation_guard;
+        install_downloaded_mod(&game_directory, &temp_path, &download, &hashes)
+    })
+    .await
+    .map_err(|error| format!("Mod install task failed: {error}"))?
+}
+
+#[tauri::command]
+pub async fn set_local_mod_enabled(
+    game_directory: String,
+    filename: String,
+    enabled: bool,
+) -> Result<LocalModFile, String> {
+    let operation_guard = MODS_OPERATION_LOCK.lock().await;
+    tokio::task::spawn_blocking(move || {
+        let _operation_guard = operation_guard;
+        set_local_mod_enabled_blocking(&game_directory, filename, enabled)
+    })
+    .await
+    .map_err(|error| format!("Local mod state task failed: {error}"))?
+}
+
+#[tauri::command]
+pub async fn set_local_mods_enabled(
+    game_directory: String,
+    filenames: Vec<String>,
+    enabled: bool,
+) -> Result<LocalModStateBatchResult, String> {
+    let operation_guard = MODS_OPERATION_LOCK.lock().await;
+    tokio::task::spawn_blocking(move || {
+        let _operation_guard = operation_guard;
+        set_local_mods_enabled_blocking(&game_directory, filenames, enabled)
+    })
+    .await
+    .map_err(|error| format!("Local mod state task failed: {error}"))?
+}
+
+/// Disable the mods a side check would warn about for a `side` instance.
+#[tauri::command]
+pub async fn disable_other_side_mods(
+    game_directory: String,
+    side: InstanceSide,
+) -> Result<LocalModStateBatchResult, String> {
+    let operation_guard = MODS_OPERATION_LOCK.lock().await;
+    tokio::task::spawn_blocking(move || {
+        let _operation_guard = operation_guard;
+        disable_other_side_mods_blocking(&game_directory, side)
+    })
+    .await
+    .map_err(|error| format!("Local mod state task failed: {error}"))?
+}
+
+#[tauri::command]
+pub async fn delete_local_mods(
+    game_directory: String,
+    filenames: Vec<String>,
+) -> Result<LocalModDeleteResult, String> {
+    let operation_guard = MODS_OPERATION_LOCK.lock().await;
+    tokio::task::spawn_blocking(move || {
+        let _operation_guard = operation_guard;
+        delete_local_mods_blocking(&game_directory, filenames)
+    })
+    .await
+    .map_err(|error| format!("Local mod delete task failed: {error}"))?
+}
+
+#[tauri::command]
+pub async fn list_trashed_local_mods(
+    game_directory: String,
+) -> Result<Vec<TrashedLocalMod>, String> {
+    let operation_guard = MODS_OPERATION_LOCK.lock().await;
+    tokio::task::spawn_blocking(move || {
+        let _operation_guard = operation_guard;
+        list_trashed_local_mods_blocking(&game_directory)
+    })
+    .await
+    .map_err(|error| format!("Mod trash listing task failed: {error}"))?
+}
+
+#[tauri::command]
+pub async fn restore_trashed_local_mods(
+    game_directory: String,
+    ids: Vec<String>,
+) -> Result<LocalModRestoreResult, String> {
+    let operation_guard = MODS_OPERATION_LOCK.lock().await;
+    tokio::task::spawn_blocking(move || {
+        let _operation_guard = operation_guard;
+        restore_trashed_local_mods_blocking(&game_directory, ids)
+    })
+    .await
+    .map_err(|error| format!("Mod restore task failed: {error}"))?
+}
+
+#[tauri::command]
+pub async fn list_mod_profiles(game_directory: String) -> Result<Vec<LocalModProfile>, String> {
+    let operation_guard = MODS_OPERATION_LOCK.lock().await;
+    tokio::task::spawn_blocking(move || {
+        let _operation_guard = operation_guard;
+        list_mod_profiles_blocking(&game_directory)
+    })
+    .await
+    .map_err(|error| format!("Mod profile listing task failed: {error}"))?
+}
+
+#[tauri::command]
+pub async fn save_mod_profile(
+    game_directory: String,
+    name: String,
+    mods: Option<Vec<String>>,
+) -> Result<LocalModProfile, String> {
+    let operation_guard = MODS_OPERATION_LOCK.lock().await;
+    tokio::task::spawn_blocking(move || {
+        let _operation_guard = operation_guard;
+        save_mod_profile_blocking(&game_directory, &name, mods)
+    })
+    .await
+    .map_err(|error| format!("Mod profile save task failed: {error}"))?
+}
+
+#[tauri::command]
+pub async fn delete_mod_profile(game_directory: String, name: String) -> Result<bool, String> {
+    let operation_guard = MODS_OPERATION_LOCK.lock().await;
+    tokio::task::spawn_blocking(move || {
+        let _operation_guard = operation_guard;
+        delete_mod_profile_blocking(&game_directory, &name)
+    })
+    .await
+    .map_err(|error| format!("Mod profile delete task failed: {error}"))?
+}
+
+#[tauri::command]
+pub async fn apply_mod_profile(
+    game_directory: String,
+    name: String,
+) -> Result<LocalModProfileApplyResult, String> {
+    let operation_guard = MODS_OPERATION_LOCK.lock().await;
+    tokio::task::spawn_blocking(move || {
+        let _operation_guard = operation_guard;
+        apply_mod_profile_blocking(&game_directory, &name)
+    })
+    .await
+    .map_err(|error| format!("Mod profile apply task failed: {error}"))?
+}
+
+#[tauri::command]
+pub async fn get_mod_bisect(
+    game_directory: String,
+) -> Result<Option<LocalModBisectSession>, String> {
+    let operation_guard = MODS_OPERATION_LOCK.lock().await;
+    tokio::task::spawn_blocking(move || {
+        let _operation_guard = operation_guard;
+        read_mod_bisect(&mod_bisect_path(&game_directory)?)
+    })
+    .await
+    .map_err(|error| format!("Mod bisection task failed: {error}"))?
+}
+
+/// Start bisecting `suspects`, or every enabled mod, and set up the first launch.
+#[tauri::command]
+pub async fn start_mod_bisect(
+    game_directory: String,
+    suspects: Option<Vec<String>>,
+) -> Result<LocalModBisectSession, String> {
+    let operation_guard = MODS_OPERATION_LOCK.lock().await;
+    tokio::task::spawn_blocking(move || {
+        let _operation_guard = operation_guard;
+        start_mod_bisect_blocking(&game_directory, suspects)
+    })
+    .await
+    .map_err(|error| format!("Mod bisection task failed: {error}"))?
+}
+
+#[tauri::command]
+pub async fn record_mod_bisect_result(
+    game_directory: String,
+    works: bool,
+) -> Result<LocalModBisectSession, String> {
+    let operation_guard = MODS_OPERATION_LOCK.lock().await;
+    tokio::task::spawn_blocking(move || {
+        let _operation_guard = operation_guard;
+        record_mod_bisect_result_blocking(&game_directory, works)
+    })
+    .await
+    .map_err(|error| format!("Mod bisection task failed: {error}"))?
+}
+
+/// Restore the original mods and forget the session; `false` when none was running.
+#[tauri::command]
+pub async fn cancel_mod_bisect(game_directory: String) -> Result<bool, String> {
+    let operation_guard = MODS_OPERATION_LOCK.lock().await;
+    tokio::task::spawn_blocking(move || {
+        let _operation_guard = operation_guard;
+        cancel_mod_bisect_blocking(&game_directory)
+    })
+    .await
+    .map_err(|error| format!("Mod bisection task failed: {error}"))?
+}
+
+#[tauri::command]
+pub async fn analyze_local_mod_dependencies(
+    game_directory: String,
+    version_id: String,
+) -> Result<LocalModDependencyReport, String> {
+    let operation_guard = MODS_OPERATION_LOCK.lock().await;
+    tokio::task::spawn_blocking(move || {
+        let _operation_guard = operation_guard;
+        analyze_local_mod_dependencies_blocking(&game_directory, &version_id)
+    })
+    .await
+    .map_err(|error| format!("Mod dependency analysis task failed: {error}"))?
+}
+
+#[tauri::command]
+pub async fn find_duplicate_local_mods(
+    game_directory: String,
+) -> Result<Vec<LocalModDuplicateGroup>, String> {
+    let operation_guard = MODS_OPERATION_LOCK.lock().await;
+    tokio::task::spawn_blocking(move || {
+        let _operation_guard = operation_guard;
+        find_duplicate_local_mods_blocking(&game_directory)
+    })
+    .await
+    .map_err(|error| format!("Duplicate mod scan task failed: {error}"))?
+}
+
+#[tauri::command]
+pub async fn get_local_mod_icon(
+    game_directory: String,
+    filename: String,
+    size: Option<u32>,
+) -> Result<Option<String>, String> {
+    // Icons only read the JAR, so they do not wait behind mod operations.
+    tokio::task::spawn_blocking(move || {
+        local_mod_icon_blocking(
+            &game_directory,
+            &filename,
+            size.unwrap_or(DEFAULT_MOD_ICON_SIZE),
+        )
+    })
+    .await
+    .map_err(|error| format!("Mod icon task failed: {error}"))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::archive::{
+        ZIP_CENTRAL_DIRECTORY_HEADER_SIZE, ZIP_EOCD_MIN_SIZE, ZIP_LOCAL_FILE_HEADER_SIZE,
+    };
+    use crate::core::test_fixtures::{temp_game_directory, zip_bytes};
+    use std::fs::OpenOptions;
+    use std::io::Write;
+    use zip::write::SimpleFileOptions;
+
+    #[test]
+    fn parses_fabric_metadata() {
+        let entries = parse_fabric_metadata(
+            r#"{
+                "id": "example",
+                "version": "1.2.3",
+                "name": "Example Mod",
                 "authors": ["Alice", {"name": "Bob"}],
                 "environment": "*",
                 "depends": {
@@ -2085,641 +5068,1825 @@ mod tests {
         )
         .expect("Fabric metadata should parse");
 
-        let entry = &entries[0];
-        assert_eq!(entry.mod_id.as_deref(), Some("example"));
-        assert_eq!(entry.name.as_deref(), Some("Example Mod"));
-        assert_eq!(entry.authors, ["Alice", "Bob"]);
-        assert_eq!(entry.loader_version.as_deref(), Some(">=0.16.0"));
-        assert_eq!(entry.game_version.as_deref(), Some(r#"["1.21","1.21.1"]"#));
-        assert_eq!(entry.dependencies.len(), 3);
+        let entry = &entries[0];
+        assert_eq!(entry.mod_id.as_deref(), Some("example"));
+        assert_eq!(entry.name.as_deref(), Some("Example Mod"));
+        assert_eq!(entry.authors, ["Alice", "Bob"]);
+        assert_eq!(entry.loader_version.as_deref(), Some(">=0.16.0"));
+        assert_eq!(entry.game_version.as_deref(), Some(r#"["1.21","1.21.1"]"#));
+        assert_eq!(entry.dependencies.len(), 3);
+    }
+
+    #[test]
+    fn parses_quilt_metadata_without_dropping_roles_or_complex_constraints() {
+        let entries = parse_quilt_metadata(
+            r#"{
+                "quilt_loader": {
+                    "id": "quilt-example",
+                    "version": "2.0.0",
+                    "metadata": {
+                        "name": "Quilt Example",
+                        "contributors": {
+                            "Alice": ["Owner", "Developer"]
+                        }
+                    },
+                    "depends": [
+                        {"id": "quilt_loader", "versions": ">=0.28.0"},
+                        {
+                            "id": "minecraft",
+                            "versions": {"any": [">=1.20", "<1.22"]},
+                            "unless": {"id": "compat-layer"},
+                            "optional": false
+                        }
+                    ]
+                },
+                "minecraft": {"environment": "client"}
+            }"#,
+            "quilt.mod.json",
+        )
+        .expect("Quilt metadata should parse");
+
+        let entry = &entries[0];
+        assert_eq!(entry.authors, ["Alice (Owner, Developer)"]);
+        assert_eq!(entry.loader_version.as_deref(), Some(">=0.28.0"));
+        assert_eq!(entry.environment.as_deref(), Some("client"));
+        let minecraft = entry
+            .dependencies
+            .iter()
+            .find(|dependency| dependency.mod_id == "minecraft")
+            .expect("Minecraft dependency should be present");
+        let constraint = minecraft
+            .version
+            .as_deref()
+            .expect("Complex constraint should be retained");
+        assert!(constraint.contains(r#""versions""#));
+        assert!(constraint.contains(r#""unless""#));
+        assert_eq!(minecraft.required, Some(true));
+    }
+
+    #[test]
+    fn parses_forge_metadata_and_resolves_manifest_version() {
+        let entries = parse_forge_metadata(
+            r#"
+                modLoader = "javafml"
+                loaderVersion = "[47,)"
+
+                [[mods]]
+                modId = "forge_example"
+                version = "${file.jarVersion}"
+                displayName = "Forge Example"
+                authors = "Alice, Bob"
+
+                [[dependencies.forge_example]]
+                modId = "minecraft"
+                mandatory = true
+                versionRange = "[1.20.1,1.21)"
+                ordering = "NONE"
+                side = "BOTH"
+
+                [[dependencies.forge_example]]
+                modId = "forge"
+                mandatory = true
+                versionRange = "[47,)"
+                ordering = "NONE"
+                side = "BOTH"
+            "#,
+            "META-INF/mods.toml",
+            "Forge / NeoForge",
+            Some("3.4.5"),
+        )
+        .expect("Forge metadata should parse");
+
+        let entry = &entries[0];
+        assert_eq!(entry.loader, "Forge / NeoForge");
+        assert_eq!(entry.version.as_deref(), Some("3.4.5"));
+        assert_eq!(entry.loader_version.as_deref(), Some("[47,)"));
+        assert_eq!(entry.language_loader.as_deref(), Some("javafml"));
+        assert_eq!(entry.language_loader_version.as_deref(), Some("[47,)"));
+        assert_eq!(entry.game_version.as_deref(), Some("[1.20.1,1.21)"));
+        assert_eq!(entry.dependencies[0].required, Some(true));
+        assert_eq!(entry.dependencies[0].side.as_deref(), Some("BOTH"));
+        assert_eq!(entry.dependencies[0].ordering.as_deref(), Some("NONE"));
+    }
+
+    #[test]
+    fn parses_neoforge_dependency_type() {
+        let entries = parse_forge_metadata(
+            r#"
+                modLoader = "javafml"
+                loaderVersion = "[1,)"
+
+                [[mods]]
+                modId = "neo_example"
+                version = "1.0.0"
+                displayName = "NeoForge Example"
+
+                [[dependencies.neo_example]]
+                modId = "neoforge"
+                type = "required"
+                versionRange = "[21.1,)"
+                side = "BOTH"
+            "#,
+            "META-INF/neoforge.mods.toml",
+            "NeoForge",
+            None,
+        )
+        .expect("NeoForge metadata should parse");
+
+        let dependency = &entries[0].dependencies[0];
+        assert_eq!(entries[0].loader_version.as_deref(), Some("[21.1,)"));
+        assert_eq!(entries[0].language_loader.as_deref(), Some("javafml"));
+        assert_eq!(entries[0].language_loader_version.as_deref(), Some("[1,)"));
+        assert_eq!(dependency.relation, "required");
+        assert_eq!(dependency.required, Some(true));
+        assert_eq!(dependency.version.as_deref(), Some("[21.1,)"));
+    }
+
+    #[test]
+    fn parses_legacy_mcmod_metadata() {
+        let entries = parse_mcmod_metadata(
+            r#"[{
+                "modid": "legacy_example",
+                "name": "Legacy Example",
+                "version": "1.0",
+                "mcversion": "1.12.2",
+                "authorList": ["Alice"],
+                "dependencies": [
+                    "required-after:Forge@[14.23.5,)",
+                    "after:another_mod"
+                ]
+            }]"#,
+            "mcmod.info",
+        )
+        .expect("mcmod.info should parse");
+
+        let entry = &entries[0];
+        assert_eq!(entry.mod_id.as_deref(), Some("legacy_example"));
+        assert_eq!(entry.game_version.as_deref(), Some("1.12.2"));
+        assert_eq!(entry.loader_version.as_deref(), Some("[14.23.5,)"));
+        assert_eq!(entry.dependencies[0].relation, "required-after");
+    }
+
+    #[test]
+    fn unfolds_manifest_continuations() {
+        let manifest = "Manifest-Version: 1.0\r\nImplementation-Version: 1.2.\r\n 3-beta\r\n\r\n";
+        assert_eq!(
+            manifest_implementation_version(manifest).as_deref(),
+            Some("1.2.3-beta")
+        );
+    }
+
+    #[test]
+    fn corrupt_jar_returns_a_diagnostic_instead_of_an_error() {
+        let filename = format!(
+            "epherome-corrupt-mod-{}-{}.jar",
+            std::process::id(),
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_nanos()
+        );
+        let path = std::env::temp_dir().join(filename);
+        fs::write(&path, b"not a zip archive").expect("Temporary corrupt JAR should be written");
+
+        let metadata = parse_mod_metadata(&path);
+        let _ = fs::remove_file(path);
+
+        assert!(metadata.entries.is_empty());
+        assert_eq!(metadata.diagnostics.len(), 1);
+        assert!(metadata.diagnostics[0].contains("inspection"));
+    }
+
+    #[test]
+    fn reads_metadata_from_a_disabled_jar_archive() {
+        let filename = format!(
+            "epherome-metadata-mod-{}-{}.jar.disabled",
+            std::process::id(),
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_nanos()
+        );
+        let path = std::env::temp_dir().join(&filename);
+        let file = File::create(&path).expect("Temporary JAR should be created");
+        let mut archive = zip::ZipWriter::new(file);
+        archive
+            .start_file("fabric.mod.json", SimpleFileOptions::default())
+            .expect("Metadata entry should be created");
+        archive
+            .write_all(
+                br#"{
+                    "id": "archive_example",
+                    "name": "Archive Example",
+                    "version": "1.0.0",
+                    "authors": ["Alice"],
+                    "depends": {"minecraft": "1.21.1"}
+                }"#,
+            )
+            .expect("Metadata should be written");
+        archive.finish().expect("Temporary JAR should be finished");
+
+        let local_mod =
+            local_mod_file(&path, filename, false).expect("The disabled JAR should be inspected");
+        let _ = fs::remove_file(path);
+
+        assert!(!local_mod.enabled);
+        assert!(local_mod.metadata.diagnostics.is_empty());
+        assert_eq!(local_mod.metadata.entries.len(), 1);
+        assert_eq!(
+            local_mod.metadata.entries[0].mod_id.as_deref(),
+            Some("archive_example")
+        );
+    }
+
+    #[test]
+    fn reads_metadata_from_a_deflated_jar_archive() {
+        let filename = format!(
+            "epherome-deflated-metadata-mod-{}-{}.jar",
+            std::process::id(),
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_nanos()
+        );
+        let path = std::env::temp_dir().join(&filename);
+        let file = File::create(&path).expect("Temporary JAR should be created");
+        let mut archive = zip::ZipWriter::new(file);
+        archive
+            .start_file(
+                "fabric.mod.json",
+                SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated),
+            )
+            .expect("Deflated metadata entry should be created");
+        archive
+            .write_all(
+                br#"{
+                    "id": "deflated_example",
+                    "name": "Deflated Example",
+                    "version": "1.0.0"
+                }"#,
+            )
+            .expect("Deflated metadata should be written");
+        archive.finish().expect("Temporary JAR should be finished");
+
+        let metadata = parse_mod_metadata(&path);
+        let _ = fs::remove_file(path);
+
+        assert!(metadata.diagnostics.is_empty());
+        assert_eq!(metadata.entries.len(), 1);
+        assert_eq!(
+            metadata.entries[0].mod_id.as_deref(),
+            Some("deflated_example")
+        );
     }
 
     #[test]
-    fn parses_quilt_metadata_without_dropping_roles_or_complex_constraints() {
-        let entries = parse_quilt_metadata(
-            r#"{
-                "quilt_loader": {
-                    "id": "quilt-example",
-                    "version": "2.0.0",
-                    "metadata": {
-                        "name": "Quilt Example",
-                        "contributors": {
-                            "Alice": ["Owner", "Developer"]
-                        }
-                    },
-                    "depends": [
-                        {"id": "quilt_loader", "versions": ">=0.28.0"},
-                        {
-                            "id": "minecraft",
-                            "versions": {"any": [">=1.20", "<1.22"]},
-                            "unless": {"id": "compat-layer"},
-                            "optional": false
-                        }
-                    ]
-                },
-                "minecraft": {"environment": "client"}
-            }"#,
-            "quilt.mod.json",
-        )
-        .expect("Quilt metadata should parse");
+    fn duplicate_metadata_paths_are_reported_instead_of_selecting_one() {
+        let filename = format!(
+            "epherome-duplicate-metadata-mod-{}-{}.jar",
+            std::process::id(),
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_nanos()
+        );
+        let path = std::env::temp_dir().join(&filename);
+        let entry_name = b"fabric.mod.json";
+        let contents = [
+            br#"{"id":"first_example","version":"1.0.0"}"#.as_slice(),
+            br#"{"id":"second_example","version":"1.0.0"}"#.as_slice(),
+        ];
+        let mut bytes = Vec::new();
+        let mut local_offsets = Vec::new();
+        for content in contents {
+            let local_offset = u32::try_from(bytes.len()).expect("Fixture local offset should fit");
+            local_offsets.push((local_offset, content.len() as u32));
+            let mut local_header = [0_u8; ZIP_LOCAL_FILE_HEADER_SIZE as usize];
+            local_header[0..4].copy_from_slice(b"PK\x03\x04");
+            local_header[4..6].copy_from_slice(&20_u16.to_le_bytes());
+            local_header[18..22].copy_from_slice(&(content.len() as u32).to_le_bytes());
+            local_header[22..26].copy_from_slice(&(content.len() as u32).to_le_bytes());
+            local_header[26..28].copy_from_slice(&(entry_name.len() as u16).to_le_bytes());
+            bytes.extend_from_slice(&local_header);
+            bytes.extend_from_slice(entry_name);
+            bytes.extend_from_slice(content);
+        }
 
-        let entry = &entries[0];
-        assert_eq!(entry.authors, ["Alice (Owner, Developer)"]);
-        assert_eq!(entry.loader_version.as_deref(), Some(">=0.28.0"));
-        assert_eq!(entry.environment.as_deref(), Some("client"));
-        let minecraft = entry
-            .dependencies
+        let central_offset = u32::try_from(bytes.len()).expect("Fixture central offset should fit");
+        for (local_offset, content_length) in local_offsets {
+            let mut central_header = [0_u8; ZIP_CENTRAL_DIRECTORY_HEADER_SIZE as usize];
+            central_header[0..4].copy_from_slice(b"PK\x01\x02");
+            central_header[4..6].copy_from_slice(&20_u16.to_le_bytes());
+            central_header[6..8].copy_from_slice(&20_u16.to_le_bytes());
+            central_header[20..24].copy_from_slice(&content_length.to_le_bytes());
+            central_header[24..28].copy_from_slice(&content_length.to_le_bytes());
+            central_header[28..30].copy_from_slice(&(entry_name.len() as u16).to_le_bytes());
+            central_header[42..46].copy_from_slice(&local_offset.to_le_bytes());
+            bytes.extend_from_slice(&central_header);
+            bytes.extend_from_slice(entry_name);
+        }
+        let central_size = u32::try_from(bytes.len())
+            .expect("Fixture size should fit")
+            .checked_sub(central_offset)
+            .expect("Fixture central size should not underflow");
+        let mut end_record = [0_u8; ZIP_EOCD_MIN_SIZE as usize];
+        end_record[0..4].copy_from_slice(b"PK\x05\x06");
+        end_record[8..10].copy_from_slice(&2_u16.to_le_bytes());
+        end_record[10..12].copy_from_slice(&2_u16.to_le_bytes());
+        end_record[12..16].copy_from_slice(&central_size.to_le_bytes());
+        end_record[16..20].copy_from_slice(&central_offset.to_le_bytes());
+        bytes.extend_from_slice(&end_record);
+        fs::write(&path, bytes).expect("Duplicate metadata fixture should be written");
+
+        let metadata = parse_mod_metadata(&path);
+        let _ = fs::remove_file(path);
+
+        assert!(metadata.entries.is_empty());
+        assert_eq!(metadata.diagnostics.len(), 1);
+        assert!(metadata.diagnostics[0].contains("ambiguous duplicate 'fabric.mod.json' entries"));
+    }
+
+    #[test]
+    fn trailing_fake_eocd_does_not_fallback_to_an_earlier_large_descriptor() {
+        let filename = format!(
+            "epherome-fake-eocd-{}-{}.jar",
+            std::process::id(),
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_nanos()
+        );
+        let path = std::env::temp_dir().join(filename);
+        let file = File::create(&path).expect("Temporary JAR should be created");
+        let mut archive = zip::ZipWriter::new(file);
+        archive
+            .start_file("fabric.mod.json", SimpleFileOptions::default())
+            .expect("Earlier metadata entry should be created");
+        let oversized_metadata = vec![b' '; MAX_METADATA_FILE_SIZE as usize + 1];
+        archive
+            .write_all(&oversized_metadata)
+            .expect("Earlier oversized metadata should be written");
+        archive.finish().expect("Earlier ZIP should be finished");
+
+        let mut fake_end_record = [0_u8; ZIP_EOCD_MIN_SIZE as usize];
+        fake_end_record[0..4].copy_from_slice(b"PK\x05\x06");
+        fake_end_record[8..10].copy_from_slice(&1_u16.to_le_bytes());
+        fake_end_record[12..16].copy_from_slice(&1_u32.to_le_bytes());
+        fake_end_record[16..20].copy_from_slice(&0_u32.to_le_bytes());
+        let mut file = OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .expect("Temporary JAR should reopen for the fake end record");
+        file.write_all(&fake_end_record)
+            .expect("Fake trailing end record should be written");
+        drop(file);
+
+        let metadata = parse_mod_metadata(&path);
+        let _ = fs::remove_file(path);
+
+        assert!(metadata.entries.is_empty());
+        assert_eq!(
+            metadata.diagnostics,
+            ["No supported mod metadata descriptor was found in the JAR."]
+        );
+    }
+
+    #[test]
+    fn bounds_repeated_forge_metadata_text_before_ipc() {
+        let mods = (0..MAX_METADATA_ENTRIES_PER_DESCRIPTOR)
+            .map(|_| {
+                r#"
+                    [[mods]]
+                    modId = "same_id"
+                    version = "1.0.0"
+                    displayName = "Repeated Mod"
+                "#
+            })
+            .collect::<String>();
+        let long_version = "x".repeat(MAX_METADATA_FIELD_BYTES * 2);
+        let text = format!(
+            r#"
+                modLoader = "javafml"
+                loaderVersion = "[1,)"
+                {mods}
+
+                [[dependencies.same_id]]
+                modId = "neoforge"
+                type = "required"
+                versionRange = "{long_version}"
+            "#
+        );
+        let entries = parse_forge_metadata(&text, "META-INF/neoforge.mods.toml", "NeoForge", None)
+            .expect("Repeated Forge metadata should parse");
+
+        assert!(entries.iter().all(|entry| {
+            entry.dependencies[0]
+                .version
+                .as_deref()
+                .is_some_and(|version| version.len() <= MAX_METADATA_FIELD_BYTES)
+        }));
+
+        let mut metadata = LocalModMetadata::default();
+        append_metadata_entries(&mut metadata, "META-INF/neoforge.mods.toml", entries);
+        let serialized = serde_json::to_vec(&metadata.entries).expect("Metadata should serialize");
+
+        assert!(serialized.len() <= MAX_METADATA_SERIALIZED_BYTES_PER_JAR);
+        assert!(metadata
+            .diagnostics
             .iter()
-            .find(|dependency| dependency.mod_id == "minecraft")
-            .expect("Minecraft dependency should be present");
-        let constraint = minecraft
-            .version
-            .as_deref()
-            .expect("Complex constraint should be retained");
-        assert!(constraint.contains(r#""versions""#));
-        assert!(constraint.contains(r#""unless""#));
-        assert_eq!(minecraft.required, Some(true));
+            .any(|diagnostic| diagnostic.contains("serialized data limit")));
     }
 
     #[test]
-    fn parses_forge_metadata_and_resolves_manifest_version() {
-        let entries = parse_forge_metadata(
+    fn deleted_mods_move_to_the_trash_and_restore() {
+        let game_directory = temp_game_directory("mod-trash");
+        let game = game_directory.to_string_lossy().to_string();
+        fs::write(game_directory.join("mods/example.jar"), b"jar")
+            .expect("Fixture mod should be written");
+        fs::write(game_directory.join("mods/other.jar.disabled"), b"jar")
+            .expect("Fixture mod should be written");
+
+        let deleted = delete_local_mods_blocking(
+            &game,
+            vec![
+                "example.jar".to_string(),
+                "other.jar.disabled".to_string(),
+                "../escape.jar".to_string(),
+            ],
+        )
+        .expect("Deletion should run");
+        assert_eq!(deleted.deleted.len(), 2);
+        assert_eq!(deleted.failed.len(), 1);
+        assert!(!game_directory.join("mods/example.jar").exists());
+
+        let trashed = list_trashed_local_mods_blocking(&game).expect("Trash should be listed");
+        assert_eq!(trashed.len(), 2);
+        let ids = trashed.iter().map(|entry| entry.id.clone()).collect();
+        let restored = restore_trashed_local_mods_blocking(&game, ids).expect("Restore should run");
+        let _ = fs::remove_dir_all(&game_directory);
+
+        assert!(restored.failed.is_empty());
+        assert_eq!(restored.restored.len(), 2);
+        assert!(restored
+            .restored
+            .contains(&"other.jar.disabled".to_string()));
+    }
+
+    #[test]
+    fn restoring_a_mod_never_overwrites_an_installed_one() {
+        let game_directory = temp_game_directory("mod-trash-conflict");
+        let game = game_directory.to_string_lossy().to_string();
+        fs::write(game_directory.join("mods/example.jar"), b"old")
+            .expect("Fixture mod should be written");
+        let deleted = delete_local_mods_blocking(&game, vec!["example.jar".to_string()])
+            .expect("Deletion should run");
+        fs::write(game_directory.join("mods/example.jar.disabled"), b"new")
+            .expect("Replacement mod should be written");
+
+        let restored =
+            restore_trashed_local_mods_blocking(&game, vec![deleted.deleted[0].id.clone()])
+                .expect("Restore should run");
+        let trashed = list_trashed_local_mods_blocking(&game).expect("Trash should be listed");
+        let replacement = fs::read(game_directory.join("mods/example.jar.disabled"));
+        let _ = fs::remove_dir_all(&game_directory);
+
+        assert!(restored.restored.is_empty());
+        assert!(restored.failed[0].reason.contains("same name"));
+        assert_eq!(trashed.len(), 1);
+        assert_eq!(replacement.ok().as_deref(), Some(b"new".as_slice()));
+    }
+
+    fn fabric_mod(filename: &str, json: &str) -> LocalModFile {
+        LocalModFile {
+            filename: filename.to_string(),
+            enabled: true,
+            size: 0,
+            metadata: LocalModMetadata {
+                entries: parse_fabric_metadata(json, "fabric.mod.json")
+                    .expect("Fixture metadata should parse"),
+                diagnostics: Vec::new(),
+                nested: Vec::new(),
+            },
+            curseforge_fingerprint: None,
+            modrinth: None,
+            curseforge: None,
+            compatibility: None,
+            side_warnings: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn flags_mods_for_another_loader_or_game_version() {
+        let instance =
+            |loader: &str, game_version: &str, loader_version: &str| InstanceVersionInfo {
+                version_id: format!("{loader}-{game_version}"),
+                game_version: Some(game_version.to_string()),
+                loader: (!loader.is_empty()).then(|| loader.to_string()),
+                loader_version: Some(loader_version.to_string()),
+                main_class: None,
+            };
+        let mut forge = fabric_mod("forge.jar", "{}");
+        forge.metadata.entries = parse_forge_metadata(
             r#"
                 modLoader = "javafml"
                 loaderVersion = "[47,)"
 
                 [[mods]]
                 modId = "forge_example"
-                version = "${file.jarVersion}"
-                displayName = "Forge Example"
-                authors = "Alice, Bob"
 
                 [[dependencies.forge_example]]
                 modId = "minecraft"
                 mandatory = true
                 versionRange = "[1.20.1,1.21)"
-                ordering = "NONE"
-                side = "BOTH"
 
                 [[dependencies.forge_example]]
                 modId = "forge"
                 mandatory = true
-                versionRange = "[47,)"
-                ordering = "NONE"
-                side = "BOTH"
+                versionRange = "[47.1,)"
             "#,
             "META-INF/mods.toml",
             "Forge / NeoForge",
-            Some("3.4.5"),
+            None,
         )
-        .expect("Forge metadata should parse");
+        .expect("Fixture metadata should parse");
+        let mut unreadable = fabric_mod("unreadable.jar", "{}");
+        unreadable.metadata.entries.clear();
+        let mut mods = [
+            fabric_mod(
+                "fabric.jar",
+                r#"{"id": "fabric_example", "depends": {"minecraft": "~1.20.1", "fabricloader": ">=0.15"}}"#,
+            ),
+            forge,
+            unreadable,
+        ];
+        let verdicts = |mods: &[LocalModFile]| -> Vec<(String, Vec<LocalModCompatibilityIssue>)> {
+            mods.iter()
+                .map(|local_mod| {
+                    let compatibility = local_mod
+                        .compatibility
+                        .clone()
+                        .expect("Compatibility should be attached");
+                    (compatibility.verdict, compatibility.issues)
+                })
+                .collect()
+        };
+        let issue = |kind: &str, required: &str, found: Option<&str>| LocalModCompatibilityIssue {
+            kind: kind.to_string(),
+            required: required.to_string(),
+            found: found.map(str::to_string),
+        };
+
+        attach_mod_compatibility(&mut mods, &instance("Fabric", "1.21.1", "0.14.21"));
+        assert_eq!(
+            verdicts(&mods),
+            [
+                (
+                    "incompatible".to_string(),
+                    vec![
+                        issue("loaderVersion", ">=0.15", Some("0.14.21")),
+                        issue("gameVersion", "~1.20.1", Some("1.21.1")),
+                    ]
+                ),
+                (
+                    "incompatible".to_string(),
+                    vec![issue("loader", "Forge / NeoForge", Some("Fabric"))]
+                ),
+                ("unknown".to_string(), Vec::new()),
+            ]
+        );
 
-        let entry = &entries[0];
-        assert_eq!(entry.loader, "Forge / NeoForge");
-        assert_eq!(entry.version.as_deref(), Some("3.4.5"));
-        assert_eq!(entry.loader_version.as_deref(), Some("[47,)"));
-        assert_eq!(entry.language_loader.as_deref(), Some("javafml"));
-        assert_eq!(entry.language_loader_version.as_deref(), Some("[47,)"));
-        assert_eq!(entry.game_version.as_deref(), Some("[1.20.1,1.21)"));
-        assert_eq!(entry.dependencies[0].required, Some(true));
-        assert_eq!(entry.dependencies[0].side.as_deref(), Some("BOTH"));
-        assert_eq!(entry.dependencies[0].ordering.as_deref(), Some("NONE"));
+        attach_mod_compatibility(&mut mods, &instance("Forge", "1.20.1", "47.2.0"));
+        assert_eq!(verdicts(&mods)[1], ("compatible".to_string(), Vec::new()));
+        attach_mod_compatibility(&mut mods, &instance("NeoForge", "1.20.1", "47.1.106"));
+        assert_eq!(verdicts(&mods)[1], ("compatible".to_string(), Vec::new()));
+        attach_mod_compatibility(&mut mods, &instance("NeoForge", "1.21.1", "21.1.77"));
+        assert_eq!(verdicts(&mods)[1].1[0].kind, "loader");
+        attach_mod_compatibility(&mut mods, &instance("Quilt", "1.20.1", "0.26.0"));
+        assert_eq!(verdicts(&mods)[0], ("compatible".to_string(), Vec::new()));
+        attach_mod_compatibility(&mut mods, &instance("", "1.20.1", ""));
+        assert_eq!(verdicts(&mods)[0].1, [issue("loader", "Fabric", None)]);
+
+        let legacy = |filename: &str, info: &str| {
+            let mut legacy = fabric_mod(filename, "{}");
+            legacy.metadata.entries =
+                parse_mcmod_metadata(info, "mcmod.info").expect("Fixture metadata should parse");
+            legacy
+        };
+        let mut legacy_mods = [
+            legacy(
+                "legacy.jar",
+                r#"[{"modid": "legacy", "mcversion": "1.7.10"}]"#,
+            ),
+            legacy("unversioned.jar", r#"[{"modid": "unversioned"}]"#),
+        ];
+        attach_mod_compatibility(&mut legacy_mods, &instance("Forge", "1.7.10", "10.13.4"));
+        assert_eq!(
+            verdicts(&legacy_mods),
+            [
+                ("compatible".to_string(), Vec::new()),
+                ("unknown".to_string(), Vec::new()),
+            ]
+        );
+        attach_mod_compatibility(&mut legacy_mods, &instance("Forge", "1.12.2", "14.23.5"));
+        assert_eq!(
+            verdicts(&legacy_mods)[0],
+            (
+                "incompatible".to_string(),
+                vec![issue("gameVersion", "1.7.10", Some("1.12.2"))]
+            )
+        );
     }
 
     #[test]
-    fn parses_neoforge_dependency_type() {
-        let entries = parse_forge_metadata(
+    fn warns_about_mods_and_dependencies_for_the_other_side() {
+        let mut forge = fabric_mod("forge.jar", "{}");
+        forge.metadata.entries = parse_forge_metadata(
             r#"
                 modLoader = "javafml"
-                loaderVersion = "[1,)"
+                loaderVersion = "[47,)"
 
                 [[mods]]
-                modId = "neo_example"
-                version = "1.0.0"
-                displayName = "NeoForge Example"
+                modId = "forge_example"
 
-                [[dependencies.neo_example]]
-                modId = "neoforge"
-                type = "required"
-                versionRange = "[21.1,)"
-                side = "BOTH"
+                [[dependencies.forge_example]]
+                modId = "jei"
+                mandatory = true
+                side = "CLIENT"
             "#,
-            "META-INF/neoforge.mods.toml",
-            "NeoForge",
+            "META-INF/mods.toml",
+            "Forge / NeoForge",
             None,
         )
-        .expect("NeoForge metadata should parse");
+        .expect("Fixture metadata should parse");
+        let mut disabled = fabric_mod(
+            "zoom.jar.disabled",
+            r#"{"id": "zoom", "environment": "client"}"#,
+        );
+        disabled.enabled = false;
+        let mut mods = [
+            fabric_mod(
+                "minimap.jar",
+                r#"{"id": "minimap", "environment": "client", "depends": {"clientlib": "*", "shared": "*"}}"#,
+            ),
+            fabric_mod(
+                "clientlib.jar",
+                r#"{"id": "clientlib", "environment": "*"}"#,
+            ),
+            fabric_mod("shared.jar", r#"{"id": "shared"}"#),
+            fabric_mod("core.jar", r#"{"id": "core", "depends": {"shared": "*"}}"#),
+            forge,
+            fabric_mod("jei.jar", r#"{"id": "jei"}"#),
+            fabric_mod(
+                "backups.jar",
+                r#"{"id": "backups", "environment": "server"}"#,
+            ),
+            disabled,
+        ];
+        let warned = |mods: &[LocalModFile]| -> Vec<(String, String, Vec<String>)> {
+            mods.iter()
+                .flat_map(|local_mod| {
+                    local_mod.side_warnings.iter().map(|warning| {
+                        (
+                            local_mod.filename.clone(),
+                            warning.kind.clone(),
+                            warning.required_by.clone(),
+                        )
+                    })
+                })
+                .collect()
+        };
 
-        let dependency = &entries[0].dependencies[0];
-        assert_eq!(entries[0].loader_version.as_deref(), Some("[21.1,)"));
-        assert_eq!(entries[0].language_loader.as_deref(), Some("javafml"));
-        assert_eq!(entries[0].language_loader_version.as_deref(), Some("[1,)"));
-        assert_eq!(dependency.relation, "required");
-        assert_eq!(dependency.required, Some(true));
-        assert_eq!(dependency.version.as_deref(), Some("[21.1,)"));
+        attach_mod_side_warnings(&mut mods, InstanceSide::Server);
+        assert_eq!(
+            warned(&mods),
+            [
+                ("minimap.jar".to_string(), "otherSide".to_string(), vec![]),
+                (
+                    "clientlib.jar".to_string(),
+                    "otherSideDependency".to_string(),
+                    vec!["minimap.jar".to_string()]
+                ),
+                (
+                    "jei.jar".to_string(),
+                    "otherSideDependency".to_string(),
+                    vec!["forge.jar".to_string()]
+                ),
+            ]
+        );
+        attach_mod_side_warnings(&mut mods, InstanceSide::Client);
+        assert_eq!(
+            warned(&mods),
+            [("backups.jar".to_string(), "otherSide".to_string(), vec![])]
+        );
+        assert_eq!(mods[6].side_warnings[0].side, InstanceSide::Server);
     }
 
     #[test]
-    fn parses_legacy_mcmod_metadata() {
-        let entries = parse_mcmod_metadata(
-            r#"[{
-                "modid": "legacy_example",
-                "name": "Legacy Example",
-                "version": "1.0",
-                "mcversion": "1.12.2",
-                "authorList": ["Alice"],
-                "dependencies": [
-                    "required-after:Forge@[14.23.5,)",
-                    "after:another_mod"
-                ]
-            }]"#,
-            "mcmod.info",
-        )
-        .expect("mcmod.info should parse");
+    fn disables_other_side_mods_in_one_batch() {
+        let game_directory = temp_game_directory("mod-side-disable");
+        let game_directory_text = game_directory.display().to_string();
+        let mods = game_directory.join("mods");
+        for (filename, json) in [
+            (
+                "minimap.jar",
+                r#"{"id": "minimap", "environment": "client"}"#,
+            ),
+            (
+                "backups.jar",
+                r#"{"id": "backups", "environment": "server"}"#,
+            ),
+            ("core.jar", r#"{"id": "core"}"#),
+        ] {
+            fs::write(
+                mods.join(filename),
+                zip_bytes(&[("fabric.mod.json", json.as_bytes())]),
+            )
+            .expect("Fixture should be written");
+        }
 
-        let entry = &entries[0];
-        assert_eq!(entry.mod_id.as_deref(), Some("legacy_example"));
-        assert_eq!(entry.game_version.as_deref(), Some("1.12.2"));
-        assert_eq!(entry.loader_version.as_deref(), Some("[14.23.5,)"));
-        assert_eq!(entry.dependencies[0].relation, "required-after");
+        let result = disable_other_side_mods_blocking(&game_directory_text, InstanceSide::Server);
+        let again = disable_other_side_mods_blocking(&game_directory_text, InstanceSide::Server);
+        let mut installed: Vec<String> = mod_directory_snapshot(&mods).into_keys().collect();
+        installed.sort();
+        let _ = fs::remove_dir_all(&game_directory);
+
+        let result = result.expect("Other-side mods should be disabled");
+        assert!(result.failed.is_empty());
+        let changed: Vec<&str> = result
+            .changed
+            .iter()
+            .map(|rename| rename.file.filename.as_str())
+            .collect();
+        assert_eq!(changed, ["minimap.jar.disabled"]);
+        assert!(again
+            .expect("A second run should succeed")
+            .changed
+            .is_empty());
+        assert_eq!(
+            installed,
+            ["backups.jar", "core.jar", "minimap.jar.disabled"]
+        );
     }
 
     #[test]
-    fn unfolds_manifest_continuations() {
-        let manifest = "Manifest-Version: 1.0\r\nImplementation-Version: 1.2.\r\n 3-beta\r\n\r\n";
+    fn reports_missing_mismatched_and_breaking_dependencies() {
+        let version = InstanceVersionInfo {
+            version_id: "fabric-1.21.1".to_string(),
+            game_version: Some("1.21.1".to_string()),
+            loader: Some("Fabric".to_string()),
+            loader_version: Some("0.16.5".to_string()),
+            main_class: None,
+        };
+        let mut disabled = fabric_mod("disabled.jar.disabled", r#"{"id": "needed"}"#);
+        disabled.enabled = false;
+        let mods = [
+            fabric_mod(
+                "needs.jar",
+                r#"{"id": "needs", "depends": {"needed": "*", "minecraft": "1.20.1", "fabricloader": "*"}}"#,
+            ),
+            fabric_mod(
+                "api-user.jar",
+                r#"{"id": "api-user", "depends": {"fabric": "*"}}"#,
+            ),
+            fabric_mod(
+                "fabric-api.jar",
+                r#"{"id": "fabric-api", "version": "0.100.0", "provides": ["fabric"]}"#,
+            ),
+            fabric_mod(
+                "breaker.jar",
+                r#"{"id": "breaker", "breaks": {"fabric-api": "*"}, "conflicts": {"absent": "*"}}"#,
+            ),
+            disabled,
+        ];
+
+        let (problems, provided) = analyze_mod_dependencies(&mods, &version);
+        let summary: Vec<(&str, &str, &str)> = problems
+            .iter()
+            .map(|problem| {
+                (
+                    problem.kind.as_str(),
+                    problem.filename.as_str(),
+                    problem.dependency_id.as_str(),
+                )
+            })
+            .collect();
+
         assert_eq!(
-            manifest_implementation_version(manifest).as_deref(),
-            Some("1.2.3-beta")
+            summary,
+            [
+                ("versionMismatch", "needs.jar", "minecraft"),
+                ("missing", "needs.jar", "needed"),
+                ("breaks", "breaker.jar", "fabric-api"),
+            ]
         );
+        assert_eq!(problems[2].dependency_filenames, ["fabric-api.jar"]);
+        assert_eq!(provided.len(), 1);
+        assert_eq!(provided[0].filename, "api-user.jar");
+        assert_eq!(provided[0].provider_filename, "fabric-api.jar");
     }
 
     #[test]
-    fn resolves_zip64_candidate_sizes_and_local_header_offset() {
-        let filename = format!(
-            "epherome-zip64-extra-{}-{}.bin",
-            std::process::id(),
-            SystemTime::now()
-                .duration_since(UNIX_EPOCH)
-                .unwrap_or_default()
-                .as_nanos()
+    fn groups_enabled_duplicates_and_suggests_the_newest() {
+        let mut disabled = fabric_mod(
+            "sodium-0.6.0.jar.disabled",
+            r#"{"id": "sodium", "version": "0.6.0"}"#,
         );
-        let path = std::env::temp_dir().join(filename);
-        let mut extra = Vec::new();
-        extra.extend_from_slice(&ZIP64_EXTRA_FIELD_ID.to_le_bytes());
-        extra.extend_from_slice(&24_u16.to_le_bytes());
-        extra.extend_from_slice(&12_u64.to_le_bytes());
-        extra.extend_from_slice(&10_u64.to_le_bytes());
-        extra.extend_from_slice(&7_u64.to_le_bytes());
-        fs::write(&path, &extra).expect("Temporary ZIP64 extra field should be written");
-
-        let mut file = File::open(&path).expect("Temporary ZIP64 extra field should open");
-        let resolved = read_zip64_target_entry(
-            &mut file,
-            0,
-            extra.len() as u64,
-            u32::MAX,
-            u32::MAX,
-            u32::MAX,
-            0,
-        )
-        .expect("ZIP64 values should resolve");
-        let _ = fs::remove_file(path);
+        disabled.enabled = false;
+        let mods = [
+            fabric_mod(
+                "Sodium-0.5.11.jar",
+                r#"{"id": "sodium", "version": "0.5.11+mc1.20.1"}"#,
+            ),
+            fabric_mod(
+                "sodium-0.5.8.jar",
+                r#"{"id": "Sodium", "version": "0.5.8+mc1.20.1"}"#,
+            ),
+            disabled,
+            fabric_mod("lithium-a.jar", r#"{"id": "lithium", "version": "0.11.2"}"#),
+            fabric_mod("lithium-b.jar", r#"{"id": "lithium", "version": "0.11.2"}"#),
+            fabric_mod("iris.jar", r#"{"id": "iris", "version": "1.6.11"}"#),
+        ];
+
+        let groups = find_duplicate_mods(&mods);
 
-        assert_eq!(resolved, (10, 12, 7));
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].mod_id, "lithium");
+        assert_eq!(groups[0].suggested_keep, None);
+        assert_eq!(groups[1].mod_id, "sodium");
+        let filenames: Vec<&str> = groups[1]
+            .providers
+            .iter()
+            .map(|provider| provider.filename.as_str())
+            .collect();
+        assert_eq!(filenames, ["Sodium-0.5.11.jar", "sodium-0.5.8.jar"]);
+        assert_eq!(
+            groups[1].suggested_keep.as_deref(),
+            Some("Sodium-0.5.11.jar")
+        );
     }
 
     #[test]
-    fn corrupt_jar_returns_a_diagnostic_instead_of_an_error() {
-        let filename = format!(
-            "epherome-corrupt-mod-{}-{}.jar",
-            std::process::id(),
-            SystemTime::now()
-                .duration_since(UNIX_EPOCH)
-                .unwrap_or_default()
-                .as_nanos()
-        );
-        let path = std::env::temp_dir().join(filename);
-        fs::write(&path, b"not a zip archive").expect("Temporary corrupt JAR should be written");
+    fn follows_declared_nested_jars_within_the_limits() {
+        let innermost = zip_bytes(&[(
+            "fabric.mod.json",
+            br#"{"id": "innermost", "jars": [{"file": "META-INF/jars/deeper.jar"}]}"#,
+        )]);
+        let library = zip_bytes(&[
+            (
+                "fabric.mod.json",
+                br#"{"id": "bundled_lib", "version": "2.0.0", "jars": [{"file": "META-INF/jars/innermost.jar"}]}"#,
+            ),
+            ("META-INF/jars/innermost.jar", &innermost),
+        ]);
+        let forge_library = zip_bytes(&[(
+            "META-INF/mods.toml",
+            b"modLoader='javafml'\nloaderVersion='[47,)'\n[[mods]]\nmodId='forge_lib'\nversion='1.0.0'\n",
+        )]);
+        let parent = zip_bytes(&[
+            (
+                "fabric.mod.json",
+                br#"{
+                    "id": "parent",
+                    "depends": {"bundled_lib": ">=2.0.0", "forge_lib": "*"},
+                    "jars": [
+                        {"file": "META-INF/jars/library.jar"},
+                        {"file": "META-INF/jars/missing.jar"},
+                        {"file": "../outside.jar"}
+                    ]
+                }"#,
+            ),
+            ("META-INF/jars/library.jar", &library),
+            (
+                JARJAR_METADATA_PATH,
+                br#"{"jars": [{"identifier": {"group": "example", "artifact": "forge_lib"}, "path": "META-INF/jarjar/forge_lib.jar"}]}"#,
+            ),
+            ("META-INF/jarjar/forge_lib.jar", &forge_library),
+        ]);
 
-        let metadata = parse_mod_metadata(&path);
-        let _ = fs::remove_file(path);
+        let metadata =
+            parse_archive_metadata(&mut io::Cursor::new(parent), 1, &mut NestedJarBudget::new());
 
-        assert!(metadata.entries.is_empty());
-        assert_eq!(metadata.diagnostics.len(), 1);
-        assert!(metadata.diagnostics[0].contains("inspection"));
+        let nested_paths: Vec<&str> = metadata
+            .nested
+            .iter()
+            .map(|nested| nested.path.as_str())
+            .collect();
+        assert_eq!(
+            nested_paths,
+            ["META-INF/jars/library.jar", "META-INF/jarjar/forge_lib.jar"]
+        );
+        assert!(metadata.diagnostics.iter().any(|diagnostic| diagnostic
+            .starts_with("META-INF/jars/missing.jar: the declared nested JAR is missing")));
+        assert!(metadata
+            .diagnostics
+            .iter()
+            .any(|diagnostic| diagnostic.starts_with("../outside.jar:")));
+
+        let library = &metadata.nested[0].metadata;
+        assert_eq!(library.entries[0].mod_id.as_deref(), Some("bundled_lib"));
+        assert_eq!(library.nested.len(), 1);
+        let innermost = &library.nested[0].metadata;
+        assert_eq!(innermost.entries[0].mod_id.as_deref(), Some("innermost"));
+        assert!(innermost.nested.is_empty());
+        assert!(innermost.diagnostics[0].contains("nesting limit"));
+
+        let local_mod = LocalModFile {
+            filename: "parent.jar".to_string(),
+            enabled: true,
+            size: 0,
+            metadata,
+            curseforge_fingerprint: None,
+            modrinth: None,
+            curseforge: None,
+            compatibility: None,
+            side_warnings: Vec::new(),
+        };
+        let (problems, _) = analyze_mod_dependencies(
+            std::slice::from_ref(&local_mod),
+            &InstanceVersionInfo::default(),
+        );
+        assert!(problems.is_empty(), "{problems:?}");
     }
 
     #[test]
-    fn reads_metadata_from_a_disabled_jar_archive() {
-        let filename = format!(
-            "epherome-metadata-mod-{}-{}.jar.disabled",
-            std::process::id(),
-            SystemTime::now()
-                .duration_since(UNIX_EPOCH)
-                .unwrap_or_default()
-                .as_nanos()
+    fn nested_metadata_shares_the_parent_serialized_limit() {
+        let dependencies: Vec<String> = (0..200)
+            .map(|index| format!(r#""dep_{index}": "{}""#, "9".repeat(2000)))
+            .collect();
+        let descriptor = format!(
+            r#"{{"id": "big", "depends": {{{}}}}}"#,
+            dependencies.join(",")
         );
-        let path = std::env::temp_dir().join(&filename);
-        let file = File::create(&path).expect("Temporary JAR should be created");
-        let mut archive = zip::ZipWriter::new(file);
-        archive
-            .start_file("fabric.mod.json", SimpleFileOptions::default())
-            .expect("Metadata entry should be created");
-        archive
-            .write_all(
-                br#"{
-                    "id": "archive_example",
-                    "name": "Archive Example",
-                    "version": "1.0.0",
-                    "authors": ["Alice"],
-                    "depends": {"minecraft": "1.21.1"}
-                }"#,
-            )
-            .expect("Metadata should be written");
-        archive.finish().expect("Temporary JAR should be finished");
+        let nested = zip_bytes(&[("fabric.mod.json", descriptor.as_bytes())]);
+        let paths: Vec<String> = (0..4)
+            .map(|index| format!("META-INF/jars/big-{index}.jar"))
+            .collect();
+        let jars: Vec<String> = paths
+            .iter()
+            .map(|path| format!(r#"{{"file": "{path}"}}"#))
+            .collect();
+        let parent_descriptor = format!(r#"{{"id": "parent", "jars": [{}]}}"#, jars.join(","));
+        let mut entries: Vec<(&str, &[u8])> =
+            vec![("fabric.mod.json", parent_descriptor.as_bytes())];
+        entries.extend(paths.iter().map(|path| (path.as_str(), nested.as_slice())));
+        let parent = zip_bytes(&entries);
+
+        let metadata =
+            parse_archive_metadata(&mut io::Cursor::new(parent), 0, &mut NestedJarBudget::new());
+        let serialized = serde_json::to_vec(&metadata).expect("Metadata should serialize");
+
+        assert!(serialized.len() <= MAX_METADATA_SERIALIZED_BYTES_PER_JAR + 1024);
+        assert!(!metadata.nested.is_empty());
+        assert!(metadata.nested[0].metadata.entries.len() == 1);
+        assert!(metadata
+            .nested
+            .iter()
+            .any(|nested| nested.metadata.entries.is_empty()));
+    }
 
-        let local_mod =
-            local_mod_file(&path, filename, false).expect("The disabled JAR should be inspected");
-        let _ = fs::remove_file(path);
+    #[test]
+    fn picks_the_declared_icon_closest_to_the_requested_size() {
+        let png = [PNG_SIGNATURE, b"small".as_slice()].concat();
+        let large_png = [PNG_SIGNATURE, b"large".as_slice()].concat();
+        let jar = zip_bytes(&[
+            (
+                "fabric.mod.json",
+                br#"{"id": "iconic", "icon": {"16": "assets/iconic/16.png", "128": "/assets/iconic/128.png"}}"#,
+            ),
+            ("assets/iconic/16.png", &png),
+            ("assets/iconic/128.png", &large_png),
+        ]);
 
-        assert!(!local_mod.enabled);
-        assert!(local_mod.metadata.diagnostics.is_empty());
-        assert_eq!(local_mod.metadata.entries.len(), 1);
         assert_eq!(
-            local_mod.metadata.entries[0].mod_id.as_deref(),
-            Some("archive_example")
+            read_mod_icon(&mut io::Cursor::new(jar.clone()), 24),
+            Ok(Some(png))
+        );
+        assert_eq!(
+            read_mod_icon(&mut io::Cursor::new(jar), 96),
+            Ok(Some(large_png))
         );
+
+        let forge_jar = zip_bytes(&[
+            (
+                "META-INF/mods.toml",
+                b"modLoader='javafml'\nloaderVersion='[47,)'\n[[mods]]\nmodId='logo'\nlogoFile='logo.jpg'\n",
+            ),
+            ("logo.jpg", b"\xff\xd8\xff"),
+        ]);
+        assert_eq!(read_mod_icon(&mut io::Cursor::new(forge_jar), 64), Ok(None));
+
+        let plain_jar = zip_bytes(&[("fabric.mod.json", br#"{"id": "plain"}"#)]);
+        assert_eq!(read_mod_icon(&mut io::Cursor::new(plain_jar), 64), Ok(None));
     }
 
     #[test]
-    fn reads_metadata_from_a_deflated_jar_archive() {
-        let filename = format!(
-            "epherome-deflated-metadata-mod-{}-{}.jar",
-            std::process::id(),
-            SystemTime::now()
-                .duration_since(UNIX_EPOCH)
-                .unwrap_or_default()
-                .as_nanos()
+    fn icon_cache_entries_follow_jar_content() {
+        let game_directory = temp_game_directory("mod-icon-cache");
+        let game_directory_text = game_directory.display().to_string();
+        let mods = game_directory.join("mods");
+        let png = [PNG_SIGNATURE, b"icon".as_slice()].concat();
+        let jar = zip_bytes(&[
+            (
+                "fabric.mod.json",
+                br#"{"id": "iconic", "icon": "icon.png"}"#,
+            ),
+            ("icon.png", &png),
+        ]);
+        fs::write(mods.join("iconic.jar"), &jar).expect("Fixture JAR should be written");
+        fs::write(mods.join("twin.jar"), &jar).expect("Fixture copy should be written");
+
+        let first = local_mod_icon_blocking(&game_directory_text, "iconic.jar", 64);
+        let twin = local_mod_icon_blocking(&game_directory_text, "twin.jar", 64);
+        fs::rename(mods.join("iconic.jar"), mods.join("iconic.jar.disabled"))
+            .expect("Fixture JAR should be disabled");
+        let toggled = local_mod_icon_blocking(&game_directory_text, "iconic.jar.disabled", 64);
+        // Same size and mtime, as left behind by a copy tool that preserves timestamps.
+        let other_png = [PNG_SIGNATURE, b"ICON".as_slice()].concat();
+        let replacement = zip_bytes(&[
+            (
+                "fabric.mod.json",
+                br#"{"id": "iconic", "icon": "icon.png"}"#,
+            ),
+            ("icon.png", &other_png),
+        ]);
+        let disabled_path = mods.join("iconic.jar.disabled");
+        let modified = fs::metadata(&disabled_path)
+            .and_then(|metadata| metadata.modified())
+            .expect("Fixture mtime should be read");
+        fs::write(&disabled_path, &replacement).expect("Fixture JAR should be replaced");
+        File::options()
+            .write(true)
+            .open(&disabled_path)
+            .and_then(|file| file.set_modified(modified))
+            .expect("Fixture mtime should be restored");
+        let replaced = local_mod_icon_blocking(&game_directory_text, "iconic.jar.disabled", 64);
+        let _ = fs::remove_dir_all(&game_directory);
+
+        assert_eq!(replacement.len(), jar.len());
+        let first = first
+            .expect("Icon should be read")
+            .expect("Icon should exist");
+        assert_eq!(twin, Ok(Some(first.clone())));
+        assert_eq!(toggled, Ok(Some(first.clone())));
+        assert_ne!(replaced.expect("Icon should be read"), Some(first));
+    }
+
+    #[test]
+    fn scans_reuse_cached_metadata_across_renames_and_ignore_corrupt_caches() {
+        let game_directory = temp_game_directory("mod-metadata-cache");
+        let jar = zip_bytes(&[("fabric.mod.json", br#"{"id": "cached"}"#)]);
+        fs::write(game_directory.join("mods/cached.jar"), jar)
+            .expect("Fixture JAR should be written");
+        let game_directory_text = game_directory.to_string_lossy().into_owned();
+        let cache_path = game_directory
+            .join(LAUNCHER_DIRECTORY_NAME)
+            .join(MOD_METADATA_CACHE_FILENAME);
+
+        let first_scan = scan_local_mods_blocking(&game_directory_text);
+        let mut cache = read_mod_metadata_cache(&cache_path);
+        let cached_entry = cache.entries.get_mut("cached.jar");
+        assert!(cached_entry.is_some());
+        // Mark the cached metadata so a reuse is observable.
+        if let Some(entry) = cached_entry {
+            entry.metadata.diagnostics.push("from cache".to_string());
+        }
+        write_json_atomically(&cache_path, &cache).expect("Cache should be rewritten");
+
+        fs::rename(
+            game_directory.join("mods/cached.jar"),
+            game_directory.join("mods/cached.jar.disabled"),
+        )
+        .expect("Fixture JAR should be disabled");
+        let renamed_scan = scan_local_mods_blocking(&game_directory_text);
+
+        fs::write(&cache_path, b"{not json").expect("Corrupt cache should be written");
+        let corrupt_scan = scan_local_mods_blocking(&game_directory_text);
+        let rewritten_cache = read_mod_metadata_cache(&cache_path);
+        let _ = fs::remove_dir_all(&game_directory);
+
+        assert_eq!(first_scan.map(|mods| mods.len()), Ok(1));
+        let renamed = renamed_scan.expect("Renamed scan should succeed");
+        assert_eq!(renamed[0].filename, "cached.jar.disabled");
+        assert_eq!(renamed[0].metadata.diagnostics, ["from cache"]);
+        let corrupt = corrupt_scan.expect("A corrupt cache should be ignored");
+        assert!(corrupt[0].metadata.diagnostics.is_empty());
+        assert_eq!(
+            corrupt[0].metadata.entries[0].mod_id.as_deref(),
+            Some("cached")
         );
-        let path = std::env::temp_dir().join(&filename);
-        let file = File::create(&path).expect("Temporary JAR should be created");
-        let mut archive = zip::ZipWriter::new(file);
-        archive
-            .start_file(
+        assert!(rewritten_cache.entries.contains_key("cached.jar.disabled"));
+    }
+
+    #[test]
+    fn enabled_and_disabled_copies_keep_separate_cache_entries() {
+        let game_directory = temp_game_directory("mod-metadata-cache-pair");
+        let mods = game_directory.join("mods");
+        fs::write(
+            mods.join("pair.jar"),
+            zip_bytes(&[("fabric.mod.json", br#"{"id": "enabled_copy"}"#)]),
+        )
+        .expect("Fixture JAR should be written");
+        fs::write(
+            mods.join("pair.jar.disabled"),
+            zip_bytes(&[(
                 "fabric.mod.json",
-                SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated),
-            )
-            .expect("Deflated metadata entry should be created");
-        archive
-            .write_all(
-                br#"{
-                    "id": "deflated_example",
-                    "name": "Deflated Example",
-                    "version": "1.0.0"
-                }"#,
-            )
-            .expect("Deflated metadata should be written");
-        archive.finish().expect("Temporary JAR should be finished");
+                br#"{"id": "disabled_copy_with_a_longer_id"}"#,
+            )]),
+        )
+        .expect("Fixture JAR should be written");
+        let game_directory_text = game_directory.to_string_lossy().into_owned();
 
-        let metadata = parse_mod_metadata(&path);
-        let _ = fs::remove_file(path);
+        let first = scan_local_mods_blocking(&game_directory_text);
+        let second = scan_local_mods_blocking(&game_directory_text);
+        let cache = read_mod_metadata_cache(
+            &mod_metadata_cache_path(&game_directory_text).expect("Cache path should resolve"),
+        );
+        let _ = fs::remove_dir_all(&game_directory);
 
-        assert!(metadata.diagnostics.is_empty());
-        assert_eq!(metadata.entries.len(), 1);
+        let mod_ids = |scan: Result<Vec<LocalModFile>, String>| {
+            scan.expect("Scan should succeed")
+                .into_iter()
+                .map(|local_mod| local_mod.metadata.entries[0].mod_id.clone())
+                .collect::<Vec<_>>()
+        };
+        let expected = [
+            Some("enabled_copy".to_string()),
+            Some("disabled_copy_with_a_longer_id".to_string()),
+        ];
+        assert_eq!(mod_ids(first), expected);
+        assert_eq!(mod_ids(second), expected);
+        assert_eq!(cache.entries.len(), 2);
+    }
+
+    #[test]
+    fn parallel_scans_report_progress_sort_results_and_cancel() {
+        let game_directory = temp_game_directory("parallel-mod-scan");
+        let filenames = ["b.jar", "A.jar", "c.jar.disabled", "D.jar", "e.jar"];
+        for filename in filenames {
+            let descriptor = format!(r#"{{"id": "{}"}}"#, filename.replace('.', "_"));
+            fs::write(
+                game_directory.join("mods").join(filename),
+                zip_bytes(&[("fabric.mod.json", descriptor.as_bytes())]),
+            )
+            .expect("Fixture JAR should be written");
+        }
+        let game_directory_text = game_directory.to_string_lossy().into_owned();
+
+        let progress = std::sync::Mutex::new(Vec::new());
+        let scanned = scan_local_mods_with_progress(
+            &game_directory_text,
+            &AtomicBool::new(false),
+            &|parsed, total, filename| {
+                progress
+                    .lock()
+                    .expect("Progress lock should not be poisoned")
+                    .push((parsed, total, filename.to_string()));
+            },
+        );
+        let cancelled = scan_local_mods_with_progress(
+            &game_directory_text,
+            &AtomicBool::new(true),
+            &|_, _, _| {},
+        );
+        let _ = fs::remove_dir_all(&game_directory);
+
+        let scanned = scanned.expect("Scan should succeed");
+        let scanned_filenames: Vec<&str> = scanned
+            .iter()
+            .map(|local_mod| local_mod.filename.as_str())
+            .collect();
         assert_eq!(
-            metadata.entries[0].mod_id.as_deref(),
-            Some("deflated_example")
+            scanned_filenames,
+            ["A.jar", "b.jar", "c.jar.disabled", "D.jar", "e.jar"]
+        );
+        let mut progress = progress
+            .into_inner()
+            .expect("Progress lock should not be poisoned");
+        progress.sort();
+        let counts: Vec<(usize, usize)> = progress
+            .iter()
+            .map(|(parsed, total, _)| (*parsed, *total))
+            .collect();
+        assert_eq!(counts, [(1, 5), (2, 5), (3, 5), (4, 5), (5, 5)]);
+        assert_eq!(
+            cancelled.err().as_deref(),
+            Some("The mod scan was cancelled.")
         );
     }
 
     #[test]
-    fn duplicate_metadata_paths_are_reported_instead_of_selecting_one() {
-        let filename = format!(
-            "epherome-duplicate-metadata-mod-{}-{}.jar",
-            std::process::id(),
-            SystemTime::now()
-                .duration_since(UNIX_EPOCH)
-                .unwrap_or_default()
-                .as_nanos()
-        );
-        let path = std::env::temp_dir().join(&filename);
-        let entry_name = b"fabric.mod.json";
-        let contents = [
-            br#"{"id":"first_example","version":"1.0.0"}"#.as_slice(),
-            br#"{"id":"second_example","version":"1.0.0"}"#.as_slice(),
-        ];
-        let mut bytes = Vec::new();
-        let mut local_offsets = Vec::new();
-        for content in contents {
-            let local_offset = u32::try_from(bytes.len()).expect("Fixture local offset should fit");
-            local_offsets.push((local_offset, content.len() as u32));
-            let mut local_header = [0_u8; ZIP_LOCAL_FILE_HEADER_SIZE as usize];
-            local_header[0..4].copy_from_slice(b"PK\x03\x04");
-            local_header[4..6].copy_from_slice(&20_u16.to_le_bytes());
-            local_header[18..22].copy_from_slice(&(content.len() as u32).to_le_bytes());
-            local_header[22..26].copy_from_slice(&(content.len() as u32).to_le_bytes());
-            local_header[26..28].copy_from_slice(&(entry_name.len() as u16).to_le_bytes());
-            bytes.extend_from_slice(&local_header);
-            bytes.extend_from_slice(entry_name);
-            bytes.extend_from_slice(content);
-        }
+    fn mod_directory_snapshots_diff_into_added_removed_and_renamed() {
+        let game_directory = temp_game_directory("mod-directory-diff");
+        let mods = game_directory.join("mods");
+        fs::write(mods.join("kept.jar"), b"kept").expect("Fixture should be written");
+        fs::write(mods.join("toggled.jar"), b"toggled").expect("Fixture should be written");
+        fs::write(mods.join("removed.jar"), b"removed").expect("Fixture should be written");
+        let previous = mod_directory_snapshot(&mods);
+
+        fs::rename(mods.join("toggled.jar"), mods.join("toggled.jar.disabled"))
+            .expect("Fixture should be renamed");
+        fs::remove_file(mods.join("removed.jar")).expect("Fixture should be removed");
+        fs::write(mods.join("new.jar"), b"new").expect("Fixture should be written");
+        fs::write(mods.join(".epherome-import-1.jar"), b"temp").expect("Fixture should be written");
+        fs::write(mods.join("notes.txt"), b"ignored").expect("Fixture should be written");
+        let current = mod_directory_snapshot(&mods);
+        let _ = fs::remove_dir_all(&game_directory);
 
-        let central_offset = u32::try_from(bytes.len()).expect("Fixture central offset should fit");
-        for (local_offset, content_length) in local_offsets {
-            let mut central_header = [0_u8; ZIP_CENTRAL_DIRECTORY_HEADER_SIZE as usize];
-            central_header[0..4].copy_from_slice(b"PK\x01\x02");
-            central_header[4..6].copy_from_slice(&20_u16.to_le_bytes());
-            central_header[6..8].copy_from_slice(&20_u16.to_le_bytes());
-            central_header[20..24].copy_from_slice(&content_length.to_le_bytes());
-            central_header[24..28].copy_from_slice(&content_length.to_le_bytes());
-            central_header[28..30].copy_from_slice(&(entry_name.len() as u16).to_le_bytes());
-            central_header[42..46].copy_from_slice(&local_offset.to_le_bytes());
-            bytes.extend_from_slice(&central_header);
-            bytes.extend_from_slice(entry_name);
-        }
-        let central_size = u32::try_from(bytes.len())
-            .expect("Fixture size should fit")
-            .checked_sub(central_offset)
-            .expect("Fixture central size should not underflow");
-        let mut end_record = [0_u8; ZIP_EOCD_MIN_SIZE as usize];
-        end_record[0..4].copy_from_slice(b"PK\x05\x06");
-        end_record[8..10].copy_from_slice(&2_u16.to_le_bytes());
-        end_record[10..12].copy_from_slice(&2_u16.to_le_bytes());
-        end_record[12..16].copy_from_slice(&central_size.to_le_bytes());
-        end_record[16..20].copy_from_slice(&central_offset.to_le_bytes());
-        bytes.extend_from_slice(&end_record);
-        fs::write(&path, bytes).expect("Duplicate metadata fixture should be written");
+        assert_eq!(
+            diff_mod_directory_snapshots(&previous, &current),
+            ModDirectoryDiff {
+                added: vec!["new.jar".to_string()],
+                removed: vec!["removed.jar".to_string()],
+                renamed: vec![(
+                    "toggled.jar".to_string(),
+                    "toggled.jar.disabled".to_string()
+                )],
+            }
+        );
+        assert_eq!(
+            diff_mod_directory_snapshots(&current, &current),
+            ModDirectoryDiff::default()
+        );
+    }
 
-        let metadata = parse_mod_metadata(&path);
-        let _ = fs::remove_file(path);
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    #[test]
+    fn watcher_falls_back_to_polling_when_the_directory_is_removed() {
+        let game_directory = temp_game_directory("mod-watch-removed");
+        let mods = game_directory.join("mods");
+        let mut waker = ModDirectoryWaker::new(&mods);
+        assert!(matches!(waker, ModDirectoryWaker::Inotify(_)));
+
+        fs::remove_dir_all(&mods).expect("Mods directory should be removed");
+        let changed = waker.changed(&mods);
+        let polling = matches!(waker, ModDirectoryWaker::Polling(_));
+        let _ = fs::remove_dir_all(&game_directory);
+
+        assert!(changed);
+        assert!(polling);
+    }
 
-        assert!(metadata.entries.is_empty());
-        assert_eq!(metadata.diagnostics.len(), 1);
-        assert!(metadata.diagnostics[0].contains("ambiguous duplicate 'fabric.mod.json' entries"));
+    #[test]
+    fn computes_whitespace_insensitive_curseforge_fingerprints() {
+        let fingerprint = |bytes: &[u8]| {
+            curseforge_fingerprint(&mut io::Cursor::new(bytes.to_vec()))
+                .expect("Fingerprint should be computed")
+        };
+        assert_eq!(fingerprint(b""), 1540447798);
+        assert_eq!(fingerprint(b"Hello, World!"), 1961219979);
+        assert_eq!(fingerprint(b"Hello,\tWorld!\r\n"), 1961219979);
+        // Spans several read buffers with tails that do not align to four bytes.
+        let large: Vec<u8> = (0..=255_u8).cycle().take(256 * 300).collect();
+        assert_eq!(fingerprint(&large), 2859018930);
+
+        let game_directory = temp_game_directory("curseforge-fingerprint");
+        let path = game_directory.join("mods").join("hello.jar");
+        fs::write(&path, b"Hello, World!").expect("Fixture should be written");
+        let single = local_mod_file(&path, "hello.jar".to_string(), true)
+            .expect("Mod file should be inspected");
+        let scanned = scan_local_mods_blocking(&game_directory.display().to_string())
+            .expect("Mods should be scanned");
+        let _ = fs::remove_dir_all(&game_directory);
+        assert_eq!(single.curseforge_fingerprint, Some(1961219979));
+        assert_eq!(scanned[0].curseforge_fingerprint, Some(1961219979));
     }
 
     #[test]
-    fn trailing_fake_eocd_does_not_fallback_to_an_earlier_large_descriptor() {
-        let filename = format!(
-            "epherome-fake-eocd-{}-{}.jar",
-            std::process::id(),
-            SystemTime::now()
-                .duration_since(UNIX_EPOCH)
-                .unwrap_or_default()
-                .as_nanos()
-        );
-        let path = std::env::temp_dir().join(filename);
-        let file = File::create(&path).expect("Temporary JAR should be created");
-        let mut archive = zip::ZipWriter::new(file);
-        archive
-            .start_file("fabric.mod.json", SimpleFileOptions::default())
-            .expect("Earlier metadata entry should be created");
-        let oversized_metadata = vec![b' '; MAX_METADATA_FILE_SIZE as usize + 1];
-        archive
-            .write_all(&oversized_metadata)
-            .expect("Earlier oversized metadata should be written");
-        archive.finish().expect("Earlier ZIP should be finished");
+    fn installs_verified_downloads_and_replaces_older_jars() {
+        let game_directory = temp_game_directory("install-download");
+        let game_directory_text = game_directory.display().to_string();
+        let mods = game_directory.join("mods");
+        let fabric_jar = |version: &str| {
+            zip_bytes(&[(
+                "fabric.mod.json",
+                format!(r#"{{"id": "example", "version": "{version}"}}"#).as_bytes(),
+            )])
+        };
+        fs::write(mods.join("example-1.0.0.jar"), fabric_jar("1.0.0"))
+            .expect("Fixture should be written");
+        fs::write(
+            mods.join("other.jar"),
+            zip_bytes(&[("fabric.mod.json", br#"{"id": "other"}"#)]),
+        )
+        .expect("Fixture should be written");
+        let stage = |bytes: &[u8]| {
+            let (temp_path, mut temp_file) =
+                create_import_temp_file(&mods).expect("Temp file should be created");
+            temp_file
+                .write_all(bytes)
+                .expect("Temp file should be written");
+            let mut hasher = ModFileHasher::default();
+            hasher.update(bytes);
+            (temp_path, hasher.finish())
+        };
+        let download =
+            |filename: &str, sha1: Option<&str>, sha512: Option<&str>| LocalModDownload {
+                url: "https://cdn.example/mod.jar".to_string(),
+                filename: filename.to_string(),
+                sha1: sha1.map(str::to_string),
+                sha512: sha512.map(str::to_string),
+                replace_older: true,
+            };
 
-        let mut fake_end_record = [0_u8; ZIP_EOCD_MIN_SIZE as usize];
-        fake_end_record[0..4].copy_from_slice(b"PK\x05\x06");
-        fake_end_record[8..10].copy_from_slice(&1_u16.to_le_bytes());
-        fake_end_record[12..16].copy_from_slice(&1_u32.to_le_bytes());
-        fake_end_record[16..20].copy_from_slice(&0_u32.to_le_bytes());
-        let mut file = OpenOptions::new()
-            .append(true)
-            .open(&path)
-            .expect("Temporary JAR should reopen for the fake end record");
-        file.write_all(&fake_end_record)
-            .expect("Fake trailing end record should be written");
-        drop(file);
+        assert!(validate_download_hashes(&download("unverified.jar", None, Some(" "))).is_err());
+        assert!(validate_download_hashes(&download("verified.jar", None, Some("00"))).is_ok());
 
-        let metadata = parse_mod_metadata(&path);
-        let _ = fs::remove_file(path);
+        let (temp_path, hashes) = stage(&fabric_jar("2.0.0"));
+        let error = install_downloaded_mod(
+            &game_directory_text,
+            &temp_path,
+            &download("example-2.0.0.jar", Some("0000"), None),
+            &hashes,
+        )
+        .err()
+        .expect("A mismatched hash should be rejected");
+        assert!(error.contains("SHA-1"));
+        assert!(!temp_path.exists());
+
+        let (temp_path, hashes) = stage(b"not a jar");
+        let error = install_downloaded_mod(
+            &game_directory_text,
+            &temp_path,
+            &download("broken.jar", Some(&hashes.sha1.clone()), None),
+            &hashes,
+        )
+        .err()
+        .expect("A non-ZIP download should be rejected");
+        assert!(error.contains("not a valid JAR"));
+        assert!(!temp_path.exists());
+
+        let (temp_path, hashes) = stage(&fabric_jar("0.9.0"));
+        let error = install_downloaded_mod(
+            &game_directory_text,
+            &temp_path,
+            &download("example-0.9.0.jar", None, None),
+            &hashes,
+        )
+        .err()
+        .expect("Downgrades should not replace newer jars");
+        assert!(error.contains("newer version (1.0.0)"));
+        assert!(!temp_path.exists());
+
+        let (temp_path, hashes) = stage(&fabric_jar("2.0.0"));
+        let result = install_downloaded_mod(
+            &game_directory_text,
+            &temp_path,
+            &download(
+                "example-2.0.0.jar",
+                Some(&hashes.sha1.clone()),
+                Some(&hashes.sha512.to_ascii_uppercase()),
+            ),
+            &hashes,
+        )
+        .expect("A verified upgrade should install");
+        let mut remaining: Vec<String> = fs::read_dir(&mods)
+            .expect("Mods directory should be readable")
+            .map(|entry| {
+                entry
+                    .expect("Entry should be readable")
+                    .file_name()
+                    .to_string_lossy()
+                    .into_owned()
+            })
+            .collect();
+        remaining.sort();
+        let trashed =
+            list_trashed_local_mods_blocking(&game_directory_text).expect("Trash should be listed");
+        let _ = fs::remove_dir_all(&game_directory);
 
-        assert!(metadata.entries.is_empty());
+        assert_eq!(result.installed.filename, "example-2.0.0.jar");
         assert_eq!(
-            metadata.diagnostics,
-            ["No supported mod metadata descriptor was found in the JAR."]
+            result.installed.metadata.entries[0].version.as_deref(),
+            Some("2.0.0")
         );
+        assert_eq!(result.replaced.len(), 1);
+        assert_eq!(result.replaced[0].filename, "example-1.0.0.jar");
+        assert_eq!(remaining, ["example-2.0.0.jar", "other.jar"]);
+        assert_eq!(trashed.len(), 1);
+        assert_eq!(trashed[0].id, result.replaced[0].id);
     }
 
     #[test]
-    fn zip64_offset_sentinel_uses_the_zip64_limits() {
-        let filename = format!(
-            "epherome-zip64-preflight-{}-{}.jar",
-            std::process::id(),
-            SystemTime::now()
-                .duration_since(UNIX_EPOCH)
-                .unwrap_or_default()
-                .as_nanos()
+    fn mod_profiles_switch_atomically_and_roll_back() {
+        let game_directory = temp_game_directory("mod-profiles");
+        let game_directory_text = game_directory.display().to_string();
+        let mods = game_directory.join("mods");
+        for filename in ["sodium.jar", "lithium.jar", "debug.jar.disabled"] {
+            fs::write(mods.join(filename), filename).expect("Fixture should be written");
+        }
+        let installed = || {
+            let mut filenames: Vec<String> = mod_directory_snapshot(&mods).into_keys().collect();
+            filenames.sort();
+            filenames
+        };
+
+        let performance = save_mod_profile_blocking(&game_directory_text, " performance ", None)
+            .expect("Profile should capture enabled mods");
+        let debug = save_mod_profile_blocking(
+            &game_directory_text,
+            "debug",
+            Some(vec![
+                "debug.jar.disabled".to_string(),
+                "sodium.jar".to_string(),
+                "gone.jar".to_string(),
+            ]),
+        )
+        .expect("Profile should be saved");
+        let applied = apply_mod_profile_blocking(&game_directory_text, "debug")
+            .expect("Profile should apply");
+        let after_debug = installed();
+
+        // A mod installed both enabled and disabled is refused before anything is renamed.
+        fs::write(mods.join("lithium.jar"), b"blocker").expect("Blocker should be written");
+        let blocked = apply_mod_profile_blocking(&game_directory_text, "performance");
+        fs::remove_file(mods.join("lithium.jar")).expect("Blocker should be removed");
+        let after_blocked = installed();
+        // The second rename fails, so the first one is undone.
+        let renames = [
+            ("sodium.jar".to_string(), "sodium.jar.disabled".to_string()),
+            (
+                "missing.jar".to_string(),
+                "missing.jar.disabled".to_string(),
+            ),
+        ];
+        let rolled_back = rename_mods_in_transaction(&mods, &renames);
+        let after_rollback = installed();
+        apply_mod_profile_blocking(&game_directory_text, "performance")
+            .expect("Profile should apply");
+        let after_performance = installed();
+        let deleted = delete_mod_profile_blocking(&game_directory_text, "debug");
+        let profiles = list_mod_profiles_blocking(&game_directory_text);
+        let _ = fs::remove_dir_all(&game_directory);
+
+        assert_eq!(performance.name, "performance");
+        assert_eq!(performance.mods, ["lithium.jar", "sodium.jar"]);
+        assert_eq!(debug.mods, ["debug.jar", "gone.jar", "sodium.jar"]);
+        assert_eq!(applied.enabled, ["debug.jar"]);
+        assert_eq!(applied.disabled, ["lithium.jar.disabled"]);
+        assert_eq!(applied.missing, ["gone.jar"]);
+        assert_eq!(
+            after_debug,
+            ["debug.jar", "lithium.jar.disabled", "sodium.jar"]
         );
-        let path = std::env::temp_dir().join(filename);
-        let mut bytes = vec![0; 98];
+        assert!(blocked.is_err());
+        assert_eq!(after_blocked, after_debug);
+        assert_eq!(rolled_back.err().map(|failure| failure.index), Some(1));
+        assert_eq!(after_rollback, after_debug);
+        assert_eq!(
+            after_performance,
+            ["debug.jar.disabled", "lithium.jar", "sodium.jar"]
+        );
+        assert_eq!(deleted, Ok(true));
+        assert_eq!(profiles, Ok(vec![performance]));
+    }
 
-        bytes[0..4].copy_from_slice(b"PK\x06\x06");
-        bytes[4..12].copy_from_slice(&44_u64.to_le_bytes());
-        bytes[32..40].copy_from_slice(&(MAX_ZIP_ENTRIES + 1).to_le_bytes());
-        bytes[40..48].copy_from_slice(&(MAX_ZIP_CENTRAL_DIRECTORY_SIZE + 1).to_le_bytes());
+    fn bisect_fixture(name: &str) -> (PathBuf, String) {
+        let game_directory = temp_game_directory(name);
+        let game_directory_text = game_directory.display().to_string();
+        let fixtures = [
+            ("a.jar", r#"{"id": "a"}"#),
+            ("b.jar", r#"{"id": "b", "depends": {"lib": "*"}}"#),
+            ("c.jar", r#"{"id": "c"}"#),
+            ("d.jar", r#"{"id": "d"}"#),
+            ("e.jar", r#"{"id": "e"}"#),
+            ("lib.jar", r#"{"id": "lib"}"#),
+            ("off.jar.disabled", r#"{"id": "off"}"#),
+        ];
+        for (filename, json) in fixtures {
+            fs::write(
+                game_directory.join("mods").join(filename),
+                zip_bytes(&[("fabric.mod.json", json.as_bytes())]),
+            )
+            .expect("Fixture should be written");
+        }
+        (game_directory, game_directory_text)
+    }
 
-        bytes[56..60].copy_from_slice(b"PK\x06\x07");
-        bytes[64..72].copy_from_slice(&0_u64.to_le_bytes());
+    fn installed_mod_filenames(game_directory: &Path) -> Vec<String> {
+        let mut filenames: Vec<String> = mod_directory_snapshot(&game_directory.join("mods"))
+            .into_keys()
+            .collect();
+        filenames.sort();
+        filenames
+    }
 
-        bytes[76..80].copy_from_slice(b"PK\x05\x06");
-        bytes[84..86].copy_from_slice(&1_u16.to_le_bytes());
-        bytes[86..88].copy_from_slice(&1_u16.to_le_bytes());
-        bytes[88..92].copy_from_slice(&1_u32.to_le_bytes());
-        bytes[92..96].copy_from_slice(&u32::MAX.to_le_bytes());
+    /// Answer every round as broken while `culprit` is enabled, returning each round's mods.
+    fn run_bisection(
+        game_directory: &Path,
+        game_directory_text: &str,
+        suspects: Option<Vec<String>>,
+        culprit: &str,
+    ) -> (Result<LocalModBisectSession, String>, Vec<Vec<String>>) {
+        let mut session = start_mod_bisect_blocking(game_directory_text, suspects);
+        let mut launches = Vec::new();
+        while let Ok(current) = &session {
+            if current.finished || launches.len() > 10 {
+                break;
+            }
+            let enabled = installed_mod_filenames(game_directory);
+            let works = !enabled.iter().any(|filename| filename == culprit);
+            launches.push(enabled);
+            session = record_mod_bisect_result_blocking(game_directory_text, works);
+        }
+        (session, launches)
+    }
 
-        fs::write(&path, bytes).expect("Temporary ZIP64 fixture should be written");
-        let mut file = File::open(&path).expect("Temporary ZIP64 fixture should open");
-        let result = preflight_zip_archive(&mut file);
-        let _ = fs::remove_file(path);
+    #[test]
+    fn bisection_finds_the_culprit_and_restores_the_original_mods() {
+        let (game_directory, game_directory_text) = bisect_fixture("mod-bisect-culprit");
+        let before = installed_mod_filenames(&game_directory);
+
+        let (session, launches) =
+            run_bisection(&game_directory, &game_directory_text, None, "c.jar");
+        let after = installed_mod_filenames(&game_directory);
+        let persisted = read_mod_bisect(
+            &mod_bisect_path(&game_directory_text).expect("Bisection path should resolve"),
+        );
+        let _ = fs::remove_dir_all(&game_directory);
+
+        let session = session.expect("Bisection should finish");
+        assert!(session.finished);
+        assert_eq!(session.culprit.as_deref(), Some("c.jar"));
+        assert!(launches.len() <= 4);
+        assert_eq!(after, before);
+        assert_eq!(persisted, Ok(None));
+    }
 
-        assert!(result.is_err());
-        assert!(result
-            .expect_err("ZIP64 fixture should exceed the limits")
-            .contains("ZIP entries"));
+    #[test]
+    fn bisection_keeps_dependencies_of_tested_mods_enabled() {
+        let (game_directory, game_directory_text) = bisect_fixture("mod-bisect-dependencies");
+
+        let (session, launches) =
+            run_bisection(&game_directory, &game_directory_text, None, "c.jar");
+        let _ = fs::remove_dir_all(&game_directory);
+
+        assert!(session.is_ok());
+        assert!(!launches.is_empty());
+        for enabled in &launches {
+            assert!(enabled.contains(&"off.jar.disabled".to_string()));
+            if enabled.contains(&"b.jar".to_string()) {
+                assert!(enabled.contains(&"lib.jar".to_string()));
+            }
+        }
     }
 
     #[test]
-    fn actual_central_directory_size_cannot_hide_behind_a_small_declared_size() {
-        let filename = format!(
-            "epherome-actual-central-directory-limit-{}-{}.jar",
-            std::process::id(),
-            SystemTime::now()
-                .duration_since(UNIX_EPOCH)
-                .unwrap_or_default()
-                .as_nanos()
+    fn bisection_keeps_suspects_required_by_cleared_mods_enabled() {
+        let (game_directory, game_directory_text) = bisect_fixture("mod-bisect-cleared");
+        let suspects = ["a.jar", "c.jar", "d.jar", "lib.jar"].map(str::to_string);
+
+        let (session, launches) = run_bisection(
+            &game_directory,
+            &game_directory_text,
+            Some(suspects.to_vec()),
+            "a.jar",
         );
-        let path = std::env::temp_dir().join(filename);
-        let mut file = File::create(&path).expect("Temporary ZIP fixture should be created");
-        let filename_length = u16::MAX;
-        let entry_size = ZIP_CENTRAL_DIRECTORY_HEADER_SIZE + filename_length as u64;
-        let entry_count = MAX_ZIP_CENTRAL_DIRECTORY_SIZE / entry_size + 1;
-        let entry_count_u16 =
-            u16::try_from(entry_count).expect("Fixture entry count should fit a classic ZIP");
-        let mut header = [0_u8; ZIP_CENTRAL_DIRECTORY_HEADER_SIZE as usize];
-        header[0..4].copy_from_slice(b"PK\x01\x02");
-        header[28..30].copy_from_slice(&filename_length.to_le_bytes());
-
-        let mut position = 0_u64;
-        for _ in 0..entry_count {
-            file.seek(SeekFrom::Start(position))
-                .expect("Fixture central directory seek should succeed");
-            file.write_all(&header)
-                .expect("Fixture central directory header should be written");
-            position = position
-                .checked_add(entry_size)
-                .expect("Fixture central directory size should not overflow");
+        let _ = fs::remove_dir_all(&game_directory);
+
+        let session = session.expect("Bisection should finish");
+        assert_eq!(session.culprit.as_deref(), Some("a.jar"));
+        assert!(!launches.is_empty());
+        for enabled in &launches {
+            assert!(enabled.contains(&"b.jar".to_string()));
+            assert!(enabled.contains(&"lib.jar".to_string()));
         }
+    }
 
-        let mut end_record = [0_u8; ZIP_EOCD_MIN_SIZE as usize];
-        end_record[0..4].copy_from_slice(b"PK\x05\x06");
-        end_record[8..10].copy_from_slice(&entry_count_u16.to_le_bytes());
-        end_record[10..12].copy_from_slice(&entry_count_u16.to_le_bytes());
-        end_record[12..16]
-            .copy_from_slice(&(ZIP_CENTRAL_DIRECTORY_HEADER_SIZE as u32).to_le_bytes());
-        end_record[16..20].copy_from_slice(&0_u32.to_le_bytes());
-        file.seek(SeekFrom::Start(position))
-            .expect("Fixture end record seek should succeed");
-        file.write_all(&end_record)
-            .expect("Fixture end record should be written");
-        drop(file);
+    #[test]
+    fn bisection_leaves_mods_installed_mid_session_alone() {
+        let (game_directory, game_directory_text) = bisect_fixture("mod-bisect-new-mods");
+        let mods = game_directory.join("mods");
+
+        let started = start_mod_bisect_blocking(&game_directory_text, None);
+        for filename in ["new.jar", "new-off.jar.disabled"] {
+            fs::write(
+                mods.join(filename),
+                zip_bytes(&[("fabric.mod.json", br#"{"id": "new"}"#)]),
+            )
+            .expect("Mod should be installed");
+        }
+        let next_round = record_mod_bisect_result_blocking(&game_directory_text, true);
+        let during = installed_mod_filenames(&game_directory);
+        let cancelled = cancel_mod_bisect_blocking(&game_directory_text);
+        let after = installed_mod_filenames(&game_directory);
+        let _ = fs::remove_dir_all(&game_directory);
+
+        assert!(started.is_ok());
+        assert!(next_round.is_ok());
+        assert_eq!(cancelled, Ok(true));
+        for installed in [&during, &after] {
+            assert!(installed.contains(&"new.jar".to_string()));
+            assert!(installed.contains(&"new-off.jar.disabled".to_string()));
+        }
+    }
 
-        let mut file = File::open(&path).expect("Temporary ZIP fixture should open");
-        let result = preflight_zip_archive(&mut file);
-        let _ = fs::remove_file(path);
+    #[test]
+    fn only_one_bisection_runs_at_a_time() {
+        let (game_directory, game_directory_text) = bisect_fixture("mod-bisect-duplicate");
+
+        let first = start_mod_bisect_blocking(&game_directory_text, None);
+        let duplicate = start_mod_bisect_blocking(&game_directory_text, None);
+        let cancelled = cancel_mod_bisect_blocking(&game_directory_text);
+        let _ = fs::remove_dir_all(&game_directory);
 
-        let error = result.expect_err("The actual central directory should exceed the limit");
-        assert!(error.contains("actual JAR ZIP directory"));
-        assert!(error.contains("inspection limit"));
+        assert!(first.is_ok());
+        assert!(duplicate.is_err());
+        assert_eq!(cancelled, Ok(true));
     }
 
     #[test]
-    fn central_directory_entry_cannot_cross_the_classic_end_record() {
-        let filename = format!(
-            "epherome-central-directory-boundary-{}-{}.jar",
-            std::process::id(),
-            SystemTime::now()
-                .duration_since(UNIX_EPOCH)
-                .unwrap_or_default()
-                .as_nanos()
-        );
-        let path = std::env::temp_dir().join(filename);
-        let mut bytes =
-            vec![0_u8; (ZIP_CENTRAL_DIRECTORY_HEADER_SIZE + ZIP_EOCD_MIN_SIZE) as usize];
-        bytes[0..4].copy_from_slice(b"PK\x01\x02");
-        bytes[28..30].copy_from_slice(&1_u16.to_le_bytes());
-        let end_record_offset = ZIP_CENTRAL_DIRECTORY_HEADER_SIZE as usize;
-        bytes[end_record_offset..end_record_offset + 4].copy_from_slice(b"PK\x05\x06");
-        bytes[end_record_offset + 8..end_record_offset + 10].copy_from_slice(&1_u16.to_le_bytes());
-        bytes[end_record_offset + 10..end_record_offset + 12].copy_from_slice(&1_u16.to_le_bytes());
-        bytes[end_record_offset + 12..end_record_offset + 16]
-            .copy_from_slice(&(ZIP_CENTRAL_DIRECTORY_HEADER_SIZE as u32).to_le_bytes());
-        bytes[end_record_offset + 16..end_record_offset + 20].copy_from_slice(&0_u32.to_le_bytes());
-        fs::write(&path, bytes).expect("Temporary ZIP fixture should be written");
-
-        let mut file = File::open(&path).expect("Temporary ZIP fixture should open");
-        let result = preflight_zip_archive(&mut file);
-        let _ = fs::remove_file(path);
+    fn cancelling_a_bisection_of_chosen_suspects_restores_the_original_mods() {
+        let (game_directory, game_directory_text) = bisect_fixture("mod-bisect-cancel");
+        let before = installed_mod_filenames(&game_directory);
 
-        assert!(result
-            .expect_err("The central directory entry should cross the classic end record")
-            .contains("crosses into the ZIP end structure"));
+        let session = start_mod_bisect_blocking(
+            &game_directory_text,
+            Some(vec!["a.jar".to_string(), "d.jar.disabled".to_string()]),
+        );
+        let during_cancel = installed_mod_filenames(&game_directory);
+        let cancelled = cancel_mod_bisect_blocking(&game_directory_text);
+        let cancelled_again = cancel_mod_bisect_blocking(&game_directory_text);
+        let after_cancel = installed_mod_filenames(&game_directory);
+        let _ = fs::remove_dir_all(&game_directory);
+
+        let session = session.expect("Bisection of chosen suspects should start");
+        assert_eq!(session.suspects, ["a.jar", "d.jar"]);
+        assert_eq!(session.testing, ["a.jar"]);
+        assert!(during_cancel.contains(&"d.jar.disabled".to_string()));
+        assert_eq!(cancelled, Ok(true));
+        assert_eq!(cancelled_again, Ok(false));
+        assert_eq!(after_cancel, before);
     }
 
     #[test]
-    fn zip64_central_directory_uses_its_own_offset_and_end_record_boundary() {
-        let filename = format!(
-            "epherome-zip64-central-directory-boundary-{}-{}.jar",
-            std::process::id(),
-            SystemTime::now()
-                .duration_since(UNIX_EPOCH)
-                .unwrap_or_default()
-                .as_nanos()
+    fn batch_state_changes_are_all_or_nothing() {
+        let game_directory = temp_game_directory("mod-state-batch");
+        let game_directory_text = game_directory.display().to_string();
+        let mods = game_directory.join("mods");
+        for filename in [
+            "a.jar",
+            "b.jar",
+            "c.jar.disabled",
+            "conflict.jar",
+            "conflict.jar.disabled",
+        ] {
+            fs::write(mods.join(filename), filename).expect("Fixture should be written");
+        }
+        let installed = || {
+            let mut filenames: Vec<String> = mod_directory_snapshot(&mods).into_keys().collect();
+            filenames.sort();
+            filenames
+        };
+        let before = installed();
+
+        let rejected = set_local_mods_enabled_blocking(
+            &game_directory_text,
+            vec![
+                "a.jar".to_string(),
+                "conflict.jar".to_string(),
+                "missing.jar".to_string(),
+                "../escape.jar".to_string(),
+            ],
+            false,
+        )
+        .expect("Batch should report per-file failures");
+        let after_rejected = installed();
+
+        let applied = set_local_mods_enabled_blocking(
+            &game_directory_text,
+            vec![
+                "a.jar".to_string(),
+                "b.jar".to_string(),
+                "c.jar.disabled".to_string(),
+            ],
+            false,
+        )
+        .expect("Batch should apply");
+        let after_applied = installed();
+        let _ = fs::remove_dir_all(&game_directory);
+
+        let mut failed: Vec<(&str, bool)> = rejected
+            .failed
+            .iter()
+            .map(|failure| {
+                (
+                    failure.filename.as_str(),
+                    failure.reason.contains("another mod"),
+                )
+            })
+            .collect();
+        failed.sort();
+        assert_eq!(
+            failed,
+            [
+                ("../escape.jar", false),
+                ("a.jar", true),
+                ("conflict.jar", false),
+                ("missing.jar", false),
+            ]
         );
-        let path = std::env::temp_dir().join(filename);
-        let zip64_end_offset = ZIP_CENTRAL_DIRECTORY_HEADER_SIZE as usize;
-        let locator_offset = zip64_end_offset + 56;
-        let end_record_offset = locator_offset + 20;
-        let mut bytes = vec![0_u8; end_record_offset + ZIP_EOCD_MIN_SIZE as usize];
-
-        bytes[0..4].copy_from_slice(b"PK\x01\x02");
-        bytes[28..30].copy_from_slice(&1_u16.to_le_bytes());
-
-        bytes[zip64_end_offset..zip64_end_offset + 4].copy_from_slice(b"PK\x06\x06");
-        bytes[zip64_end_offset + 4..zip64_end_offset + 12].copy_from_slice(&44_u64.to_le_bytes());
-        bytes[zip64_end_offset + 24..zip64_end_offset + 32].copy_from_slice(&1_u64.to_le_bytes());
-        bytes[zip64_end_offset + 32..zip64_end_offset + 40].copy_from_slice(&1_u64.to_le_bytes());
-        bytes[zip64_end_offset + 40..zip64_end_offset + 48]
-            .copy_from_slice(&ZIP_CENTRAL_DIRECTORY_HEADER_SIZE.to_le_bytes());
-        bytes[zip64_end_offset + 48..zip64_end_offset + 56].copy_from_slice(&0_u64.to_le_bytes());
-
-        bytes[locator_offset..locator_offset + 4].copy_from_slice(b"PK\x06\x07");
-        bytes[locator_offset + 8..locator_offset + 16]
-            .copy_from_slice(&(zip64_end_offset as u64).to_le_bytes());
-
-        bytes[end_record_offset..end_record_offset + 4].copy_from_slice(b"PK\x05\x06");
-        bytes[end_record_offset + 8..end_record_offset + 10].copy_from_slice(&1_u16.to_le_bytes());
-        bytes[end_record_offset + 10..end_record_offset + 12].copy_from_slice(&1_u16.to_le_bytes());
-        bytes[end_record_offset + 12..end_record_offset + 16]
-            .copy_from_slice(&(ZIP_CENTRAL_DIRECTORY_HEADER_SIZE as u32).to_le_bytes());
-        bytes[end_record_offset + 16..end_record_offset + 20]
-            .copy_from_slice(&u32::MAX.to_le_bytes());
-        fs::write(&path, bytes).expect("Temporary ZIP64 fixture should be written");
-
-        let mut file = File::open(&path).expect("Temporary ZIP64 fixture should open");
-        let result = preflight_zip_archive(&mut file);
-        let _ = fs::remove_file(path);
+        assert!(rejected.changed.is_empty());
+        assert_eq!(after_rejected, before);
 
-        assert!(result
-            .expect_err("The central directory entry should cross the ZIP64 end record")
-            .contains("crosses into the ZIP end structure"));
+        let changed: Vec<(&str, &str, bool)> = applied
+            .changed
+            .iter()
+            .map(|rename| {
+                (
+                    rename.from.as_str(),
+                    rename.file.filename.as_str(),
+                    rename.file.enabled,
+                )
+            })
+            .collect();
+        assert_eq!(
+            changed,
+            [
+                ("a.jar", "a.jar.disabled", false),
+                ("b.jar", "b.jar.disabled", false),
+            ]
+        );
+        assert_eq!(applied.unchanged, ["c.jar.disabled"]);
+        assert!(applied.failed.is_empty());
+        assert_eq!(
+            after_applied,
+            [
+                "a.jar.disabled",
+                "b.jar.disabled",
+                "c.jar.disabled",
+                "conflict.jar",
+                "conflict.jar.disabled",
+            ]
+        );
     }
 
     #[test]
-    fn bounds_repeated_forge_metadata_text_before_ipc() {
-        let mods = (0..MAX_METADATA_ENTRIES_PER_DESCRIPTOR)
-            .map(|_| {
-                r#"
-                    [[mods]]
-                    modId = "same_id"
-                    version = "1.0.0"
-                    displayName = "Repeated Mod"
-                "#
-            })
-            .collect::<String>();
-        let long_version = "x".repeat(MAX_METADATA_FIELD_BYTES * 2);
-        let text = format!(
-            r#"
-                modLoader = "javafml"
-                loaderVersion = "[1,)"
-                {mods}
+    fn imports_jars_from_zip_bundles_and_folders() {
+        let game_directory = temp_game_directory("bundle-import");
+        let jar = zip_bytes(&[("fabric.mod.json", br#"{"id":"a"}"#)]);
+        let long_name = format!("mods/{}.jar", "x".repeat(600));
+        let bundle = game_directory.join("bundle.zip");
+        fs::write(
+            &bundle,
+            zip_bytes(&[
+                ("mods/a.jar", jar.as_slice()),
+                ("mods/broken.jar", b"not a jar"),
+                (long_name.as_str(), jar.as_slice()),
+                ("__MACOSX/mods/._a.jar", b"resource fork"),
+                ("readme.txt", b"install these"),
+            ]),
+        )
+        .expect("Bundle should be written");
+        let folder = game_directory.join("pack");
+        fs::create_dir_all(folder.join("extra")).expect("Folder bundle should be created");
+        fs::write(folder.join("extra").join("b.jar"), &jar).expect("Mod should be written");
+        fs::write(folder.join("a.jar"), &jar).expect("Mod should be written");
+        fs::write(folder.join("broken.jar"), b"not a jar").expect("Mod should be written");
+        fs::write(folder.join("notes.txt"), b"ignored").expect("Notes should be written");
+
+        let result = import_local_mods_blocking(
+            &game_directory.to_string_lossy(),
+            vec![
+                bundle.to_string_lossy().into_owned(),
+                folder.to_string_lossy().into_owned(),
+            ],
+        )
+        .expect("Bundles should be imported");
 
-                [[dependencies.same_id]]
-                modId = "neoforge"
-                type = "required"
-                versionRange = "{long_version}"
-            "#
+        assert_eq!(result.imported, vec!["a.jar", "b.jar"]);
+        let failed: Vec<&str> = result
+            .failed
+            .iter()
+            .map(|failure| failure.filename.as_str())
+            .collect();
+        assert_eq!(
+            failed,
+            vec![
+                "bundle.zip",
+                "bundle.zip/mods/broken.jar",
+                "pack/a.jar",
+                "pack/broken.jar"
+            ]
+        );
+        assert!(result.failed[0].reason.contains("-byte name"));
+        assert!(result.failed[2].reason.contains("already installed"));
+        assert!(result.failed[3].reason.contains("not a valid JAR"));
+        assert!(!game_directory.join("mods").join("broken.jar").exists());
+        assert_eq!(
+            fs::read(game_directory.join("mods").join("b.jar")).expect("Imported mod should exist"),
+            jar
         );
-        let entries = parse_forge_metadata(&text, "META-INF/neoforge.mods.toml", "NeoForge", None)
-            .expect("Repeated Forge metadata should parse");
-
-        assert!(entries.iter().all(|entry| {
-            entry.dependencies[0]
-                .version
-                .as_deref()
-                .is_some_and(|version| version.len() <= MAX_METADATA_FIELD_BYTES)
-        }));
 
-        let mut metadata = LocalModMetadata::default();
-        append_metadata_entries(&mut metadata, "META-INF/neoforge.mods.toml", entries);
-        let serialized = serde_json::to_vec(&metadata.entries).expect("Metadata should serialize");
+        let empty = game_directory.join("empty.zip");
+        fs::write(&empty, zip_bytes(&[("readme.txt", b"nothing")]))
+            .expect("Empty bundle should be written");
+        let result = import_local_mods_blocking(
+            &game_directory.to_string_lossy(),
+            vec![empty.to_string_lossy().into_owned()],
+        )
+        .expect("Empty bundle should be reported");
+        let _ = fs::remove_dir_all(&game_directory);
 
-        assert!(serialized.len() <= MAX_METADATA_SERIALIZED_BYTES_PER_JAR);
-        assert!(metadata
-            .diagnostics
-            .iter()
-            .any(|diagnostic| diagnostic.contains("serialized data limit")));
+        assert_eq!(result.failed[0].filename, "empty.zip");
     }
 }
@@ -0,0 +1,412 @@
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::io::{Read, Write};
+
+/// Vanilla refuses deeper trees as well.
+const MAX_NBT_DEPTH: usize = 512;
+
+const TAG_END: u8 = 0;
+const TAG_BYTE: u8 = 1;
+const TAG_SHORT: u8 = 2;
+const TAG_INT: u8 = 3;
+const TAG_LONG: u8 = 4;
+const TAG_FLOAT: u8 = 5;
+const TAG_DOUBLE: u8 = 6;
+const TAG_BYTE_ARRAY: u8 = 7;
+const TAG_STRING: u8 = 8;
+const TAG_LIST: u8 = 9;
+const TAG_COMPOUND: u8 = 10;
+const TAG_INT_ARRAY: u8 = 11;
+const TAG_LONG_ARRAY: u8 = 12;
+
+/// A string in Java's modified UTF-8, kept as raw bytes so untouched values round-trip exactly.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NbtString(Vec<u8>);
+
+impl NbtString {
+    pub fn new(text: &str) -> Self {
+        let mut bytes = Vec::with_capacity(text.len());
+        for unit in text.encode_utf16() {
+            match unit {
+                0x0001..=0x007f => bytes.push(unit as u8),
+                0x0000 | 0x0080..=0x07ff => {
+                    bytes.push(0xc0 | (unit >> 6) as u8);
+                    bytes.push(0x80 | (unit & 0x3f) as u8);
+                }
+                _ => {
+                    bytes.push(0xe0 | (unit >> 12) as u8);
+                    bytes.push(0x80 | ((unit >> 6) & 0x3f) as u8);
+                    bytes.push(0x80 | (unit & 0x3f) as u8);
+                }
+            }
+        }
+        Self(bytes)
+    }
+
+    pub fn to_string_lossy(&self) -> String {
+        let mut units = Vec::with_capacity(self.0.len());
+        let mut bytes = self.0.iter().copied();
+        while let Some(first) = bytes.next() {
+            let unit = if first < 0x80 {
+                u16::from(first)
+            } else if first & 0xe0 == 0xc0 {
+                let second = bytes.next().unwrap_or(0);
+                (u16::from(first & 0x1f) << 6) | u16::from(second & 0x3f)
+            } else if first & 0xf0 == 0xe0 {
+                let second = bytes.next().unwrap_or(0);
+                let third = bytes.next().unwrap_or(0);
+                (u16::from(first & 0x0f) << 12)
+                    | (u16::from(second & 0x3f) << 6)
+                    | u16::from(third & 0x3f)
+            } else {
+                0xfffd
+            };
+            units.push(unit);
+        }
+        String::from_utf16_lossy(&units)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum NbtTag {
+    Byte(i8),
+    Short(i16),
+    Int(i32),
+    Long(i64),
+    Float(f32),
+    Double(f64),
+    ByteArray(Vec<u8>),
+    String(NbtString),
+    /// Element type and elements; empty lists keep the type they were read with.
+    List(u8, Vec<NbtTag>),
+    /// Entries in file order.
+    Compound(Vec<(NbtString, NbtTag)>),
+    IntArray(Vec<i32>),
+    LongArray(Vec<i64>),
+}
+
+impl NbtTag {
+    fn id(&self) -> u8 {
+        match self {
+            NbtTag::Byte(_) => TAG_BYTE,
+            NbtTag::Short(_) => TAG_SHORT,
+            NbtTag::Int(_) => TAG_INT,
+            NbtTag::Long(_) => TAG_LONG,
+            NbtTag::Float(_) => TAG_FLOAT,
+            NbtTag::Double(_) => TAG_DOUBLE,
+            NbtTag::ByteArray(_) => TAG_BYTE_ARRAY,
+            NbtTag::String(_) => TAG_STRING,
+            NbtTag::List(..) => TAG_LIST,
+            NbtTag::Compound(_) => TAG_COMPOUND,
+            NbtTag::IntArray(_) => TAG_INT_ARRAY,
+            NbtTag::LongArray(_) => TAG_LONG_ARRAY,
+        }
+    }
+
+    pub fn get(&self, key: &str) -> Option<&NbtTag> {
+        let NbtTag::Compound(entries) = self else {
+            return None;
+        };
+        let key = NbtString::new(key);
+        entries
+            .iter()
+            .find(|(name, _)| *name == key)
+            .map(|(_, tag)| tag)
+    }
+
+    pub fn get_mut(&mut self, key: &str) -> Option<&mut NbtTag> {
+        let NbtTag::Compound(entries) = self else {
+            return None;
+        };
+        let key = NbtString::new(key);
+        entries
+            .iter_mut()
+            .find(|(name, _)| *name == key)
+            .map(|(_, tag)| tag)
+    }
+
+    pub fn as_string(&self) -> Option<String> {
+        match self {
+            NbtTag::String(value) => Some(value.to_string_lossy()),
+            _ => None,
+        }
+    }
+
+    /// The strings of a string list; an empty list of any type counts as empty.
+    pub fn as_string_list(&self) -> Option<Vec<String>> {
+        match self {
+            NbtTag::List(_, items) => items.iter().map(NbtTag::as_string).collect(),
+            _ => None,
+        }
+    }
+
+    pub fn string_list(items: &[String]) -> Self {
+        NbtTag::List(
+            TAG_STRING,
+            items
+                .iter()
+                .map(|item| NbtTag::String(NbtString::new(item)))
+                .collect(),
+        )
+    }
+}
+
+struct NbtReader<'a> {
+    bytes: &'a [u8],
+}
+
+impl NbtReader<'_> {
+    fn take(&mut self, length: usize) -> Result<&[u8], String> {
+        if length > self.bytes.len() {
+            return Err("The NBT data ends unexpectedly.".to_string());
+        }
+        let (taken, rest) = self.bytes.split_at(length);
+        self.bytes = rest;
+        Ok(taken)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], String> {
+        let mut array = [0; N];
+        array.copy_from_slice(self.take(N)?);
+        Ok(array)
+    }
+
+    fn length(&mut self, element_size: usize) -> Result<usize, String> {
+        let length = i32::from_be_bytes(self.array()?);
+        let length = usize::try_from(length)
+            .map_err(|_| "The NBT data has a negative length.".to_string())?;
+        // Every element needs at least this many bytes, which bounds allocations.
+        if length.saturating_mul(element_size.max(1)) > self.bytes.len() {
+            return Err("The NBT data declares more elements than it contains.".to_string());
+        }
+        Ok(length)
+    }
+
+    fn string(&mut self) -> Result<NbtString, String> {
+        let length = u16::from_be_bytes(self.array()?);
+        Ok(NbtString(self.take(usize::from(length))?.to_vec()))
+    }
+
+    fn payload(&mut self, id: u8, depth: usize) -> Result<NbtTag, String> {
+        if depth > MAX_NBT_DEPTH {
+            return Err("The NBT data is nested too deeply.".to_string());
+        }
+        Ok(match id {
+            TAG_BYTE => NbtTag::Byte(i8::from_be_bytes(self.array()?)),
+            TAG_SHORT => NbtTag::Short(i16::from_be_bytes(self.array()?)),
+            TAG_INT => NbtTag::Int(i32::from_be_bytes(self.array()?)),
+            TAG_LONG => NbtTag::Long(i64::from_be_bytes(self.array()?)),
+            TAG_FLOAT => NbtTag::Float(f32::from_be_bytes(self.array()?)),
+            TAG_DOUBLE => NbtTag::Double(f64::from_be_bytes(self.array()?)),
+            TAG_BYTE_ARRAY => {
+                let length = self.length(1)?;
+                NbtTag::ByteArray(self.take(length)?.to_vec())
+            }
+            TAG_STRING => NbtTag::String(self.string()?),
+            TAG_LIST => {
+                let element_id = self.array::<1>()?[0];
+                let length = self.length(if element_id == TAG_END { 0 } else { 1 })?;
+                if element_id == TAG_END && length > 0 {
+                    return Err("The NBT data has a non-empty list of end tags.".to_string());
+                }
+                let items = (0..length)
+                    .map(|_| self.payload(element_id, depth + 1))
+                    .collect::<Result<_, _>>()?;
+                NbtTag::List(element_id, items)
+            }
+            TAG_COMPOUND => {
+                let mut entries = Vec::new();
+                loop {
+                    let entry_id = self.array::<1>()?[0];
+                    if entry_id == TAG_END {
+                        break;
+                    }
+                    let name = self.string()?;
+                    entries.push((name, self.payload(entry_id, depth + 1)?));
+                }
+                NbtTag::Compound(entries)
+            }
+            TAG_INT_ARRAY => {
+                let length = self.length(4)?;
+                let values = (0..length)
+                    .map(|_| self.array().map(i32::from_be_bytes))
+                    .collect::<Result<_, _>>()?;
+                NbtTag::IntArray(values)
+            }
+            TAG_LONG_ARRAY => {
+                let length = self.length(8)?;
+                let values = (0..length)
+                    .map(|_| self.array().map(i64::from_be_bytes))
+                    .collect::<Result<_, _>>()?;
+                NbtTag::LongArray(values)
+            }
+            _ => return Err(format!("The NBT data has an unknown tag type {id}.")),
+        })
+    }
+}
+
+fn write_string(output: &mut Vec<u8>, value: &NbtString) -> Result<(), String> {
+    let length =
+        u16::try_from(value.0.len()).map_err(|_| "An NBT string is too long.".to_string())?;
+    output.extend_from_slice(&length.to_be_bytes());
+    output.extend_from_slice(&value.0);
+    Ok(())
+}
+
+fn write_length(output: &mut Vec<u8>, length: usize) -> Result<(), String> {
+    let length = i32::try_from(length).map_err(|_| "An NBT array is too long.".to_string())?;
+    output.extend_from_slice(&length.to_be_bytes());
+    Ok(())
+}
+
+fn write_payload(output: &mut Vec<u8>, tag: &NbtTag) -> Result<(), String> {
+    match tag {
+        NbtTag::Byte(value) => output.extend_from_slice(&value.to_be_bytes()),
+        NbtTag::Short(value) => output.extend_from_slice(&value.to_be_bytes()),
+        NbtTag::Int(value) => output.extend_from_slice(&value.to_be_bytes()),
+        NbtTag::Long(value) => output.extend_from_slice(&value.to_be_bytes()),
+        NbtTag::Float(value) => output.extend_from_slice(&value.to_be_bytes()),
+        NbtTag::Double(value) => output.extend_from_slice(&value.to_be_bytes()),
+        NbtTag::ByteArray(values) => {
+            write_length(output, values.len())?;
+            output.extend_from_slice(values);
+        }
+        NbtTag::String(value) => write_string(output, value)?,
+        NbtTag::List(element_id, items) => {
+            if items.iter().any(|item| item.id() != *element_id) {
+                return Err("An NBT list mixes tag types.".to_string());
+            }
+            output.push(*element_id);
+            write_length(output, items.len())?;
+            for item in items {
+                write_payload(output, item)?;
+            }
+        }
+        NbtTag::Compound(entries) => {
+            for (name, value) in entries {
+                output.push(value.id());
+                write_string(output, name)?;
+                write_payload(output, value)?;
+            }
+            output.push(TAG_END);
+        }
+        NbtTag::IntArray(values) => {
+            write_length(output, values.len())?;
+            for value in values {
+                output.extend_from_slice(&value.to_be_bytes());
+            }
+        }
+        NbtTag::LongArray(values) => {
+            write_length(output, values.len())?;
+            for value in values {
+                output.extend_from_slice(&value.to_be_bytes());
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Decode a gzip-compressed NBT file such as `level.dat` into its root name and compound.
+pub fn read_gzip_nbt(compressed: &[u8], size_limit: u64) -> Result<(NbtString, NbtTag), String> {
+    let mut bytes = Vec::new();
+    GzDecoder::new(compressed)
+        .take(size_limit + 1)
+        .read_to_end(&mut bytes)
+        .map_err(|error| format!("The file is not valid gzip data: {error}"))?;
+    if bytes.len() as u64 > size_limit {
+        return Err(format!(
+            "The decompressed NBT data exceeds the {size_limit}-byte limit."
+        ));
+    }
+
+    let mut reader = NbtReader { bytes: &bytes };
+    if reader.array::<1>()?[0] != TAG_COMPOUND {
+        return Err("The NBT root is not a compound.".to_string());
+    }
+    let name = reader.string()?;
+    let root = reader.payload(TAG_COMPOUND, 0)?;
+    if !reader.bytes.is_empty() {
+        return Err("The NBT data has trailing bytes.".to_string());
+    }
+    Ok((name, root))
+}
+
+pub fn write_gzip_nbt(name: &NbtString, root: &NbtTag) -> Result<Vec<u8>, String> {
+    let mut bytes = vec![root.id()];
+    write_string(&mut bytes, name)?;
+    write_payload(&mut bytes, root)?;
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder
+        .write_all(&bytes)
+        .and_then(|_| encoder.finish())
+        .map_err(|error| format!("Failed to compress NBT data: {error}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_every_tag_type_and_modified_utf8() {
+        let root = NbtTag::Compound(vec![(
+            NbtString::new("Data"),
+            NbtTag::Compound(vec![
+                (NbtString::new("b"), NbtTag::Byte(-1)),
+                (NbtString::new("s"), NbtTag::Short(300)),
+                (NbtString::new("i"), NbtTag::Int(-70000)),
+                (NbtString::new("l"), NbtTag::Long(1 << 40)),
+                (NbtString::new("f"), NbtTag::Float(1.5)),
+                (NbtString::new("d"), NbtTag::Double(-2.25)),
+                (NbtString::new("ba"), NbtTag::ByteArray(vec![1, 2, 255])),
+                (
+                    NbtString::new("name"),
+                    NbtTag::String(NbtString::new("Wörld \0 🌍")),
+                ),
+                (NbtString::new("empty"), NbtTag::List(TAG_END, Vec::new())),
+                (
+                    NbtString::new("packs"),
+                    NbtTag::string_list(&["vanilla".to_string(), "file/a.zip".to_string()]),
+                ),
+                (NbtString::new("ia"), NbtTag::IntArray(vec![1, -1])),
+                (NbtString::new("la"), NbtTag::LongArray(vec![i64::MIN])),
+            ]),
+        )]);
+
+        let encoded = write_gzip_nbt(&NbtString::new(""), &root).expect("NBT should encode");
+        let (name, decoded) = read_gzip_nbt(&encoded, 1024).expect("NBT should decode");
+
+        assert_eq!(name, NbtString::new(""));
+        assert_eq!(decoded, root);
+        let data = decoded.get("Data").expect("Data should exist");
+        assert_eq!(
+            data.get("name").and_then(NbtTag::as_string).as_deref(),
+            Some("Wörld \0 🌍")
+        );
+        assert_eq!(
+            data.get("packs").and_then(NbtTag::as_string_list),
+            Some(vec!["vanilla".to_string(), "file/a.zip".to_string()])
+        );
+        // Java encodes NUL as two bytes and supplementary characters as surrogate pairs.
+        assert_eq!(NbtString::new("\0").0, [0xc0, 0x80]);
+        assert_eq!(NbtString::new("🌍").0.len(), 6);
+    }
+
+    #[test]
+    fn rejects_truncated_and_oversized_data() {
+        let root = NbtTag::Compound(vec![(NbtString::new("a"), NbtTag::IntArray(vec![1; 64]))]);
+        let encoded = write_gzip_nbt(&NbtString::new(""), &root).expect("NBT should encode");
+
+        assert!(read_gzip_nbt(&encoded, 16).is_err());
+        let mut truncated = Vec::new();
+        GzDecoder::new(encoded.as_slice())
+            .read_to_end(&mut truncated)
+            .expect("NBT should decompress");
+        truncated.truncate(truncated.len() - 8);
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&truncated).expect("NBT should compress");
+        let truncated = encoder.finish().expect("NBT should compress");
+        assert!(read_gzip_nbt(&truncated, 1024).is_err());
+        assert!(read_gzip_nbt(b"not gzip", 1024).is_err());
+    }
+}
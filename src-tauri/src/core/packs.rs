@@ -0,0 +1,992 @@
+use crate::core::archive::{
+    cleanup_import_failure, create_import_temp_file, is_launcher_temp_filename, path_exists,
+    preflight_zip_archive, publish_import_temp, read_zip_entries, sha1_reader_hex,
+    validate_single_filename, write_file_atomically, zip_contains_entries, ImportFailure,
+    ImportResult, ZipEntryContents, PNG_SIGNATURE,
+};
+use crate::core::mods::{create_launcher_directory, game_directory_path, LAUNCHER_DIRECTORY_NAME};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use std::fs::{self, File};
+use std::io::{self, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use tokio::sync::Mutex;
+
+const PACK_METADATA_PATH: &str = "pack.mcmeta";
+const PACK_ICON_PATH: &str = "pack.png";
+const PACK_ICON_CACHE_DIRECTORY_NAME: &str = "pack-icons";
+const OPTIONS_FILENAME: &str = "options.txt";
+const RESOURCE_PACKS_OPTION: &str = "resourcePacks";
+const INCOMPATIBLE_RESOURCE_PACKS_OPTION: &str = "incompatibleResourcePacks";
+const IRIS_PROPERTIES_PATH: &str = "config/iris.properties";
+const OPTIFINE_SHADER_OPTIONS_FILENAME: &str = "optionsshaders.txt";
+/// OptiFine's `shaderPack` value when shaders are off.
+const OPTIFINE_SHADERS_OFF: &str = "OFF";
+const MAX_PACK_METADATA_SIZE: u64 = 1024 * 1024;
+const MAX_PACK_ICON_SIZE: u64 = 2 * 1024 * 1024;
+const MAX_OPTIONS_FILE_SIZE: u64 = 4 * 1024 * 1024;
+/// Properties files Iris and OptiFine read from a shader pack.
+const SHADER_PROPERTIES_PATHS: [&str; 5] = [
+    "shaders/shaders.properties",
+    "shaders/block.properties",
+    "shaders/item.properties",
+    "shaders/entity.properties",
+    "shaders/dimension.properties",
+];
+
+/// Serializes pack changes, since enabling a pack rewrites shared option files.
+static PACKS_OPERATION_LOCK: Mutex<()> = Mutex::const_new(());
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum LocalPackKind {
+    ResourcePack,
+    ShaderPack,
+}
+
+impl LocalPackKind {
+    fn directory_name(self) -> &'static str {
+        match self {
+            LocalPackKind::ResourcePack => "resourcepacks",
+            LocalPackKind::ShaderPack => "shaderpacks",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct PackFormatRange {
+    pub min: i64,
+    pub max: i64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalPack {
+    pub filename: String,
+    /// True for unpacked folder packs.
+    pub directory: bool,
+    /// Resource packs: listed in `options.txt`. Shader packs: the selected pack.
+    pub enabled: bool,
+    /// Archive size in bytes; 0 for folder packs.
+    pub size: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pack_format: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub supported_formats: Option<PackFormatRange>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub has_icon: bool,
+    /// Shader packs only: the Iris/OptiFine properties files the pack ships.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub properties_files: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub diagnostics: Vec<String>,
+}
+
+fn packs_directory(game_directory: &str, kind: LocalPackKind) -> Result<PathBuf, String> {
+    Ok(game_directory_path(game_directory)?.join(kind.directory_name()))
+}
+
+pub fn is_pack_archive_filename(filename: &str) -> bool {
+    filename.to_ascii_lowercase().ends_with(".zip")
+}
+
+/// Read a small file; `None` when it does not exist.
+fn read_limited_file(path: &Path, size_limit: u64) -> Option<Result<Vec<u8>, String>> {
+    let metadata = match fs::symlink_metadata(path) {
+        Ok(metadata) => metadata,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => return None,
+        Err(error) => return Some(Err(format!("Failed to access the file: {error}"))),
+    };
+    if !metadata.is_file() {
+        return Some(Err("The path is not a regular file.".to_string()));
+    }
+    if metadata.len() > size_limit {
+        return Some(Err(format!(
+            "The file is too large to inspect ({} bytes; limit is {size_limit} bytes).",
+            metadata.len()
+        )));
+    }
+    Some(fs::read(path).map_err(|error| format!("Failed to read the file: {error}")))
+}
+
+fn read_limited_text(path: &Path, size_limit: u64) -> Result<Option<String>, String> {
+    match read_limited_file(path, size_limit) {
+        None => Ok(None),
+        Some(bytes) => {
+            let bytes = bytes.map_err(|error| format!("{}: {error}", path.display()))?;
+            String::from_utf8(bytes)
+                .map(Some)
+                .map_err(|error| format!("'{}' is not valid UTF-8: {error}", path.display()))
+        }
+    }
+}
+
+/// Read entries from a pack archive or folder, in the shape of `read_zip_entries`.
+fn read_pack_entries(
+    path: &Path,
+    directory: bool,
+    paths: &[&str],
+    size_limit: u64,
+) -> Result<ZipEntryContents, String> {
+    if directory {
+        return Ok(paths
+            .iter()
+            .map(|entry| read_limited_file(&path.join(entry), size_limit))
+            .collect());
+    }
+    let mut file = File::open(path)
+        .map_err(|error| format!("Failed to open pack '{}': {error}", path.display()))?;
+    read_zip_entries(&mut file, paths, size_limit)
+}
+
+/// Whether each entry exists in a pack archive or folder, without reading it.
+fn pack_contains_entries(
+    path: &Path,
+    directory: bool,
+    paths: &[&str],
+) -> Result<Vec<bool>, String> {
+    if directory {
+        return paths
+            .iter()
+            .map(|entry| path_exists(&path.join(entry)))
+            .collect();
+    }
+    let mut file = File::open(path)
+        .map_err(|error| format!("Failed to open pack '{}': {error}", path.display()))?;
+    zip_contains_entries(&mut file, paths)
+}
+
+/// Flatten a JSON text component into plain text.
+fn text_component(value: &JsonValue) -> String {
+    match value {
+        JsonValue::String(text) => text.clone(),
+        JsonValue::Array(parts) => parts.iter().map(text_component).collect(),
+        JsonValue::Object(object) => {
+            let mut text = object
+                .get("text")
+                .or_else(|| object.get("translate"))
+                .map(text_component)
+                .unwrap_or_default();
+            if let Some(extra) = object.get("extra") {
+                text.push_str(&text_component(extra));
+            }
+            text
+        }
+        JsonValue::Number(number) => number.to_string(),
+        JsonValue::Bool(value) => value.to_string(),
+        JsonValue::Null => String::new(),
+    }
+}
+
+/// A single format, `[min, max]`, or `{"min_inclusive", "max_inclusive"}`.
+fn pack_format_range(value: &JsonValue) -> Option<PackFormatRange> {
+    match value {
+        JsonValue::Number(number) => {
+            let format = number.as_i64()?;
+            Some(PackFormatRange {
+                min: format,
+                max: format,
+            })
+        }
+        JsonValue::Array(bounds) => Some(PackFormatRange {
+            min: bounds.first()?.as_i64()?,
+            max: bounds.get(1)?.as_i64()?,
+        }),
+        JsonValue::Object(bounds) => Some(PackFormatRange {
+            min: bounds.get("min_inclusive")?.as_i64()?,
+            max: bounds.get("max_inclusive")?.as_i64()?,
+        }),
+        _ => None,
+    }
+}
+
+/// Major version of a 1.21.9+ `min_format`/`max_format`, which may be `[major, minor]`.
+fn major_pack_format(value: &JsonValue) -> Option<i64> {
+    value
+        .as_i64()
+        .or_else(|| value.as_array()?.first()?.as_i64())
+}
+
+/// The `pack` section of a `pack.mcmeta` file.
+fn pack_section(bytes: &[u8]) -> Result<JsonValue, String> {
+    let text = std::str::from_utf8(bytes)
+        .map_err(|error| format!("The file is not valid UTF-8: {error}"))?;
+    let mut document: JsonValue = serde_json::from_str(text.trim_start_matches('\u{feff}'))
+        .map_err(|error| format!("The file is not valid JSON: {error}"))?;
+    document
+        .get_mut("pack")
+        .filter(|section| section.is_object())
+        .map(JsonValue::take)
+        .ok_or_else(|| "The file has no \"pack\" section.".to_string())
+}
+
+fn apply_pack_metadata(pack: &mut LocalPack, bytes: &[u8]) -> Result<(), String> {
+    let section = pack_section(bytes)?;
+    pack.pack_format = section.get("pack_format").and_then(JsonValue::as_i64);
+    pack.supported_formats = section
+        .get("supported_formats")
+        .and_then(pack_format_range)
+        .or_else(|| {
+            Some(PackFormatRange {
+                min: major_pack_format(section.get("min_format")?)?,
+                max: major_pack_format(section.get("max_format")?)?,
+            })
+        });
+    pack.description = section
+        .get("description")
+        .map(|description| text_component(description).trim().to_string())
+        .filter(|description| !description.is_empty());
+    Ok(())
+}
+
+fn inspect_pack(path: &Path, kind: LocalPackKind, filename: String, directory: bool) -> LocalPack {
+    let mut pack = LocalPack {
+        filename,
+        directory,
+        enabled: false,
+        size: if directory {
+            0
+        } else {
+            fs::metadata(path)
+                .map(|metadata| metadata.len())
+                .unwrap_or(0)
+        },
+        pack_format: None,
+        supported_formats: None,
+        description: None,
+        has_icon: false,
+        properties_files: Vec::new(),
+        diagnostics: Vec::new(),
+    };
+
+    match kind {
+        LocalPackKind::ResourcePack => {
+            let entries = read_pack_entries(
+                path,
+                directory,
+                &[PACK_METADATA_PATH, PACK_ICON_PATH],
+                MAX_PACK_ICON_SIZE.max(MAX_PACK_METADATA_SIZE),
+            );
+            let mut entries = match entries {
+                Ok(entries) => entries.into_iter(),
+                Err(error) => {
+                    pack.diagnostics
+                        .push(format!("Pack inspection was skipped: {error}"));
+                    return pack;
+                }
+            };
+            match entries.next().flatten() {
+                Some(Ok(bytes)) => {
+                    if let Err(error) = apply_pack_metadata(&mut pack, &bytes) {
+                        pack.diagnostics
+                            .push(format!("{PACK_METADATA_PATH}: {error}"));
+                    }
+                }
+                Some(Err(error)) => pack
+                    .diagnostics
+                    .push(format!("{PACK_METADATA_PATH}: {error}")),
+                None => pack
+                    .diagnostics
+                    .push(format!("{PACK_METADATA_PATH} is missing.")),
+            }
+            pack.has_icon = matches!(
+                entries.next().flatten(),
+                Some(Ok(bytes)) if bytes.starts_with(PNG_SIGNATURE)
+            );
+        }
+        LocalPackKind::ShaderPack => {
+            match pack_contains_entries(path, directory, &SHADER_PROPERTIES_PATHS) {
+                Ok(present) => {
+                    pack.properties_files = SHADER_PROPERTIES_PATHS
+                        .iter()
+                        .zip(present)
+                        .filter(|(_, present)| *present)
+                        .map(|(path, _)| path.to_string())
+                        .collect();
+                    if pack.properties_files.is_empty() {
+                        pack.diagnostics
+                            .push("No Iris or OptiFine properties files were found.".to_string());
+                    }
+                }
+                Err(error) => pack
+                    .diagnostics
+                    .push(format!("Pack inspection was skipped: {error}")),
+            }
+        }
+    }
+    pack
+}
+
+/// Datapacks share the resource pack layout: `pack.mcmeta` plus an optional `pack.png`.
+pub fn inspect_data_pack(path: &Path, filename: String, directory: bool) -> LocalPack {
+    inspect_pack(path, LocalPackKind::ResourcePack, filename, directory)
+}
+
+/// Value of a `key:value` line in `options.txt`.
+fn option_value<'a>(text: &'a str, key: &str) -> Option<&'a str> {
+    text.lines()
+        .find_map(|line| line.strip_prefix(key)?.strip_prefix(':'))
+}
+
+/// Value of a `key=value` line in a properties file.
+fn property_value<'a>(text: &'a str, key: &str) -> Option<&'a str> {
+    text.lines().find_map(|line| {
+        let (name, value) = line.split_once('=')?;
+        (name.trim() == key).then(|| value.trim())
+    })
+}
+
+/// Replace the first `key{separator}` line, or append one; other lines are kept as they are.
+fn set_line_value(text: &str, key: &str, separator: char, value: &str) -> String {
+    let mut found = false;
+    let mut lines: Vec<String> = text
+        .lines()
+        .map(|line| {
+            let matches = line
+                .split_once(separator)
+                .is_some_and(|(name, _)| name.trim() == key);
+            if matches && !found {
+                found = true;
+                format!("{key}{separator}{value}")
+            } else {
+                line.to_string()
+            }
+        })
+        .collect();
+    if !found {
+        lines.push(format!("{key}{separator}{value}"));
+    }
+    let mut updated = lines.join("\n");
+    updated.push('\n');
+    updated
+}
+
+fn resource_pack_list(text: &str, key: &str) -> Vec<String> {
+    option_value(text, key)
+        .and_then(|value| serde_json::from_str(value).ok())
+        .unwrap_or_default()
+}
+
+/// The id Minecraft records for a pack loaded from a file or folder.
+pub fn file_pack_id(filename: &str) -> String {
+    format!("file/{filename}")
+}
+
+fn selected_shader_pack(game_directory: &Path) -> Result<Option<String>, String> {
+    if let Some(text) = read_limited_text(
+        &game_directory.join(IRIS_PROPERTIES_PATH),
+        MAX_OPTIONS_FILE_SIZE,
+    )? {
+        if property_value(&text, "enableShaders") == Some("false") {
+            return Ok(None);
+        }
+        return Ok(property_value(&text, "shaderPack")
+            .filter(|pack| !pack.is_empty())
+            .map(str::to_string));
+    }
+    let text = read_limited_text(
+        &game_directory.join(OPTIFINE_SHADER_OPTIONS_FILENAME),
+        MAX_OPTIONS_FILE_SIZE,
+    )?;
+    Ok(text.and_then(|text| {
+        property_value(&text, "shaderPack")
+            .filter(|pack| !pack.is_empty() && *pack != OPTIFINE_SHADERS_OFF)
+            .map(str::to_string)
+    }))
+}
+
+/// Add or remove a resource pack in `options.txt`, keeping the order of the others.
+fn set_resource_pack_enabled(
+    game_directory: &Path,
+    filename: &str,
+    enabled: bool,
+) -> Result<(), String> {
+    let path = game_directory.join(OPTIONS_FILENAME);
+    let mut text = read_limited_text(&path, MAX_OPTIONS_FILE_SIZE)?.unwrap_or_default();
+    let id = file_pack_id(filename);
+    let mut packs = resource_pack_list(&text, RESOURCE_PACKS_OPTION);
+    packs.retain(|pack| *pack != id);
+    if enabled {
+        // Later entries take priority, so a newly enabled pack goes on top.
+        packs.push(id.clone());
+    } else {
+        let mut incompatible = resource_pack_list(&text, INCOMPATIBLE_RESOURCE_PACKS_OPTION);
+        if incompatible.contains(&id) {
+            incompatible.retain(|pack| *pack != id);
+            text = set_line_value(
+                &text,
+                INCOMPATIBLE_RESOURCE_PACKS_OPTION,
+                ':',
+                &JsonValue::from(incompatible).to_string(),
+            );
+        }
+    }
+    text = set_line_value(
+        &text,
+        RESOURCE_PACKS_OPTION,
+        ':',
+        &JsonValue::from(packs).to_string(),
+    );
+    write_file_atomically(&path, text.as_bytes())
+}
+
+/// Select or deselect a shader pack for Iris and, if the instance has it, OptiFine.
+fn set_shader_pack_enabled(
+    game_directory: &Path,
+    filename: &str,
+    enabled: bool,
+) -> Result<(), String> {
+    let iris_path = game_directory.join(IRIS_PROPERTIES_PATH);
+    let optifine_path = game_directory.join(OPTIFINE_SHADER_OPTIONS_FILENAME);
+    let iris = read_limited_text(&iris_path, MAX_OPTIONS_FILE_SIZE)?;
+    let optifine = read_limited_text(&optifine_path, MAX_OPTIONS_FILE_SIZE)?;
+
+    if enabled {
+        let mut text = iris.unwrap_or_default();
+        text = set_line_value(&text, "shaderPack", '=', filename);
+        text = set_line_value(&text, "enableShaders", '=', "true");
+        if let Some(config_directory) = iris_path.parent() {
+            fs::create_dir_all(config_directory).map_err(|error| {
+                format!("Failed to create '{}': {error}", config_directory.display())
+            })?;
+        }
+        write_file_atomically(&iris_path, text.as_bytes())?;
+        if let Some(text) = optifine {
+            let text = set_line_value(&text, "shaderPack", '=', filename);
+            write_file_atomically(&optifine_path, text.as_bytes())?;
+        }
+        return Ok(());
+    }
+
+    if let Some(text) = iris.filter(|text| property_value(text, "shaderPack") == Some(filename)) {
+        let text = set_line_value(&text, "enableShaders", '=', "false");
+        write_file_atomically(&iris_path, text.as_bytes())?;
+    }
+    if let Some(text) = optifine.filter(|text| property_value(text, "shaderPack") == Some(filename))
+    {
+        let text = set_line_value(&text, "shaderPack", '=', OPTIFINE_SHADERS_OFF);
+        write_file_atomically(&optifine_path, text.as_bytes())?;
+    }
+    Ok(())
+}
+
+/// Whether `pack` is enabled, given the parsed option files.
+fn pack_enabled(
+    kind: LocalPackKind,
+    filename: &str,
+    resource_packs: &[String],
+    shader_pack: Option<&str>,
+) -> bool {
+    match kind {
+        LocalPackKind::ResourcePack => resource_packs.contains(&file_pack_id(filename)),
+        LocalPackKind::ShaderPack => shader_pack == Some(filename),
+    }
+}
+
+fn enabled_resource_packs(game_directory: &Path) -> Result<Vec<String>, String> {
+    let text = read_limited_text(
+        &game_directory.join(OPTIONS_FILENAME),
+        MAX_OPTIONS_FILE_SIZE,
+    )?;
+    Ok(text
+        .map(|text| resource_pack_list(&text, RESOURCE_PACKS_OPTION))
+        .unwrap_or_default())
+}
+
+/// An installed pack's path and whether it is a folder.
+fn installed_pack(
+    game_directory: &str,
+    kind: LocalPackKind,
+    filename: &str,
+) -> Result<(PathBuf, bool), String> {
+    validate_single_filename(filename)?;
+    let path = packs_directory(game_directory, kind)?.join(filename);
+    let metadata = fs::symlink_metadata(&path)
+        .map_err(|error| format!("Failed to access pack '{filename}': {error}"))?;
+    if metadata.is_dir() {
+        Ok((path, true))
+    } else if metadata.is_file() && is_pack_archive_filename(filename) {
+        Ok((path, false))
+    } else {
+        Err(format!("'{filename}' is not a pack."))
+    }
+}
+
+fn scan_local_packs_blocking(
+    game_directory: &str,
+    kind: LocalPackKind,
+) -> Result<Vec<LocalPack>, String> {
+    let root = game_directory_path(game_directory)?;
+    let directory = packs_directory(game_directory, kind)?;
+    let entries = match fs::read_dir(&directory) {
+        Ok(entries) => entries,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(error) => {
+            return Err(format!("Failed to read '{}': {error}", directory.display()));
+        }
+    };
+    let resource_packs = match kind {
+        LocalPackKind::ResourcePack => enabled_resource_packs(&root)?,
+        LocalPackKind::ShaderPack => Vec::new(),
+    };
+    let shader_pack = match kind {
+        LocalPackKind::ResourcePack => None,
+        LocalPackKind::ShaderPack => selected_shader_pack(&root)?,
+    };
+
+    let mut packs = Vec::new();
+    for entry in entries {
+        let entry = entry.map_err(|error| {
+            format!(
+                "Failed to read an entry in '{}': {error}",
+                directory.display()
+            )
+        })?;
+        let Ok(filename) = entry.file_name().into_string() else {
+            continue;
+        };
+        if is_launcher_temp_filename(&filename) {
+            continue;
+        }
+        let Ok(metadata) = fs::symlink_metadata(entry.path()) else {
+            continue;
+        };
+        let folder = metadata.is_dir();
+        let archive = metadata.is_file() && is_pack_archive_filename(&filename);
+        if !folder && !archive {
+            continue;
+        }
+        let enabled = pack_enabled(kind, &filename, &resource_packs, shader_pack.as_deref());
+        let mut pack = inspect_pack(&entry.path(), kind, filename, folder);
+        pack.enabled = enabled;
+        packs.push(pack);
+    }
+    packs.sort_by_key(|pack| pack.filename.to_lowercase());
+    Ok(packs)
+}
+
+fn set_local_pack_enabled_blocking(
+    game_directory: &str,
+    kind: LocalPackKind,
+    filename: &str,
+    enabled: bool,
+) -> Result<LocalPack, String> {
+    let (path, directory) = installed_pack(game_directory, kind, filename)?;
+    let root = game_directory_path(game_directory)?;
+    match kind {
+        LocalPackKind::ResourcePack => set_resource_pack_enabled(&root, filename, enabled)?,
+        LocalPackKind::ShaderPack => set_shader_pack_enabled(&root, filename, enabled)?,
+    }
+    let mut pack = inspect_pack(&path, kind, filename.to_string(), directory);
+    pack.enabled = enabled;
+    Ok(pack)
+}
+
+fn import_pack(
+    packs_directory: &Path,
+    require_metadata: bool,
+    source_path: &str,
+) -> Result<String, String> {
+    if source_path.trim().is_empty() {
+        return Err("Source path cannot be empty.".to_string());
+    }
+    let source = Path::new(source_path);
+    let filename = source
+        .file_name()
+        .and_then(|name| name.to_str())
+        .ok_or_else(|| "Source file has an invalid filename.".to_string())?;
+    validate_single_filename(filename)?;
+    if !is_pack_archive_filename(filename) {
+        return Err("Only '.zip' packs can be imported.".to_string());
+    }
+    let source_metadata =
+        fs::metadata(source).map_err(|error| format!("Failed to access source file: {error}"))?;
+    if !source_metadata.is_file() {
+        return Err("Source path is not a regular file.".to_string());
+    }
+    let destination = packs_directory.join(filename);
+    if path_exists(&destination)? {
+        return Err(format!("A pack named '{filename}' is already installed."));
+    }
+
+    let mut source_file =
+        File::open(source).map_err(|error| format!("Failed to open source file: {error}"))?;
+    preflight_zip_archive(&mut source_file)
+        .map_err(|error| format!("'{filename}' is not a valid pack: {error}"))?;
+    if require_metadata {
+        let metadata = read_zip_entries(
+            &mut source_file,
+            &[PACK_METADATA_PATH],
+            MAX_PACK_METADATA_SIZE,
+        )?
+        .pop()
+        .flatten()
+        .ok_or_else(|| format!("'{filename}' has no {PACK_METADATA_PATH}."))?;
+        metadata
+            .and_then(|bytes| pack_section(&bytes))
+            .map_err(|error| {
+                format!("'{filename}' has an invalid {PACK_METADATA_PATH}: {error}")
+            })?;
+    }
+    source_file
+        .seek(SeekFrom::Start(0))
+        .map_err(|error| format!("Failed to read source file: {error}"))?;
+
+    let (temp_path, mut temp_file) = create_import_temp_file(packs_directory)?;
+    let copied = io::copy(&mut source_file, &mut temp_file).and_then(|_| temp_file.sync_all());
+    drop(temp_file);
+    if let Err(error) = copied {
+        return Err(cleanup_import_failure(
+            &temp_path,
+            format!("Failed to copy pack file: {error}"),
+        ));
+    }
+    publish_import_temp(&temp_path, &destination, filename)?;
+    Ok(filename.to_string())
+}
+
+/// Copy `.zip` packs into `directory`, reporting each failure separately.
+pub fn import_pack_archives(
+    directory: &Path,
+    require_metadata: bool,
+    source_paths: Vec<String>,
+) -> Result<ImportResult, String> {
+    fs::create_dir_all(directory)
+        .map_err(|error| format!("Failed to create '{}': {error}", directory.display()))?;
+    let metadata = fs::symlink_metadata(directory)
+        .map_err(|error| format!("Failed to access '{}': {error}", directory.display()))?;
+    if metadata.file_type().is_symlink() || !metadata.is_dir() {
+        return Err(format!(
+            "Pack directory '{}' must be a real directory.",
+            directory.display()
+        ));
+    }
+
+    let mut result = ImportResult {
+        imported: Vec::with_capacity(source_paths.len()),
+        failed: Vec::new(),
+    };
+    for source_path in source_paths {
+        let display_filename = Path::new(&source_path)
+            .file_name()
+            .map(|filename| filename.to_string_lossy().into_owned())
+            .unwrap_or_else(|| source_path.clone());
+        match import_pack(directory, require_metadata, &source_path) {
+            Ok(filename) => result.imported.push(filename),
+            Err(reason) => result.failed.push(ImportFailure {
+                filename: display_filename,
+                reason,
+            }),
+        }
+    }
+    Ok(result)
+}
+
+fn import_local_packs_blocking(
+    game_directory: &str,
+    kind: LocalPackKind,
+    source_paths: Vec<String>,
+) -> Result<ImportResult, String> {
+    let directory = packs_directory(game_directory, kind)?;
+    import_pack_archives(
+        &directory,
+        kind == LocalPackKind::ResourcePack,
+        source_paths,
+    )
+}
+
+fn local_pack_icon_blocking(
+    game_directory: &str,
+    kind: LocalPackKind,
+    filename: &str,
+) -> Result<Option<String>, String> {
+    let (path, directory) = installed_pack(game_directory, kind, filename)?;
+    let Some(bytes) = read_pack_entries(&path, directory, &[PACK_ICON_PATH], MAX_PACK_ICON_SIZE)?
+        .pop()
+        .flatten()
+    else {
+        return Ok(None);
+    };
+    let bytes = bytes.map_err(|error| format!("{PACK_ICON_PATH}: {error}"))?;
+    if !bytes.starts_with(PNG_SIGNATURE) {
+        return Err(format!("{PACK_ICON_PATH}: the icon is not a PNG image."));
+    }
+
+    // Cached by icon content, so identical icons share one file.
+    let hash = sha1_reader_hex(&mut bytes.as_slice())?;
+    let cache_directory = game_directory_path(game_directory)?
+        .join(LAUNCHER_DIRECTORY_NAME)
+        .join(PACK_ICON_CACHE_DIRECTORY_NAME);
+    let cached_path = cache_directory.join(format!("{hash}.png"));
+    if !path_exists(&cached_path)? {
+        create_launcher_directory(&cache_directory)?;
+        write_file_atomically(&cached_path, &bytes)?;
+    }
+    Ok(Some(cached_path.to_string_lossy().into_owned()))
+}
+
+#[tauri::command]
+pub async fn scan_local_packs(
+    game_directory: String,
+    kind: LocalPackKind,
+) -> Result<Vec<LocalPack>, String> {
+    let operation_guard = PACKS_OPERATION_LOCK.lock().await;
+    tokio::task::spawn_blocking(move || {
+        let _operation_guard = operation_guard;
+        scan_local_packs_blocking(&game_directory, kind)
+    })
+    .await
+    .map_err(|error| format!("Pack scan task failed: {error}"))?
+}
+
+#[tauri::command]
+pub async fn import_local_packs(
+    game_directory: String,
+    kind: LocalPackKind,
+    source_paths: Vec<String>,
+) -> Result<ImportResult, String> {
+    let operation_guard = PACKS_OPERATION_LOCK.lock().await;
+    tokio::task::spawn_blocking(move || {
+        let _operation_guard = operation_guard;
+        import_local_packs_blocking(&game_directory, kind, source_paths)
+    })
+    .await
+    .map_err(|error| format!("Pack import task failed: {error}"))?
+}
+
+#[tauri::command]
+pub async fn set_local_pack_enabled(
+    game_directory: String,
+    kind: LocalPackKind,
+    filename: String,
+    enabled: bool,
+) -> Result<LocalPack, String> {
+    let operation_guard = PACKS_OPERATION_LOCK.lock().await;
+    tokio::task::spawn_blocking(move || {
+        let _operation_guard = operation_guard;
+        set_local_pack_enabled_blocking(&game_directory, kind, &filename, enabled)
+    })
+    .await
+    .map_err(|error| format!("Pack state task failed: {error}"))?
+}
+
+#[tauri::command]
+pub async fn get_local_pack_icon(
+    game_directory: String,
+    kind: LocalPackKind,
+    filename: String,
+) -> Result<Option<String>, String> {
+    let operation_guard = PACKS_OPERATION_LOCK.lock().await;
+    tokio::task::spawn_blocking(move || {
+        let _operation_guard = operation_guard;
+        local_pack_icon_blocking(&game_directory, kind, &filename)
+    })
+    .await
+    .map_err(|error| format!("Pack icon task failed: {error}"))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::test_fixtures::{temp_directory, write_zip};
+
+    #[test]
+    fn reads_pack_metadata_formats_and_text_components() {
+        let mut pack = inspect_pack(
+            Path::new("/nonexistent"),
+            LocalPackKind::ShaderPack,
+            "x".to_string(),
+            true,
+        );
+        apply_pack_metadata(
+            &mut pack,
+            br#"{"pack": {
+                "pack_format": 15,
+                "supported_formats": {"min_inclusive": 15, "max_inclusive": 34},
+                "description": [{"text": "Faithful ", "extra": [{"text": "32x"}]}, "!"]
+            }}"#,
+        )
+        .expect("Metadata should parse");
+        assert_eq!(pack.pack_format, Some(15));
+        assert_eq!(
+            pack.supported_formats,
+            Some(PackFormatRange { min: 15, max: 34 })
+        );
+        assert_eq!(pack.description.as_deref(), Some("Faithful 32x!"));
+
+        apply_pack_metadata(
+            &mut pack,
+            br#"{"pack": {"min_format": [69, 0], "max_format": 70, "description": ""}}"#,
+        )
+        .expect("Metadata should parse");
+        assert_eq!(pack.pack_format, None);
+        assert_eq!(
+            pack.supported_formats,
+            Some(PackFormatRange { min: 69, max: 70 })
+        );
+        assert_eq!(pack.description, None);
+        assert!(apply_pack_metadata(&mut pack, b"{}").is_err());
+    }
+
+    #[test]
+    fn scans_imports_and_toggles_packs_through_option_files() {
+        let game_directory = temp_directory("packs-scan");
+        let game_directory_text = game_directory.display().to_string();
+        let downloads = game_directory.join("downloads");
+        fs::create_dir_all(&downloads).expect("Downloads should be created");
+        fs::create_dir_all(game_directory.join("resourcepacks/Folder Pack"))
+            .expect("Folder pack should be created");
+        fs::write(
+            game_directory.join("resourcepacks/Folder Pack/pack.mcmeta"),
+            r#"{"pack": {"pack_format": 34, "description": "Folder"}}"#,
+        )
+        .expect("Folder metadata should be written");
+        write_zip(
+            &downloads.join("Faithful.zip"),
+            &[
+                (
+                    "pack.mcmeta",
+                    br#"{"pack": {"pack_format": 15, "description": {"text": "Faithful"}}}"#,
+                ),
+                ("pack.png", b"\x89PNG\r\n\x1a\nicon"),
+            ],
+        );
+        write_zip(&downloads.join("NotAPack.zip"), &[("readme.txt", b"hi")]);
+        write_zip(
+            &downloads.join("Complementary.zip"),
+            &[
+                ("shaders/shaders.properties", b"sliders=A"),
+                ("shaders/final.fsh", b""),
+            ],
+        );
+        fs::write(
+            game_directory.join("options.txt"),
+            "version:3465\nresourcePacks:[\"vanilla\",\"file/Folder Pack\"]\nincompatibleResourcePacks:[\"file/Faithful.zip\"]\nlang:en_us\n",
+        )
+        .expect("Options should be written");
+        fs::write(
+            game_directory.join("optionsshaders.txt"),
+            "shaderPack=OFF\nantialiasingLevel=0\n",
+        )
+        .expect("OptiFine options should be written");
+
+        let source = |name: &str| downloads.join(name).display().to_string();
+        let imported = import_local_packs_blocking(
+            &game_directory_text,
+            LocalPackKind::ResourcePack,
+            vec![
+                source("Faithful.zip"),
+                source("NotAPack.zip"),
+                source("Faithful.zip"),
+            ],
+        )
+        .expect("Resource packs should be imported");
+        let shader_import = import_local_packs_blocking(
+            &game_directory_text,
+            LocalPackKind::ShaderPack,
+            vec![source("Complementary.zip")],
+        )
+        .expect("Shader packs should be imported");
+
+        let resource_packs =
+            scan_local_packs_blocking(&game_directory_text, LocalPackKind::ResourcePack)
+                .expect("Resource packs should be scanned");
+        let enabled = set_local_pack_enabled_blocking(
+            &game_directory_text,
+            LocalPackKind::ResourcePack,
+            "Faithful.zip",
+            true,
+        )
+        .expect("Resource pack should be enabled");
+        set_local_pack_enabled_blocking(
+            &game_directory_text,
+            LocalPackKind::ResourcePack,
+            "Folder Pack",
+            false,
+        )
+        .expect("Resource pack should be disabled");
+        let options = fs::read_to_string(game_directory.join("options.txt"));
+        let icon = local_pack_icon_blocking(
+            &game_directory_text,
+            LocalPackKind::ResourcePack,
+            "Faithful.zip",
+        );
+
+        set_local_pack_enabled_blocking(
+            &game_directory_text,
+            LocalPackKind::ShaderPack,
+            "Complementary.zip",
+            true,
+        )
+        .expect("Shader pack should be enabled");
+        let shader_packs =
+            scan_local_packs_blocking(&game_directory_text, LocalPackKind::ShaderPack)
+                .expect("Shader packs should be scanned");
+        let iris = fs::read_to_string(game_directory.join(IRIS_PROPERTIES_PATH));
+        set_local_pack_enabled_blocking(
+            &game_directory_text,
+            LocalPackKind::ShaderPack,
+            "Complementary.zip",
+            false,
+        )
+        .expect("Shader pack should be disabled");
+        let optifine = fs::read_to_string(game_directory.join("optionsshaders.txt"));
+        let shader_enabled_after = selected_shader_pack(&game_directory);
+        let _ = fs::remove_dir_all(&game_directory);
+
+        assert_eq!(imported.imported, ["Faithful.zip"]);
+        let failed: Vec<&str> = imported
+            .failed
+            .iter()
+            .map(|failure| failure.filename.as_str())
+            .collect();
+        assert_eq!(failed, ["NotAPack.zip", "Faithful.zip"]);
+        assert_eq!(shader_import.imported, ["Complementary.zip"]);
+
+        let summary: Vec<(&str, bool, bool, Option<i64>, bool)> = resource_packs
+            .iter()
+            .map(|pack| {
+                (
+                    pack.filename.as_str(),
+                    pack.directory,
+                    pack.enabled,
+                    pack.pack_format,
+                    pack.has_icon,
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("Faithful.zip", false, false, Some(15), true),
+                ("Folder Pack", true, true, Some(34), false),
+            ]
+        );
+        assert_eq!(resource_packs[0].description.as_deref(), Some("Faithful"));
+        assert!(enabled.enabled);
+        assert_eq!(
+            options.expect("Options should be readable"),
+            "version:3465\nresourcePacks:[\"vanilla\",\"file/Faithful.zip\"]\nincompatibleResourcePacks:[\"file/Faithful.zip\"]\nlang:en_us\n"
+        );
+        assert!(icon
+            .expect("Icon should be extracted")
+            .is_some_and(|path| path.ends_with(".png")));
+
+        assert_eq!(shader_packs.len(), 1);
+        assert!(shader_packs[0].enabled);
+        assert_eq!(
+            shader_packs[0].properties_files,
+            ["shaders/shaders.properties"]
+        );
+        assert_eq!(
+            iris.expect("Iris properties should be written"),
+            "shaderPack=Complementary.zip\nenableShaders=true\n"
+        );
+        assert_eq!(
+            optifine.expect("OptiFine options should be readable"),
+            "shaderPack=OFF\nantialiasingLevel=0\n"
+        );
+        assert_eq!(shader_enabled_after, Ok(None));
+    }
+}
@@ -0,0 +1,57 @@
+//! Temporary directories and ZIP archives shared by the backend tests.
+
+use std::fs;
+use std::io::{Cursor, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+use zip::write::SimpleFileOptions;
+
+static NEXT_DIRECTORY_ID: AtomicU64 = AtomicU64::new(0);
+
+/// Create an empty directory under the system temp directory, unique to this call.
+pub(crate) fn temp_directory(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!(
+        "epherome-{name}-{}-{}-{}",
+        std::process::id(),
+        NEXT_DIRECTORY_ID.fetch_add(1, Ordering::Relaxed),
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos()
+    ));
+    fs::create_dir_all(&path).expect("Temporary directory should be created");
+    path
+}
+
+/// Create a temporary game directory with an empty `mods` folder.
+pub(crate) fn temp_game_directory(name: &str) -> PathBuf {
+    let path = temp_directory(name);
+    fs::create_dir_all(path.join("mods")).expect("Temporary mods directory should be created");
+    path
+}
+
+/// Build a deflated ZIP archive in memory from `(path, contents)` entries.
+pub(crate) fn zip_bytes(entries: &[(&str, &[u8])]) -> Vec<u8> {
+    let mut archive = zip::ZipWriter::new(Cursor::new(Vec::new()));
+    for (path, contents) in entries {
+        archive
+            .start_file(
+                *path,
+                SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated),
+            )
+            .expect("Fixture entry should be created");
+        archive
+            .write_all(contents)
+            .expect("Fixture entry should be written");
+    }
+    archive
+        .finish()
+        .expect("Fixture archive should be finished")
+        .into_inner()
+}
+
+/// Write a ZIP archive built by [`zip_bytes`] to `path`.
+pub(crate) fn write_zip(path: &Path, entries: &[(&str, &[u8])]) {
+    fs::write(path, zip_bytes(entries)).expect("Fixture archive should be written");
+}
@@ -0,0 +1,589 @@
+use serde_json::Value as JsonValue;
+use std::cmp::Ordering;
+
+/// Rank of an unqualified release among Maven-style qualifiers.
+const RELEASE_QUALIFIER_RANK: u8 = 5;
+/// Unknown qualifiers sort after releases and then alphabetically.
+const UNKNOWN_QUALIFIER_RANK: u8 = 7;
+
+/// Grammar a dependency version requirement is written in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VersionSyntax {
+    /// Fabric and Quilt predicates such as `>=0.15.0 <0.16`, `~1.20.1` or `1.20.x`.
+    Predicate,
+    /// Maven ranges used by Forge and NeoForge, such as `[47,)` or `[1.20.1,1.21)`.
+    MavenRange,
+}
+
+impl VersionSyntax {
+    pub fn for_loader(loader: &str) -> Self {
+        match loader {
+            "Fabric" | "Quilt" => VersionSyntax::Predicate,
+            _ => VersionSyntax::MavenRange,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum VersionItem {
+    /// Decimal digits without leading zeros; empty for zero.
+    Number(String),
+    /// Lowercase letters between separators.
+    Qualifier(String),
+}
+
+fn qualifier_rank(qualifier: &str) -> u8 {
+    match qualifier {
+        "alpha" => 0,
+        "beta" | "pre" => 1,
+        "milestone" => 2,
+        "rc" | "cr" => 3,
+        "snapshot" => 4,
+        "ga" | "final" | "release" => RELEASE_QUALIFIER_RANK,
+        "sp" => 6,
+        _ => UNKNOWN_QUALIFIER_RANK,
+    }
+}
+
+fn push_version_item(items: &mut Vec<VersionItem>, current: &mut String, is_number: bool) {
+    if current.is_empty() {
+        return;
+    }
+    let text = std::mem::take(current);
+    items.push(if is_number {
+        VersionItem::Number(text.trim_start_matches('0').to_string())
+    } else {
+        VersionItem::Qualifier(text)
+    });
+}
+
+fn pop_trailing_zeros(items: &mut Vec<VersionItem>) {
+    while items.last() == Some(&VersionItem::Number(String::new())) {
+        items.pop();
+    }
+}
+
+/// Split a version into numbers and qualifiers the way Maven's `ComparableVersion` does.
+fn version_items(version: &str) -> Vec<VersionItem> {
+    let mut raw_items = Vec::new();
+    let mut current = String::new();
+    let mut current_is_number = false;
+    for character in version.chars() {
+        if matches!(character, '.' | '-' | '_') {
+            push_version_item(&mut raw_items, &mut current, current_is_number);
+            continue;
+        }
+        let is_number = character.is_ascii_digit();
+        if is_number != current_is_number {
+            push_version_item(&mut raw_items, &mut current, current_is_number);
+        }
+        current_is_number = is_number;
+        current.push(character.to_ascii_lowercase());
+    }
+    push_version_item(&mut raw_items, &mut current, current_is_number);
+
+    // `1.20.0-rc1` and `1.20-rc1` are the same version, and `1.0a1` is `1.0-alpha-1`.
+    let mut items = Vec::with_capacity(raw_items.len());
+    let mut raw_items = raw_items.into_iter().peekable();
+    while let Some(item) = raw_items.next() {
+        let item = match item {
+            VersionItem::Qualifier(qualifier) => {
+                pop_trailing_zeros(&mut items);
+                let followed_by_number = matches!(raw_items.peek(), Some(VersionItem::Number(_)));
+                VersionItem::Qualifier(match qualifier.as_str() {
+                    "a" if followed_by_number => "alpha".to_string(),
+                    "b" if followed_by_number => "beta".to_string(),
+                    "m" if followed_by_number => "milestone".to_string(),
+                    _ => qualifier,
+                })
+            }
+            number => number,
+        };
+        items.push(item);
+    }
+    pop_trailing_zeros(&mut items);
+    items
+}
+
+fn compare_version_items(left: &VersionItem, right: &VersionItem) -> Ordering {
+    match (left, right) {
+        (VersionItem::Number(left), VersionItem::Number(right)) => {
+            left.len().cmp(&right.len()).then_with(|| left.cmp(right))
+        }
+        (VersionItem::Qualifier(left), VersionItem::Qualifier(right)) => {
+            let left_rank = qualifier_rank(left);
+            let right_rank = qualifier_rank(right);
+            left_rank.cmp(&right_rank).then_with(|| {
+                if left_rank == UNKNOWN_QUALIFIER_RANK {
+                    left.cmp(right)
+                } else {
+                    Ordering::Equal
+                }
+            })
+        }
+        (VersionItem::Number(_), VersionItem::Qualifier(_)) => Ordering::Greater,
+        (VersionItem::Qualifier(_), VersionItem::Number(_)) => Ordering::Less,
+    }
+}
+
+/// Compare an item against the implicit zero or release marker of a shorter version.
+fn compare_version_item_to_padding(item: &VersionItem) -> Ordering {
+    match item {
+        VersionItem::Number(number) if number.is_empty() => Ordering::Equal,
+        VersionItem::Number(_) => Ordering::Greater,
+        VersionItem::Qualifier(qualifier) => qualifier_rank(qualifier).cmp(&RELEASE_QUALIFIER_RANK),
+    }
+}
+
+/// Parse a Minecraft weekly snapshot such as `24w14a` into (year, week, letter).
+fn minecraft_snapshot(version: &str) -> Option<(u32, u32, char)> {
+    let (year, rest) = version.split_once('w')?;
+    let letter = rest.chars().last()?;
+    let week = &rest[..rest.len() - letter.len_utf8()];
+    if year.len() != 2 || week.len() != 2 || !letter.is_ascii_lowercase() {
+        return None;
+    }
+    Some((year.parse().ok()?, week.parse().ok()?, letter))
+}
+
+fn strip_build_metadata(version: &str) -> &str {
+    version.split('+').next().unwrap_or(version).trim()
+}
+
+fn is_comparable_version(version: &str) -> bool {
+    !version.is_empty()
+        && version.chars().all(|character| {
+            character.is_ascii_alphanumeric() || matches!(character, '.' | '-' | '_')
+        })
+}
+
+/// Order two concrete versions, ignoring `+build` metadata.
+///
+/// Returns `None` when the versions cannot be ordered, such as a weekly snapshot against a release.
+pub fn compare_versions(left: &str, right: &str) -> Option<Ordering> {
+    let left = strip_build_metadata(left);
+    let right = strip_build_metadata(right);
+    if !is_comparable_version(left) || !is_comparable_version(right) {
+        return None;
+    }
+
+    match (minecraft_snapshot(left), minecraft_snapshot(right)) {
+        (Some(left), Some(right)) => return Some(left.cmp(&right)),
+        (None, None) => {}
+        _ => return None,
+    }
+
+    let left = version_items(left);
+    let right = version_items(right);
+    for index in 0..left.len().max(right.len()) {
+        let ordering = match (left.get(index), right.get(index)) {
+            (Some(left), Some(right)) => compare_version_items(left, right),
+            (Some(left), None) => compare_version_item_to_padding(left),
+            (None, Some(right)) => compare_version_item_to_padding(right).reverse(),
+            (None, None) => Ordering::Equal,
+        };
+        if ordering != Ordering::Equal {
+            return Some(ordering);
+        }
+    }
+    Some(Ordering::Equal)
+}
+
+fn all_of(results: impl IntoIterator<Item = Option<bool>>) -> Option<bool> {
+    let mut combined = Some(true);
+    for result in results {
+        match result {
+            Some(false) => return Some(false),
+            None => combined = None,
+            Some(true) => {}
+        }
+    }
+    combined
+}
+
+fn any_of(results: impl IntoIterator<Item = Option<bool>>) -> Option<bool> {
+    let mut combined = Some(false);
+    for result in results {
+        match result {
+            Some(true) => return Some(true),
+            None => combined = None,
+            Some(false) => {}
+        }
+    }
+    combined
+}
+
+/// Leading dot-separated numbers, e.g. `[1, 21]` for `1.21-pre1`.
+fn numeric_core(version: &str) -> Option<Vec<u64>> {
+    strip_build_metadata(version)
+        .split('-')
+        .next()?
+        .split('.')
+        .map(|component| component.parse().ok())
+        .collect()
+}
+
+fn compare_numeric_cores(left: &[u64], right: &[u64]) -> Ordering {
+    (0..left.len().max(right.len()))
+        .map(|index| {
+            let left = left.get(index).copied().unwrap_or(0);
+            let right = right.get(index).copied().unwrap_or(0);
+            left.cmp(&right)
+        })
+        .find(|ordering| *ordering != Ordering::Equal)
+        .unwrap_or(Ordering::Equal)
+}
+
+/// Check one whitespace-separated term of a Fabric or Quilt predicate.
+fn predicate_term_matches(term: &str, version: &str) -> Option<bool> {
+    if term == "*" || term.eq_ignore_ascii_case("any") {
+        return Some(true);
+    }
+
+    let (operator, operand) = [">=", "<=", ">", "<", "=", "~", "^"]
+        .into_iter()
+        .find_map(|operator| {
+            term.strip_prefix(operator)
+                .map(|operand| (operator, operand))
+        })
+        .unwrap_or(("", term));
+    if operand.is_empty() {
+        return None;
+    }
+
+    let operand_core = strip_build_metadata(operand).split('-').next()?;
+    if let Some(wildcard) = operand_core
+        .split('.')
+        .position(|component| matches!(component, "x" | "X" | "*"))
+    {
+        if !matches!(operator, "" | "=")
+            || operand_core.len() != strip_build_metadata(operand).len()
+        {
+            return None;
+        }
+        let prefix: Vec<u64> = operand_core
+            .split('.')
+            .take(wildcard)
+            .map(|component| component.parse().ok())
+            .collect::<Option<_>>()?;
+        let core = numeric_core(version)?;
+        let core: Vec<u64> = (0..prefix.len())
+            .map(|index| core.get(index).copied().unwrap_or(0))
+            .collect();
+        return Some(core == prefix);
+    }
+
+    let ordering = compare_versions(version, operand)?;
+    match operator {
+        "" | "=" => Some(ordering == Ordering::Equal),
+        ">=" => Some(ordering != Ordering::Less),
+        "<=" => Some(ordering != Ordering::Greater),
+        ">" => Some(ordering == Ordering::Greater),
+        "<" => Some(ordering == Ordering::Less),
+        "~" | "^" => {
+            let operand_core = numeric_core(operand)?;
+            let mut upper = match (operator, operand_core.as_slice()) {
+                ("~", [major, minor, ..]) => vec![*major, *minor],
+                (_, [major, ..]) => vec![*major],
+                _ => return None,
+            };
+            *upper.last_mut()? += 1;
+            // The exclusive upper bound also excludes pre-releases of the next version.
+            let below_upper =
+                compare_numeric_cores(&numeric_core(version)?, &upper) == Ordering::Less;
+            Some(ordering != Ordering::Less && below_upper)
+        }
+        _ => None,
+    }
+}
+
+fn predicate_value_matches(value: &JsonValue, version: &str) -> Option<bool> {
+    match value {
+        JsonValue::String(predicate) => predicate_matches(predicate, version),
+        // Quilt and Fabric accept any one of several predicates.
+        JsonValue::Array(predicates) => any_of(
+            predicates
+                .iter()
+                .map(|predicate| predicate_value_matches(predicate, version)),
+        ),
+        // Quilt constraint objects; `unless` names another mod and cannot be evaluated here.
+        JsonValue::Object(constraint) => match constraint.get("versions") {
+            Some(versions) => predicate_value_matches(versions, version),
+            None => Some(true),
+        },
+        _ => None,
+    }
+}
+
+fn predicate_matches(predicate: &str, version: &str) -> Option<bool> {
+    let predicate = predicate.trim();
+    if predicate.starts_with(['[', '{', '"']) {
+        return predicate_value_matches(&serde_json::from_str(predicate).ok()?, version);
+    }
+    all_of(
+        predicate
+            .split_whitespace()
+            .map(|term| predicate_term_matches(term, version)),
+    )
+}
+
+struct MavenInterval<'a> {
+    lower: Option<&'a str>,
+    lower_inclusive: bool,
+    upper: Option<&'a str>,
+    upper_inclusive: bool,
+}
+
+fn maven_bound(value: &str) -> Option<&str> {
+    Some(value.trim()).filter(|value| !value.is_empty())
+}
+
+fn parse_maven_range(range: &str) -> Option<Vec<MavenInterval<'_>>> {
+    let mut intervals = Vec::new();
+    let mut rest = range.trim();
+    while !rest.is_empty() {
+        let lower_inclusive = match rest.as_bytes()[0] {
+            b'[' => true,
+            b'(' => false,
+            _ => return None,
+        };
+        let end = rest.find([']', ')'])?;
+        let upper_inclusive = rest.as_bytes()[end] == b']';
+        let body = &rest[1..end];
+
+        intervals.push(match body.split_once(',') {
+            Some((lower, upper)) if !upper.contains(',') => MavenInterval {
+                lower: maven_bound(lower),
+                lower_inclusive,
+                upper: maven_bound(upper),
+                upper_inclusive,
+            },
+            // `[1.20.1]` pins an exact version.
+            None if lower_inclusive && upper_inclusive && maven_bound(body).is_some() => {
+                MavenInterval {
+                    lower: maven_bound(body),
+                    lower_inclusive,
+                    upper: maven_bound(body),
+                    upper_inclusive,
+                }
+            }
+            _ => return None,
+        });
+
+        rest = rest[end + 1..].trim_start();
+        if let Some(next) = rest.strip_prefix(',') {
+            rest = next.trim_start();
+            if rest.is_empty() {
+                return None;
+            }
+        } else if !rest.is_empty() {
+            return None;
+        }
+    }
+    Some(intervals)
+}
+
+fn maven_range_matches(range: &str, version: &str) -> Option<bool> {
+    let range = range.trim();
+    if range.is_empty() || range == "*" {
+        return Some(true);
+    }
+    if !range.starts_with(['[', '(']) {
+        // A bare version is only a recommendation in Maven and Forge.
+        return Some(true);
+    }
+
+    let intervals = parse_maven_range(range)?;
+    any_of(intervals.iter().map(|interval| {
+        all_of(
+            [
+                interval.lower.map(|lower| {
+                    compare_versions(version, lower).map(|ordering| {
+                        ordering == Ordering::Greater
+                            || (interval.lower_inclusive && ordering == Ordering::Equal)
+                    })
+                }),
+                interval.upper.map(|upper| {
+                    compare_versions(version, upper).map(|ordering| {
+                        ordering == Ordering::Less
+                            || (interval.upper_inclusive && ordering == Ordering::Equal)
+                    })
+                }),
+            ]
+            .into_iter()
+            .map(|bound| bound.unwrap_or(Some(true))),
+        )
+    }))
+}
+
+/// Check a concrete version against a dependency requirement as stored in mod metadata.
+///
+/// Returns `None` when the requirement cannot be parsed or the versions cannot be ordered.
+pub fn version_requirement_matches(
+    syntax: VersionSyntax,
+    requirement: &str,
+    version: &str,
+) -> Option<bool> {
+    match syntax {
+        VersionSyntax::Predicate => predicate_matches(requirement, version),
+        VersionSyntax::MavenRange => maven_range_matches(requirement, version),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compares_concrete_versions() {
+        let cases = [
+            ("1.20.1", "1.20.1", Some(Ordering::Equal)),
+            ("1.20", "1.20.0", Some(Ordering::Equal)),
+            ("1.20.1", "1.20", Some(Ordering::Greater)),
+            ("1.9", "1.10", Some(Ordering::Less)),
+            ("1.20.10", "1.20.9", Some(Ordering::Greater)),
+            ("0.92.2+1.20.1", "0.92.2+1.20.4", Some(Ordering::Equal)),
+            ("1.0.0-beta.2", "1.0.0-beta.10", Some(Ordering::Less)),
+            ("1.0.0-alpha", "1.0.0-beta", Some(Ordering::Less)),
+            ("1.0.0-rc.1", "1.0.0", Some(Ordering::Less)),
+            ("1.0.0", "1.0.0-sp1", Some(Ordering::Less)),
+            ("1.0a1", "1.0-alpha-1", Some(Ordering::Equal)),
+            ("1.21-pre1", "1.21", Some(Ordering::Less)),
+            ("1.21-pre2", "1.21-rc1", Some(Ordering::Less)),
+            ("1.21-rc1", "1.20.6", Some(Ordering::Greater)),
+            (
+                "1.21-alpha.24.14.a",
+                "1.21-alpha.24.20.a",
+                Some(Ordering::Less),
+            ),
+            ("1.21-alpha.24.14.a", "1.20.6", Some(Ordering::Greater)),
+            ("24w14a", "24w14b", Some(Ordering::Less)),
+            ("23w51b", "24w03a", Some(Ordering::Less)),
+            ("24w14a", "1.20.4", None),
+            ("47.2.0", "47.1.3", Some(Ordering::Greater)),
+            ("14.23.5.2859", "14.23.5.2860", Some(Ordering::Less)),
+            ("20.4.80-beta", "20.4.80", Some(Ordering::Less)),
+            ("1.20.1-47.2.0", "1.20.1-47.10.0", Some(Ordering::Less)),
+            ("${file.jarVersion}", "1.0", None),
+            ("1.0 beta", "1.0", None),
+            ("", "1.0", None),
+        ];
+
+        for (left, right, expected) in cases {
+            assert_eq!(compare_versions(left, right), expected, "{left} vs {right}");
+            assert_eq!(
+                compare_versions(right, left),
+                expected.map(Ordering::reverse),
+                "{right} vs {left}"
+            );
+        }
+    }
+
+    #[test]
+    fn evaluates_fabric_and_quilt_predicates() {
+        let cases = [
+            ("*", "1.20.1", Some(true)),
+            ("", "1.20.1", Some(true)),
+            ("any", "24w14a", Some(true)),
+            ("1.20.1", "1.20.1", Some(true)),
+            ("1.20.1", "1.20.2", Some(false)),
+            ("=1.20.1", "1.20.1+build.3", Some(true)),
+            (">=0.15.0 <0.16", "0.15.11", Some(true)),
+            (">=0.15.0 <0.16", "0.16.0", Some(false)),
+            (">=0.15.0 <0.16", "0.14.24", Some(false)),
+            (">0.15.0", "0.15.0", Some(false)),
+            ("<=1.20.4", "1.20.4", Some(true)),
+            ("~1.20.1", "1.20.4", Some(true)),
+            ("~1.20.1", "1.20", Some(false)),
+            ("~1.20.1", "1.21", Some(false)),
+            ("~1.20.1", "1.21-pre1", Some(false)),
+            ("~1", "1.9", Some(true)),
+            ("~1", "2.0", Some(false)),
+            ("^1.2.3", "1.9.0", Some(true)),
+            ("^1.2.3", "1.2.2", Some(false)),
+            ("^1.2.3", "2.0.0-alpha.1", Some(false)),
+            ("1.20.x", "1.20.6", Some(true)),
+            ("1.20.x", "1.20", Some(true)),
+            ("1.20.x", "1.21", Some(false)),
+            ("1.X", "1.7.10", Some(true)),
+            ("=1.20.*", "1.20.1", Some(true)),
+            (">=1.20.x", "1.20.1", None),
+            (">=1.21-alpha.24.14.a", "1.21", Some(true)),
+            (">=23w51a", "24w14a", Some(true)),
+            (">=1.20.4", "24w14a", None),
+            (">=1.20.4 <1.20", "24w14a", None),
+            ("<1.20 >=1.20.4", "1.20.1", Some(false)),
+            (r#"["1.20.1", "1.20.2"]"#, "1.20.2", Some(true)),
+            (r#"["1.20.1", "1.20.2"]"#, "1.20.3", Some(false)),
+            (
+                r#"{"versions":">=1.20","unless":"sodium"}"#,
+                "1.20.1",
+                Some(true),
+            ),
+            (r#"{"versions":["<1.19", ">=1.21"]}"#, "1.20.1", Some(false)),
+            (">=", "1.20.1", None),
+            ("[1.20,1.21)", "1.20.1", None),
+        ];
+
+        for (predicate, version, expected) in cases {
+            assert_eq!(
+                version_requirement_matches(VersionSyntax::Predicate, predicate, version),
+                expected,
+                "{predicate} against {version}"
+            );
+        }
+    }
+
+    #[test]
+    fn evaluates_maven_ranges() {
+        let cases = [
+            ("[47,)", "47.2.0", Some(true)),
+            ("[47,)", "46.0.14", Some(false)),
+            ("[1.20.1,1.21)", "1.20.1", Some(true)),
+            ("[1.20.1,1.21)", "1.20.6", Some(true)),
+            ("[1.20.1,1.21)", "1.21", Some(false)),
+            ("(1.20.1,1.21]", "1.20.1", Some(false)),
+            ("(1.20.1,1.21]", "1.21.0", Some(true)),
+            ("(,1.20]", "1.16.5", Some(true)),
+            ("[1.20.1]", "1.20.1", Some(true)),
+            ("[1.20.1]", "1.20.2", Some(false)),
+            ("[1.16,1.17),[1.18,)", "1.18.2", Some(true)),
+            ("[1.16,1.17),[1.18,)", "1.17.1", Some(false)),
+            ("[10.13.0.1180,)", "10.13.4.1614", Some(true)),
+            ("[20.4,)", "20.4.80-beta", Some(true)),
+            ("[20.4.80,)", "20.4.80-beta", Some(false)),
+            ("[1.20.1,1.21)", "24w14a", None),
+            ("*", "1.20.1", Some(true)),
+            ("47.2.0", "46.0.0", Some(true)),
+            ("[1.20.1", "1.20.1", None),
+            ("(1.20.1)", "1.20.1", None),
+            ("[1.16,1.17),", "1.16.5", None),
+            ("[1.16,1.17) [1.18,)", "1.16.5", None),
+        ];
+
+        for (range, version, expected) in cases {
+            assert_eq!(
+                version_requirement_matches(VersionSyntax::MavenRange, range, version),
+                expected,
+                "{range} against {version}"
+            );
+        }
+    }
+
+    #[test]
+    fn picks_syntax_from_the_metadata_loader() {
+        assert_eq!(
+            VersionSyntax::for_loader("Fabric"),
+            VersionSyntax::Predicate
+        );
+        assert_eq!(VersionSyntax::for_loader("Quilt"), VersionSyntax::Predicate);
+        assert_eq!(
+            VersionSyntax::for_loader("NeoForge"),
+            VersionSyntax::MavenRange
+        );
+        assert_eq!(
+            VersionSyntax::for_loader("Forge Legacy"),
+            VersionSyntax::MavenRange
+        );
+    }
+}
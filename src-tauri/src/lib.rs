@@ -2,8 +2,26 @@ mod core;
 mod utils;
 
 use core::auth::get_microsoft_auth_code;
-use core::java::{detect_java_runtimes, get_java_version};
-use core::mods::{import_local_mods, scan_local_mods, set_local_mod_enabled};
+use core::curseforge::identify_local_mods_on_curseforge;
+use core::datapacks::{import_world_datapacks, scan_local_worlds, set_world_datapack_enabled};
+use core::instances::get_instance_version_info;
+use core::java::{
+    check_jvm_arguments, detect_java_runtimes, get_java_architecture, get_java_version,
+    get_jvm_argument_presets,
+};
+use core::modpacks::{export_modrinth_pack, import_curseforge_pack, import_modrinth_pack};
+use core::modrinth::identify_local_mods_on_modrinth;
+use core::mods::{
+    analyze_local_mod_dependencies, apply_mod_profile, cancel_local_mod_scan, cancel_mod_bisect,
+    delete_local_mods, delete_mod_profile, disable_other_side_mods, find_duplicate_local_mods,
+    get_local_mod_icon, get_mod_bisect, import_local_mods, install_mod_from_url, list_mod_profiles,
+    list_trashed_local_mods, record_mod_bisect_result, restore_trashed_local_mods,
+    save_mod_profile, scan_local_mods, set_local_mod_enabled, set_local_mods_enabled,
+    start_mod_bisect, unwatch_local_mods, watch_local_mods,
+};
+use core::packs::{
+    get_local_pack_icon, import_local_packs, scan_local_packs, set_local_pack_enabled,
+};
 use core::runner::launch_minecraft;
 use utils::fs::{
     check_files, exists, inspect_files, mkdir, read_dir, read_file, read_text_file, sha1_file,
@@ -22,9 +40,45 @@ pub fn run() {
             get_microsoft_auth_code,
             get_java_version,
             detect_java_runtimes,
+            get_java_architecture,
+            check_jvm_arguments,
+            get_jvm_argument_presets,
             scan_local_mods,
             import_local_mods,
+            install_mod_from_url,
             set_local_mod_enabled,
+            set_local_mods_enabled,
+            disable_other_side_mods,
+            delete_local_mods,
+            list_trashed_local_mods,
+            restore_trashed_local_mods,
+            list_mod_profiles,
+            save_mod_profile,
+            delete_mod_profile,
+            apply_mod_profile,
+            get_mod_bisect,
+            start_mod_bisect,
+            record_mod_bisect_result,
+            cancel_mod_bisect,
+            analyze_local_mod_dependencies,
+            find_duplicate_local_mods,
+            cancel_local_mod_scan,
+            watch_local_mods,
+            unwatch_local_mods,
+            get_local_mod_icon,
+            identify_local_mods_on_modrinth,
+            identify_local_mods_on_curseforge,
+            export_modrinth_pack,
+            import_curseforge_pack,
+            import_modrinth_pack,
+            scan_local_packs,
+            import_local_packs,
+            set_local_pack_enabled,
+            get_local_pack_icon,
+            scan_local_worlds,
+            import_world_datapacks,
+            set_world_datapack_enabled,
+            get_instance_version_info,
             read_text_file,
             write_text_file,
             read_file,
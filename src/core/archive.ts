@@ -0,0 +1,9 @@
+export interface ImportFailure {
+  filename: string;
+  reason: string;
+}
+
+export interface ImportResult {
+  imported: string[];
+  failed: ImportFailure[];
+}
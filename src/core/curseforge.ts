@@ -0,0 +1,14 @@
+import { invoke } from "@tauri-apps/api/core";
+import type { LocalModFile } from "./mods";
+
+export async function identifyLocalModsOnCurseForge(
+  gameDirectory: string,
+  apiKey: string,
+  apiBaseUrl?: string,
+): Promise<LocalModFile[]> {
+  return await invoke("identify_local_mods_on_curseforge", {
+    gameDirectory,
+    apiKey,
+    apiBaseUrl,
+  });
+}
@@ -0,0 +1,43 @@
+import { invoke } from "@tauri-apps/api/core";
+import type { ImportResult } from "./archive";
+import type { LocalPack } from "./packs";
+
+export interface LocalWorld {
+  directory: string;
+  name?: string;
+  tracksDatapacks: boolean;
+  datapacks: LocalPack[];
+  diagnostics?: string[];
+}
+
+export async function scanLocalWorlds(
+  gameDirectory: string,
+): Promise<LocalWorld[]> {
+  return await invoke("scan_local_worlds", { gameDirectory });
+}
+
+export async function importWorldDatapacks(
+  gameDirectory: string,
+  world: string,
+  sourcePaths: string[],
+): Promise<ImportResult> {
+  return await invoke("import_world_datapacks", {
+    gameDirectory,
+    world,
+    sourcePaths,
+  });
+}
+
+export async function setWorldDatapackEnabled(
+  gameDirectory: string,
+  world: string,
+  filename: string,
+  enabled: boolean,
+): Promise<LocalPack> {
+  return await invoke("set_world_datapack_enabled", {
+    gameDirectory,
+    world,
+    filename,
+    enabled,
+  });
+}
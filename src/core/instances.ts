@@ -1,4 +1,5 @@
 import { path } from "@tauri-apps/api";
+import { invoke } from "@tauri-apps/api/core";
 import { exists, readDir, readTextFile } from "../utils/fs";
 
 const versionCollator = new Intl.Collator(undefined, {
@@ -6,6 +7,14 @@ const versionCollator = new Intl.Collator(undefined, {
   sensitivity: "base",
 });
 
+export interface InstanceVersionInfo {
+  versionId: string;
+  gameVersion?: string;
+  loader?: "Fabric" | "Quilt" | "Forge" | "NeoForge";
+  loaderVersion?: string;
+  mainClass?: string;
+}
+
 function isMinecraftVersionJson(value: unknown): boolean {
   return (
     typeof value === "object" &&
@@ -52,3 +61,13 @@ export async function listInstalledMinecraftVersions(
     .filter((version): version is string => version !== null)
     .sort((left, right) => versionCollator.compare(right, left));
 }
+
+export async function getInstanceVersionInfo(
+  gameDirectory: string,
+  versionId: string,
+): Promise<InstanceVersionInfo> {
+  return await invoke("get_instance_version_info", {
+    gameDirectory,
+    versionId,
+  });
+}
@@ -2,10 +2,50 @@ import { invoke } from "@tauri-apps/api/core";
 import { nanoid } from "nanoid";
 import type { JavaRuntime } from "../store/data";
 
+export interface ExecutableArchitecture {
+  architecture: string;
+  bitness: number;
+}
+
+export interface JavaArchitecture {
+  format: string;
+  slices: ExecutableArchitecture[];
+  hostArchitecture: string;
+  matchesHost: boolean;
+  runnable: boolean;
+  warning?: string;
+}
+
 interface DetectedJava {
   pathname: string;
   version: string;
   vendor: string;
+  architecture?: JavaArchitecture;
+}
+
+export interface UnsupportedJvmArgument {
+  argument: string;
+  reason: string;
+}
+
+export interface JvmArgumentCheck {
+  javaVersion?: string;
+  majorVersion?: number;
+  supported: string[];
+  unsupported: UnsupportedJvmArgument[];
+  unchecked: string[];
+  warnings: string[];
+  conflict?: string;
+}
+
+export interface JvmArgumentPreset {
+  id: string;
+  name: string;
+  description: string;
+  minMajorVersion: number;
+  maxMajorVersion?: number;
+  excludedVendors?: string[];
+  arguments: string[];
 }
 
 export async function getJavaVersion(javaPath: string): Promise<string> {
@@ -27,7 +67,12 @@ export async function detectJavas(): Promise<JavaRuntime[]> {
 
   return detected.map((d) => {
     const major = getJavaMajorVersion(d.version);
-    const nickname = major ? `${d.vendor} ${major}` : d.vendor;
+    const name = major ? `${d.vendor} ${major}` : d.vendor;
+    const arch = d.architecture;
+    const nickname =
+      arch && !arch.matchesHost
+        ? `${name} (${arch.slices.map((s) => s.architecture).join("/")})`
+        : name;
     return {
       id: nanoid(),
       nickname,
@@ -36,3 +81,23 @@ export async function detectJavas(): Promise<JavaRuntime[]> {
     };
   });
 }
+
+export async function getJavaArchitecture(
+  javaPath: string,
+): Promise<JavaArchitecture> {
+  return await invoke("get_java_architecture", { javaPath });
+}
+
+export async function checkJvmArguments(
+  javaPath: string,
+  args: string[],
+): Promise<JvmArgumentCheck> {
+  return await invoke("check_jvm_arguments", { javaPath, arguments: args });
+}
+
+export async function getJvmArgumentPresets(
+  majorVersion: number,
+  vendor?: string,
+): Promise<JvmArgumentPreset[]> {
+  return await invoke("get_jvm_argument_presets", { majorVersion, vendor });
+}
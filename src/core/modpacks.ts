@@ -0,0 +1,114 @@
+import { invoke } from "@tauri-apps/api/core";
+import { listen, type UnlistenFn } from "@tauri-apps/api/event";
+import { nanoid } from "nanoid";
+import type { MinecraftInstance } from "../store/data";
+
+export interface ModpackImportProgress {
+  importId?: string;
+  stage: "files" | "overrides" | "version";
+  completed: number;
+  total: number;
+  path?: string;
+}
+
+export interface ModpackImport {
+  name: string;
+  packVersion?: string;
+  versionId: string;
+  gameVersion: string;
+  loader?: "Fabric" | "Quilt" | "Forge" | "NeoForge";
+  loaderVersion?: string;
+  loaderInstalled: boolean;
+  skipped: string[];
+  manualDownloads: ModpackManualDownload[];
+}
+
+export interface ModpackManualDownload {
+  projectId: number;
+  fileId: number;
+  projectName: string;
+  filename: string;
+  path: string;
+  pageUrl: string;
+}
+
+export async function importModrinthPack(
+  packPath: string,
+  gameDirectory: string,
+  importId?: string,
+): Promise<ModpackImport> {
+  return await invoke("import_modrinth_pack", {
+    packPath,
+    gameDirectory,
+    importId,
+  });
+}
+
+export async function importCurseForgePack(
+  packPath: string,
+  gameDirectory: string,
+  apiKey: string,
+  apiBaseUrl?: string,
+  importId?: string,
+): Promise<ModpackImport> {
+  return await invoke("import_curseforge_pack", {
+    packPath,
+    gameDirectory,
+    apiKey,
+    apiBaseUrl,
+    importId,
+  });
+}
+
+export interface ModpackExportOptions {
+  name: string;
+  packVersion?: string;
+  summary?: string;
+  overrideDirectories?: string[];
+  excludeDisabledMods?: boolean;
+  apiBaseUrl?: string;
+}
+
+export interface ModpackExport {
+  files: string[];
+  overrides: string[];
+}
+
+export async function exportModrinthPack(
+  gameDirectory: string,
+  versionId: string,
+  destination: string,
+  options: ModpackExportOptions,
+): Promise<ModpackExport> {
+  return await invoke("export_modrinth_pack", {
+    gameDirectory,
+    versionId,
+    destination,
+    options,
+  });
+}
+
+export async function listenModpackImportProgress(
+  handler: (progress: ModpackImportProgress) => void,
+): Promise<UnlistenFn> {
+  return await listen<ModpackImportProgress>(
+    "modpack-import-progress",
+    (event) => {
+      handler(event.payload);
+    },
+  );
+}
+
+export function createModpackInstance(
+  imported: ModpackImport,
+  gameDirectory: string,
+): MinecraftInstance {
+  return {
+    id: nanoid(),
+    name: imported.name,
+    directory: gameDirectory,
+    version: imported.versionId,
+    timestamp: Date.now(),
+    checked: false,
+  };
+}
@@ -0,0 +1,29 @@
+import { invoke } from "@tauri-apps/api/core";
+import type { LocalModFile } from "./mods";
+
+export interface ModrinthModUpdate {
+  filename: string;
+  projectId: string;
+  currentVersionId: string;
+  versionId: string;
+  versionNumber: string;
+  downloadUrl?: string;
+  downloadFilename?: string;
+}
+
+export interface ModrinthIdentification {
+  files: LocalModFile[];
+  updates: ModrinthModUpdate[];
+}
+
+export async function identifyLocalModsOnModrinth(
+  gameDirectory: string,
+  versionId: string,
+  apiBaseUrl?: string,
+): Promise<ModrinthIdentification> {
+  return await invoke("identify_local_mods_on_modrinth", {
+    gameDirectory,
+    versionId,
+    apiBaseUrl,
+  });
+}
@@ -1,4 +1,7 @@
 import { invoke } from "@tauri-apps/api/core";
+import { listen, type UnlistenFn } from "@tauri-apps/api/event";
+import { readFile } from "../utils/fs";
+import type { InstanceVersionInfo } from "./instances";
 
 export interface LocalModDependency {
   relation: string;
@@ -22,11 +25,33 @@ export interface LocalModMetadataEntry {
   gameVersion?: string;
   environment?: string;
   dependencies: LocalModDependency[];
+  provides: string[];
 }
 
 export interface LocalModMetadata {
   entries: LocalModMetadataEntry[];
   diagnostics: string[];
+  nested: LocalNestedMod[];
+}
+
+export interface LocalNestedMod {
+  path: string;
+  size: number;
+  metadata: LocalModMetadata;
+}
+
+export interface LocalModModrinthInfo {
+  projectId: string;
+  versionId: string;
+  projectTitle: string;
+  pageUrl: string;
+}
+
+export interface LocalModCurseForgeInfo {
+  projectId: number;
+  fileId: number;
+  projectName: string;
+  pageUrl: string;
 }
 
 export interface LocalModFile {
@@ -34,6 +59,30 @@ export interface LocalModFile {
   enabled: boolean;
   size: number;
   metadata: LocalModMetadata;
+  curseforgeFingerprint?: number;
+  modrinth?: LocalModModrinthInfo;
+  curseforge?: LocalModCurseForgeInfo;
+  compatibility?: LocalModCompatibility;
+  sideWarnings?: LocalModSideWarning[];
+}
+
+export type InstanceSide = "client" | "server";
+
+export interface LocalModSideWarning {
+  kind: "otherSide" | "otherSideDependency";
+  side: InstanceSide;
+  requiredBy?: string[];
+}
+
+export interface LocalModCompatibilityIssue {
+  kind: "loader" | "gameVersion" | "loaderVersion";
+  required: string;
+  found?: string;
+}
+
+export interface LocalModCompatibility {
+  verdict: "compatible" | "incompatible" | "unknown";
+  issues: LocalModCompatibilityIssue[];
 }
 
 export interface LocalModImportFailure {
@@ -46,10 +95,144 @@ export interface LocalModImportResult {
   failed: LocalModImportFailure[];
 }
 
+export interface TrashedLocalMod {
+  id: string;
+  filename: string;
+  size: number;
+  deletedAtMs: number;
+}
+
+export interface LocalModProfile {
+  name: string;
+  mods: string[];
+}
+
+export interface LocalModProfileApplyResult {
+  enabled: string[];
+  disabled: string[];
+  missing: string[];
+}
+
+export interface LocalModOperationFailure {
+  filename: string;
+  reason: string;
+}
+
+export interface LocalModDeleteResult {
+  deleted: TrashedLocalMod[];
+  failed: LocalModOperationFailure[];
+}
+
+export interface LocalModStateBatchResult {
+  changed: LocalModRename[];
+  unchanged: string[];
+  failed: LocalModOperationFailure[];
+}
+
+export interface LocalModRestoreResult {
+  restored: string[];
+  failed: LocalModOperationFailure[];
+}
+
+export interface LocalModDependencyProblem {
+  kind: "missing" | "versionMismatch" | "breaks" | "conflicts";
+  severity: "error" | "warning";
+  modId: string;
+  filename: string;
+  dependencyId: string;
+  versionRequirement?: string;
+  foundVersions: string[];
+  dependencyFilenames: string[];
+}
+
+export interface LocalModProvidedDependency {
+  modId: string;
+  filename: string;
+  dependencyId: string;
+  providerModId: string;
+  providerFilename: string;
+}
+
+export interface LocalModDependencyReport {
+  version: InstanceVersionInfo;
+  problems: LocalModDependencyProblem[];
+  provided: LocalModProvidedDependency[];
+}
+
+export interface LocalModDuplicateProvider {
+  filename: string;
+  loader: string;
+  version?: string;
+}
+
+export interface LocalModDuplicateGroup {
+  modId: string;
+  providers: LocalModDuplicateProvider[];
+  suggestedKeep?: string;
+}
+
+export interface LocalModScanProgress {
+  scanId?: string;
+  parsed: number;
+  total: number;
+  filename: string;
+}
+
 export async function scanLocalMods(
   gameDirectory: string,
+  scanId?: string,
+  versionId?: string,
+  side?: InstanceSide,
 ): Promise<LocalModFile[]> {
-  return await invoke("scan_local_mods", { gameDirectory });
+  return await invoke("scan_local_mods", {
+    gameDirectory,
+    scanId,
+    versionId,
+    side,
+  });
+}
+
+export async function cancelLocalModScan(scanId: string): Promise<boolean> {
+  return await invoke("cancel_local_mod_scan", { scanId });
+}
+
+export async function listenLocalModScanProgress(
+  handler: (progress: LocalModScanProgress) => void,
+): Promise<UnlistenFn> {
+  return await listen<LocalModScanProgress>("mod-scan-progress", (event) => {
+    handler(event.payload);
+  });
+}
+
+export interface LocalModRename {
+  from: string;
+  file: LocalModFile;
+}
+
+export interface LocalModDirectoryChange {
+  watchId: string;
+  added: LocalModFile[];
+  removed: string[];
+  renamed: LocalModRename[];
+}
+
+export async function watchLocalMods(gameDirectory: string): Promise<string> {
+  return await invoke("watch_local_mods", { gameDirectory });
+}
+
+export async function unwatchLocalMods(watchId: string): Promise<boolean> {
+  return await invoke("unwatch_local_mods", { watchId });
+}
+
+export async function listenLocalModDirectoryChanges(
+  handler: (change: LocalModDirectoryChange) => void,
+): Promise<UnlistenFn> {
+  return await listen<LocalModDirectoryChange>(
+    "mod-directory-change",
+    (event) => {
+      handler(event.payload);
+    },
+  );
 }
 
 export async function importLocalMods(
@@ -59,6 +242,26 @@ export async function importLocalMods(
   return await invoke("import_local_mods", { gameDirectory, sourcePaths });
 }
 
+export interface LocalModDownload {
+  url: string;
+  filename: string;
+  sha1?: string;
+  sha512?: string;
+  replaceOlder?: boolean;
+}
+
+export interface LocalModInstallResult {
+  installed: LocalModFile;
+  replaced: TrashedLocalMod[];
+}
+
+export async function installModFromUrl(
+  gameDirectory: string,
+  download: LocalModDownload,
+): Promise<LocalModInstallResult> {
+  return await invoke("install_mod_from_url", { gameDirectory, download });
+}
+
 export async function setLocalModEnabled(
   gameDirectory: string,
   filename: string,
@@ -70,3 +273,141 @@ export async function setLocalModEnabled(
     enabled,
   });
 }
+
+export async function setLocalModsEnabled(
+  gameDirectory: string,
+  filenames: string[],
+  enabled: boolean,
+): Promise<LocalModStateBatchResult> {
+  return await invoke("set_local_mods_enabled", {
+    gameDirectory,
+    filenames,
+    enabled,
+  });
+}
+
+export async function disableOtherSideMods(
+  gameDirectory: string,
+  side: InstanceSide,
+): Promise<LocalModStateBatchResult> {
+  return await invoke("disable_other_side_mods", { gameDirectory, side });
+}
+
+export async function deleteLocalMods(
+  gameDirectory: string,
+  filenames: string[],
+): Promise<LocalModDeleteResult> {
+  return await invoke("delete_local_mods", { gameDirectory, filenames });
+}
+
+export async function listTrashedLocalMods(
+  gameDirectory: string,
+): Promise<TrashedLocalMod[]> {
+  return await invoke("list_trashed_local_mods", { gameDirectory });
+}
+
+export async function restoreTrashedLocalMods(
+  gameDirectory: string,
+  ids: string[],
+): Promise<LocalModRestoreResult> {
+  return await invoke("restore_trashed_local_mods", { gameDirectory, ids });
+}
+
+export async function listModProfiles(
+  gameDirectory: string,
+): Promise<LocalModProfile[]> {
+  return await invoke("list_mod_profiles", { gameDirectory });
+}
+
+export async function saveModProfile(
+  gameDirectory: string,
+  name: string,
+  mods?: string[],
+): Promise<LocalModProfile> {
+  return await invoke("save_mod_profile", { gameDirectory, name, mods });
+}
+
+export async function deleteModProfile(
+  gameDirectory: string,
+  name: string,
+): Promise<boolean> {
+  return await invoke("delete_mod_profile", { gameDirectory, name });
+}
+
+export async function applyModProfile(
+  gameDirectory: string,
+  name: string,
+): Promise<LocalModProfileApplyResult> {
+  return await invoke("apply_mod_profile", { gameDirectory, name });
+}
+
+export interface LocalModBisectSession {
+  originalEnabled: string[];
+  suspects: string[];
+  testing: string[];
+  keptForDependencies: string[];
+  round: number;
+  culprit?: string;
+  finished: boolean;
+}
+
+export async function getModBisect(
+  gameDirectory: string,
+): Promise<LocalModBisectSession | null> {
+  return await invoke("get_mod_bisect", { gameDirectory });
+}
+
+export async function startModBisect(
+  gameDirectory: string,
+  suspects?: string[],
+): Promise<LocalModBisectSession> {
+  return await invoke("start_mod_bisect", { gameDirectory, suspects });
+}
+
+export async function recordModBisectResult(
+  gameDirectory: string,
+  works: boolean,
+): Promise<LocalModBisectSession> {
+  return await invoke("record_mod_bisect_result", { gameDirectory, works });
+}
+
+export async function cancelModBisect(gameDirectory: string): Promise<boolean> {
+  return await invoke("cancel_mod_bisect", { gameDirectory });
+}
+
+export async function analyzeLocalModDependencies(
+  gameDirectory: string,
+  versionId: string,
+): Promise<LocalModDependencyReport> {
+  return await invoke("analyze_local_mod_dependencies", {
+    gameDirectory,
+    versionId,
+  });
+}
+
+export async function findDuplicateLocalMods(
+  gameDirectory: string,
+): Promise<LocalModDuplicateGroup[]> {
+  return await invoke("find_duplicate_local_mods", { gameDirectory });
+}
+
+export async function getLocalModIcon(
+  gameDirectory: string,
+  filename: string,
+  size?: number,
+): Promise<string | null> {
+  return await invoke("get_local_mod_icon", { gameDirectory, filename, size });
+}
+
+export async function loadLocalModIconUrl(
+  gameDirectory: string,
+  filename: string,
+  size?: number,
+): Promise<string | null> {
+  const iconPath = await getLocalModIcon(gameDirectory, filename, size);
+  if (!iconPath) {
+    return null;
+  }
+  const bytes = await readFile(iconPath);
+  return URL.createObjectURL(new Blob([bytes], { type: "image/png" }));
+}
@@ -0,0 +1,63 @@
+import { invoke } from "@tauri-apps/api/core";
+import type { ImportResult } from "./archive";
+
+export type LocalPackKind = "resourcePack" | "shaderPack";
+
+export interface PackFormatRange {
+  min: number;
+  max: number;
+}
+
+export interface LocalPack {
+  filename: string;
+  directory: boolean;
+  enabled: boolean;
+  size: number;
+  packFormat?: number;
+  supportedFormats?: PackFormatRange;
+  description?: string;
+  hasIcon: boolean;
+  propertiesFiles?: string[];
+  diagnostics?: string[];
+}
+
+export async function scanLocalPacks(
+  gameDirectory: string,
+  kind: LocalPackKind,
+): Promise<LocalPack[]> {
+  return await invoke("scan_local_packs", { gameDirectory, kind });
+}
+
+export async function importLocalPacks(
+  gameDirectory: string,
+  kind: LocalPackKind,
+  sourcePaths: string[],
+): Promise<ImportResult> {
+  return await invoke("import_local_packs", {
+    gameDirectory,
+    kind,
+    sourcePaths,
+  });
+}
+
+export async function setLocalPackEnabled(
+  gameDirectory: string,
+  kind: LocalPackKind,
+  filename: string,
+  enabled: boolean,
+): Promise<LocalPack> {
+  return await invoke("set_local_pack_enabled", {
+    gameDirectory,
+    kind,
+    filename,
+    enabled,
+  });
+}
+
+export async function getLocalPackIcon(
+  gameDirectory: string,
+  kind: LocalPackKind,
+  filename: string,
+): Promise<string | null> {
+  return await invoke("get_local_pack_icon", { gameDirectory, kind, filename });
+}
@@ -23,12 +23,18 @@ import Checkbox from "../components/Checkbox";
 import Input from "../components/Input";
 import Spin from "../components/Spin";
 import {
+  cancelLocalModScan,
   importLocalMods,
   type LocalModDependency,
   type LocalModFile,
   type LocalModMetadataEntry,
+  type LocalModScanProgress,
+  listenLocalModDirectoryChanges,
+  listenLocalModScanProgress,
   scanLocalMods,
   setLocalModEnabled,
+  unwatchLocalMods,
+  watchLocalMods,
 } from "../core/mods";
 import { AppContext } from "../store";
 import type { MinecraftInstance } from "../store/data";
@@ -121,6 +127,9 @@ function modSummaryParts(mod: LocalModFile): string[] {
   } else if (!primaryEntry) {
     parts.push("No supported metadata");
   }
+  if (mod.compatibility?.verdict === "incompatible") {
+    parts.push("Incompatible with this instance");
+  }
 
   return parts;
 }
@@ -436,10 +445,13 @@ export default function InstanceModsView(props: {
   const [pendingFilenames, setPendingFilenames] = useState<Set<string>>(
     () => new Set(),
   );
+  const [scanProgress, setScanProgress] = useState<LocalModScanProgress>();
   const scanRequestId = useRef(0);
+  const scanIdPrefix = useId();
   const mounted = useRef(true);
 
   const gameDirectory = props.instance.directory;
+  const versionId = props.instance.version;
   const sharedInstanceCount = app
     .getData()
     .instances.filter(
@@ -450,12 +462,19 @@ export default function InstanceModsView(props: {
     async (showErrorDialog = true): Promise<ModRefreshResult> => {
       if (!mounted.current) return { success: false };
 
+      // A newer scan supersedes the previous one, so stop parsing for it.
+      void cancelLocalModScan(`${scanIdPrefix}-${scanRequestId.current}`);
       const requestId = ++scanRequestId.current;
       setLoading(true);
       setLoadError(undefined);
+      setScanProgress(undefined);
 
       try {
-        const scannedMods = await scanLocalMods(gameDirectory);
+        const scannedMods = await scanLocalMods(
+          gameDirectory,
+          `${scanIdPrefix}-${requestId}`,
+          versionId,
+        );
         if (!mounted.current || requestId !== scanRequestId.current) {
           return { success: false };
         }
@@ -485,9 +504,27 @@ export default function InstanceModsView(props: {
         }
       }
     },
-    [app.openDialog, gameDirectory],
+    [app.openDialog, gameDirectory, scanIdPrefix, versionId],
   );
 
+  useEffect(() => {
+    let disposed = false;
+    let unlisten: (() => void) | undefined;
+    void listenLocalModScanProgress((progress) => {
+      if (progress.scanId === `${scanIdPrefix}-${scanRequestId.current}`) {
+        setScanProgress(progress);
+      }
+    }).then((stopListening) => {
+      if (disposed) stopListening();
+      else unlisten = stopListening;
+    });
+
+    return () => {
+      disposed = true;
+      unlisten?.();
+    };
+  }, [scanIdPrefix]);
+
   useEffect(() => {
     mounted.current = true;
     setMods([]);
@@ -497,9 +534,43 @@ export default function InstanceModsView(props: {
 
     return () => {
       mounted.current = false;
+      void cancelLocalModScan(`${scanIdPrefix}-${scanRequestId.current}`);
       scanRequestId.current += 1;
     };
-  }, [refreshMods]);
+  }, [refreshMods, scanIdPrefix]);
+
+  useEffect(() => {
+    let disposed = false;
+    let watchId: string | undefined;
+    let unlisten: (() => void) | undefined;
+
+    const subscribe = async () => {
+      const stopListening = await listenLocalModDirectoryChanges((change) => {
+        // Compatibility and side warnings depend on the whole set, so rescan.
+        if (change.watchId === watchId) void refreshMods(false);
+      });
+      if (disposed) {
+        stopListening();
+        return;
+      }
+      unlisten = stopListening;
+
+      const id = await watchLocalMods(gameDirectory);
+      if (disposed) {
+        void unwatchLocalMods(id);
+        return;
+      }
+      watchId = id;
+    };
+    // Watching is best-effort; Refresh still picks up outside changes.
+    subscribe().catch(() => {});
+
+    return () => {
+      disposed = true;
+      unlisten?.();
+      if (watchId) void unwatchLocalMods(watchId);
+    };
+  }, [gameDirectory, refreshMods]);
 
   const searchableMods = useMemo(
     () =>
@@ -693,7 +764,11 @@ export default function InstanceModsView(props: {
         <Center className="py-12">
           <div className="flex items-center">
             <Spin />
-            <span>Loading mods</span>
+            <span>
+              Loading mods
+              {scanProgress &&
+                ` (${scanProgress.parsed}/${scanProgress.total})`}
+            </span>
           </div>
         </Center>
       ) : loadError && mods.length === 0 ? (